@@ -0,0 +1,194 @@
+use crate::finance::api::detail::{AccountInput, AccountKind, AccountSavingSchema, AccountSchema, StatementSchema};
+use dioxus::prelude::*;
+
+#[component]
+pub fn AccountSelect(accounts: Signal<Vec<AccountSchema>>, value: u64, handle_change: Callback<u64>) -> Element {
+    rsx! {
+        select {
+            onchange: move |e| {
+                if let Ok(id) = e.value().parse() {
+                    handle_change(id);
+                }
+            },
+            for account in accounts() {
+                option {
+                    value: "{account.id}",
+                    selected: value == account.id,
+                    "{account.name}"
+                }
+            }
+        }
+    }
+}
+
+// 口座ごとの残高と実際の残高による調整
+#[component]
+pub fn AccountList(
+    accounts: Signal<Vec<AccountSchema>>,
+    default_account_id: u64,
+    savings: Signal<Vec<AccountSavingSchema>>,
+    handle_add: Callback<AccountInput>,
+    handle_create_adjustment: Callback<(u64, String)>,
+    error: Signal<Option<String>>,
+    adjustment_error: Signal<Option<String>>,
+) -> Element {
+    let mut name = use_signal(|| "".to_string());
+    let mut kind = use_signal(|| AccountKind::Bank);
+    let mut closing_day = use_signal(|| "".to_string());
+    let mut payment_day = use_signal(|| "".to_string());
+    let mut payment_account_id = use_signal(|| default_account_id);
+
+    let handle_add = move |_| {
+        handle_add(AccountInput {
+            name: name(),
+            kind: kind(),
+            closing_day: closing_day(),
+            payment_day: payment_day(),
+            payment_account_id: payment_account_id(),
+        });
+        if error().is_none() {
+            name.set("".to_string());
+            closing_day.set("".to_string());
+            payment_day.set("".to_string());
+        }
+    };
+
+    rsx! {
+        div {
+            id: "account-list",
+            h3 { "口座" }
+            table {
+                thead {
+                    tr {
+                        th { "名前" }
+                        th { "種類" }
+                        th { "先月の貯金" }
+                        th { "今月の貯金" }
+                        th { "" }
+                    }
+                }
+                tbody {
+                    for saving in savings() {
+                        tr {
+                            td { "{saving.name}" }
+                            td {
+                                "{saving.kind.label()}"
+                                if let Some(card_cycle) = saving.card_cycle {
+                                    span { class: "card-cycle", " ({card_cycle})" }
+                                }
+                            }
+                            td { class: "amount", "{saving.last_month}" }
+                            td { class: "amount", "{saving.current_month}" }
+                            td {
+                                input {
+                                    type: "number",
+                                    placeholder: "貯蓄手動入力",
+                                    onchange: move |e| handle_create_adjustment((saving.account_id, e.value())),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(message) = adjustment_error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+            div {
+                class: "account-add",
+                input {
+                    type: "text",
+                    placeholder: "口座名",
+                    value: "{name}",
+                    onchange: move |e| name.set(e.value()),
+                }
+                select {
+                    onchange: move |e| {
+                        if let Some(selected) = AccountKind::ALL.into_iter().find(|k| k.label() == e.value()) {
+                            kind.set(selected);
+                        }
+                    },
+                    for k in AccountKind::ALL {
+                        option {
+                            value: k.label(),
+                            selected: kind() == k,
+                            "{k.label()}"
+                        }
+                    }
+                }
+                if kind() == AccountKind::CreditCard {
+                    input {
+                        type: "number",
+                        min: "1",
+                        max: "31",
+                        placeholder: "締め日",
+                        value: "{closing_day}",
+                        onchange: move |e| closing_day.set(e.value()),
+                    }
+                    input {
+                        type: "number",
+                        min: "1",
+                        max: "31",
+                        placeholder: "引き落とし日",
+                        value: "{payment_day}",
+                        onchange: move |e| payment_day.set(e.value()),
+                    }
+                    label { "支払い口座" }
+                    AccountSelect {
+                        accounts,
+                        value: payment_account_id(),
+                        handle_change: move |id| payment_account_id.set(id),
+                    }
+                }
+                button {
+                    onclick: handle_add,
+                    "口座を追加"
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
+
+// その月に締めるクレジットカードの請求
+#[component]
+pub fn StatementList(statements: Signal<Vec<StatementSchema>>) -> Element {
+    rsx! {
+        if !statements().is_empty() {
+            div {
+                id: "statement-list",
+                h3 { "カードの請求" }
+                table {
+                    thead {
+                        tr {
+                            th { "カード" }
+                            th { "利用期間" }
+                            th { "引き落とし日" }
+                            th { "支払い口座" }
+                            th { "金額" }
+                        }
+                    }
+                    tbody {
+                        for statement in statements() {
+                            tr {
+                                id: "statement-{statement.account_id}",
+                                td { "{statement.account}" }
+                                td { "{statement.opening_date} 〜 {statement.closing_date}" }
+                                td { "{statement.payment_date}" }
+                                td { "{statement.payment_account}" }
+                                td { class: "amount", "{statement.amount}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use super::category::CategorySelect;
+use crate::finance::api::detail::{BudgetStatusSchema, BudgetTarget, CategorySchema};
+use dioxus::prelude::*;
+use rust_decimal::Decimal;
+
+#[component]
+pub fn BudgetList(
+    budgets: Signal<Vec<BudgetStatusSchema>>,
+    categories: Signal<Vec<CategorySchema>>,
+    handle_add: Callback<(BudgetTarget, String, bool)>,
+    handle_delete: Callback<u64>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut open_budget_add = use_signal(|| false);
+
+    let handle_add = move |(target, amount, rollover)| {
+        handle_add((target, amount, rollover));
+        if error().is_none() {
+            open_budget_add.set(false);
+        }
+    };
+
+    let handle_cancel = move |_| {
+        error.set(None);
+        open_budget_add.set(false);
+    };
+
+    rsx! {
+        div {
+            id: "budget-list",
+            h3 { "予算" }
+            table {
+                thead {
+                    tr {
+                        th { "対象" }
+                        th { "予算" }
+                        th { "実績" }
+                        th { "残り" }
+                        th { "" }
+                    }
+                }
+                tbody {
+                    for budget in budgets() {
+                        tr {
+                            id: "budget-{budget.id}",
+                            td {
+                                "{budget.label}"
+                                if budget.rollover {
+                                    span { class: "budget-rollover", " (繰越)" }
+                                }
+                            }
+                            td { class: "amount", "{budget.limit}" }
+                            td { class: "amount", "{budget.actual}" }
+                            td {
+                                class: "amount",
+                                style: if budget.remaining < Decimal::ZERO { "color: red;" } else { "color: lightgreen;" },
+                                "{budget.remaining}"
+                            }
+                            td {
+                                button {
+                                    onclick: move |_| handle_delete(budget.id),
+                                    "削除"
+                                }
+                            }
+                        }
+                        if let Some(warning) = budget.warning {
+                            tr {
+                                class: "budget-warning",
+                                td { colspan: "5", "{warning}" }
+                            }
+                        }
+                    }
+                    if open_budget_add() {
+                        BudgetAdd { categories, handle_add, handle_cancel }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+            if !open_budget_add() {
+                div {
+                    class: "budget-add",
+                    button {
+                        onclick: move |_| open_budget_add.set(true),
+                        "予算を追加"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn BudgetAdd(
+    categories: Signal<Vec<CategorySchema>>,
+    handle_add: Callback<(BudgetTarget, String, bool)>,
+    handle_cancel: EventHandler<MouseEvent>,
+) -> Element {
+    // overall, name, category
+    let mut target_kind = use_signal(|| "overall".to_string());
+    let mut name = use_signal(|| "".to_string());
+    let mut category_id = use_signal(|| None::<u64>);
+    let mut amount = use_signal(|| "".to_string());
+    let mut rollover = use_signal(|| false);
+
+    let handle_add = move |_| {
+        let target = match (target_kind().as_str(), category_id()) {
+            ("name", _) => BudgetTarget::OutcomeName(name()),
+            ("category", Some(id)) => BudgetTarget::Category(id),
+            _ => BudgetTarget::Overall,
+        };
+        handle_add((target, amount(), rollover()));
+    };
+
+    rsx! {
+        tr {
+            td {
+                select {
+                    onchange: move |e| target_kind.set(e.value()),
+                    option { value: "overall", "全体" }
+                    option { value: "name", "支出名" }
+                    option { value: "category", "カテゴリ" }
+                }
+                if target_kind() == "name" {
+                    input {
+                        type: "text",
+                        placeholder: "支出名",
+                        value: "{name}",
+                        onchange: move |e| name.set(e.value()),
+                    }
+                }
+                if target_kind() == "category" {
+                    CategorySelect {
+                        categories,
+                        value: category_id(),
+                        none_label: "カテゴリを選択",
+                        handle_change: move |id| category_id.set(id),
+                    }
+                }
+            }
+            td {
+                input {
+                    type: "text",
+                    placeholder: "金額",
+                    value: "{amount}",
+                    onchange: move |e| amount.set(e.value()),
+                }
+            }
+            td {
+                label {
+                    input {
+                        type: "checkbox",
+                        checked: rollover(),
+                        onchange: move |e| rollover.set(e.checked()),
+                    }
+                    "繰越"
+                }
+            }
+            td {}
+            td {
+                button {
+                    disabled: target_kind() == "category" && category_id().is_none(),
+                    onclick: handle_add,
+                    "追加"
+                }
+                button {
+                    onclick: handle_cancel,
+                    "キャンセル"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::finance::api::detail::{AccountSchema, CategorySchema, LedgerFilter};
+use dioxus::prelude::*;
+
+#[component]
+pub fn CategorySelect(
+    categories: Signal<Vec<CategorySchema>>,
+    value: Option<u64>,
+    none_label: String,
+    handle_change: Callback<Option<u64>>,
+) -> Element {
+    rsx! {
+        select {
+            onchange: move |e| handle_change(e.value().parse().ok()),
+            option {
+                value: "",
+                selected: value.is_none(),
+                "{none_label}"
+            }
+            for category in categories() {
+                option {
+                    value: "{category.id}",
+                    selected: value == Some(category.id),
+                    "{category.path}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn LedgerFilterBar(
+    accounts: Signal<Vec<AccountSchema>>,
+    categories: Signal<Vec<CategorySchema>>,
+    filter: Signal<LedgerFilter>,
+) -> Element {
+    rsx! {
+        div {
+            id: "ledger-filter",
+            label { "口座" }
+            select {
+                onchange: move |e| filter.write().account_id = e.value().parse().ok(),
+                option {
+                    value: "",
+                    selected: filter().account_id.is_none(),
+                    "すべて"
+                }
+                for account in accounts() {
+                    option {
+                        value: "{account.id}",
+                        selected: filter().account_id == Some(account.id),
+                        "{account.name}"
+                    }
+                }
+            }
+            label { "カテゴリ" }
+            CategorySelect {
+                categories,
+                value: filter().category_id,
+                none_label: "すべて",
+                handle_change: move |category_id| filter.write().category_id = category_id,
+            }
+            label { "タグ" }
+            input {
+                type: "text",
+                placeholder: "タグ",
+                value: filter().tag.unwrap_or_default(),
+                onchange: move |e| {
+                    let tag = e.value().trim().to_string();
+                    filter.write().tag = if tag.is_empty() { None } else { Some(tag) };
+                },
+            }
+        }
+    }
+}
+
+#[component]
+pub fn CategoryList(
+    categories: Signal<Vec<CategorySchema>>,
+    handle_add: Callback<(String, Option<u64>)>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut name = use_signal(|| "".to_string());
+    let mut parent_id = use_signal(|| None::<u64>);
+
+    let handle_add = move |_| {
+        handle_add((name(), parent_id()));
+        if error().is_none() {
+            name.set("".to_string());
+            parent_id.set(None);
+        }
+    };
+
+    rsx! {
+        div {
+            id: "category-list",
+            h2 { "カテゴリ" }
+            ul {
+                for category in categories() {
+                    li { "{category.path}" }
+                }
+            }
+            div {
+                class: "category-add",
+                input {
+                    type: "text",
+                    placeholder: "カテゴリ名",
+                    value: "{name}",
+                    onchange: move |e| name.set(e.value()),
+                }
+                CategorySelect {
+                    categories,
+                    value: parent_id(),
+                    none_label: "親カテゴリなし",
+                    handle_change: move |id| parent_id.set(id),
+                }
+                button {
+                    onclick: handle_add,
+                    "カテゴリを追加"
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
@@ -1,32 +1,45 @@
-use crate::finance::api::detail::IncomeSchema;
+use super::account::AccountSelect;
+use super::category::CategorySelect;
+use crate::finance::api::detail::{AccountSchema, CategorySchema, LedgerEntryInput, IncomeSchema};
 use dioxus::prelude::*;
 
 #[component]
 pub fn IncomeList(
     incomes: Signal<Vec<IncomeSchema>>,
-    handle_add: Callback<(String, String, String)>,
-    handle_edit: Callback<(u64, String, String, String)>,
+    accounts: Signal<Vec<AccountSchema>>,
+    default_account_id: u64,
+    categories: Signal<Vec<CategorySchema>>,
+    handle_add: Callback<LedgerEntryInput>,
+    handle_edit: Callback<(u64, LedgerEntryInput)>,
     handle_delete: Callback<u64>,
+    error: Signal<Option<String>>,
 ) -> Element {
     let mut open_income_add = use_signal(|| false);
     let mut open_income_edit = use_signal(|| false);
     let mut opening_income_id = use_signal(|| 0_u64);
 
-    let handle_add = move |(name, amount, date)| {
-        handle_add((name, amount, date));
-        open_income_add.set(false);
+    let handle_add = move |input| {
+        handle_add(input);
+        // 入力が受け付けられなかった場合はフォームを開いたままにする
+        if error().is_none() {
+            open_income_add.set(false);
+        }
     };
 
     let handle_add_cancel = move |_| {
+        error.set(None);
         open_income_add.set(false);
     };
 
-    let handle_edit = move |(id, name, amount, date)| {
-        handle_edit((id, name, amount, date));
-        open_income_edit.set(false);
+    let handle_edit = move |(id, input)| {
+        handle_edit((id, input));
+        if error().is_none() {
+            open_income_edit.set(false);
+        }
     };
 
     let handle_edit_cancel = move |_| {
+        error.set(None);
         open_income_edit.set(false);
     };
 
@@ -44,17 +57,35 @@ pub fn IncomeList(
                     tr {
                         th { "名前" }
                         th { "日付" }
+                        th { "口座" }
+                        th { "カテゴリ" }
+                        th { "タグ" }
                         th { "金額" }
                     }
                 }
                 tbody {
                     for income in incomes() {
                         if open_income_edit() && opening_income_id() == income.id {
-                            IncomeEdit { id: income.id, name: income.name, amount: income.amount.to_string(), date: income.date, handle_edit, handle_cancel: handle_edit_cancel }
+                            IncomeEdit {
+                                id: income.id,
+                                name: income.name,
+                                amount: income.amount.to_string(),
+                                date: income.date,
+                                account_id: income.account_id,
+                                category_id: income.category_id,
+                                tags: income.tags.join(", "),
+                                accounts,
+                                categories,
+                                handle_edit,
+                                handle_cancel: handle_edit_cancel,
+                            }
                         } else {
                             tr {
                                 td { "{income.name}" }
                                 td { "{income.date}" }
+                                td { "{income.account}" }
+                                td { "{income.category}" }
+                                td { {income.tags.join(", ")} }
                                 td {
                                     class: "amount",
                                     "{income.amount}"
@@ -73,10 +104,16 @@ pub fn IncomeList(
                         }
                     }
                     if open_income_add() {
-                        IncomeAdd { handle_add, handle_cancel: handle_add_cancel }
+                        IncomeAdd { accounts, default_account_id, categories, handle_add, handle_cancel: handle_add_cancel }
                     }
                 }
             }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
             if !open_income_add() {
                 div {
                     class: "income-add",
@@ -91,13 +128,29 @@ pub fn IncomeList(
 }
 
 #[component]
-fn IncomeAdd(handle_add: Callback<(String, String, String)>, handle_cancel: EventHandler<MouseEvent>) -> Element {
+fn IncomeAdd(
+    accounts: Signal<Vec<AccountSchema>>,
+    default_account_id: u64,
+    categories: Signal<Vec<CategorySchema>>,
+    handle_add: Callback<LedgerEntryInput>,
+    handle_cancel: EventHandler<MouseEvent>,
+) -> Element {
     let mut name = use_signal(|| "".to_string());
     let mut amount = use_signal(|| "".to_string());
     let mut date = use_signal(|| "".to_string());
+    let mut account_id = use_signal(|| default_account_id);
+    let mut category_id = use_signal(|| None::<u64>);
+    let mut tags = use_signal(|| "".to_string());
 
     let handle_add = move |_| {
-        handle_add((name(), amount(), date()));
+        handle_add(LedgerEntryInput {
+            name: name(),
+            amount: amount(),
+            date: date(),
+            account_id: account_id(),
+            category_id: category_id(),
+            tags: tags(),
+        });
     };
 
     rsx! {
@@ -117,6 +170,29 @@ fn IncomeAdd(handle_add: Callback<(String, String, String)>, handle_cancel: Even
                     onchange: move |e| date.set(e.value()),
                 }
             }
+            td {
+                AccountSelect {
+                    accounts,
+                    value: account_id(),
+                    handle_change: move |id| account_id.set(id),
+                }
+            }
+            td {
+                CategorySelect {
+                    categories,
+                    value: category_id(),
+                    none_label: "未分類",
+                    handle_change: move |id| category_id.set(id),
+                }
+            }
+            td {
+                input {
+                    type: "text",
+                    placeholder: "タグ (カンマ区切り)",
+                    value: "{tags}",
+                    onchange: move |e| tags.set(e.value()),
+                }
+            }
             td {
                 input {
                     type: "text",
@@ -145,15 +221,33 @@ pub fn IncomeEdit(
     name: String,
     amount: String,
     date: String,
-    handle_edit: Callback<(u64, String, String, String)>,
+    account_id: u64,
+    category_id: Option<u64>,
+    tags: String,
+    accounts: Signal<Vec<AccountSchema>>,
+    categories: Signal<Vec<CategorySchema>>,
+    handle_edit: Callback<(u64, LedgerEntryInput)>,
     handle_cancel: EventHandler<MouseEvent>,
 ) -> Element {
     let mut name = use_signal(|| name);
     let mut amount = use_signal(|| amount);
     let mut date = use_signal(|| date);
+    let mut account_id = use_signal(|| account_id);
+    let mut category_id = use_signal(|| category_id);
+    let mut tags = use_signal(|| tags);
 
     let handle_edit = move |_| {
-        handle_edit((id, name(), amount(), date()));
+        handle_edit((
+            id,
+            LedgerEntryInput {
+                name: name(),
+                amount: amount(),
+                date: date(),
+                account_id: account_id(),
+                category_id: category_id(),
+                tags: tags(),
+            },
+        ));
     };
 
     rsx! {
@@ -173,6 +267,29 @@ pub fn IncomeEdit(
                     onchange: move |e| date.set(e.value()),
                 }
             }
+            td {
+                AccountSelect {
+                    accounts,
+                    value: account_id(),
+                    handle_change: move |id| account_id.set(id),
+                }
+            }
+            td {
+                CategorySelect {
+                    categories,
+                    value: category_id(),
+                    none_label: "未分類",
+                    handle_change: move |id| category_id.set(id),
+                }
+            }
+            td {
+                input {
+                    type: "text",
+                    placeholder: "タグ (カンマ区切り)",
+                    value: "{tags}",
+                    onchange: move |e| tags.set(e.value()),
+                }
+            }
             td {
                 input {
                     type: "text",
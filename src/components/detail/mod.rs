@@ -5,4 +5,18 @@ mod outcome;
 pub use outcome::OutcomeList;
 
 mod summary;
-pub use summary::Summary;
\ No newline at end of file
+pub use summary::Summary;
+
+mod category;
+pub use category::{CategoryList, LedgerFilterBar};
+
+mod budget;
+
+mod reconciliation;
+pub use reconciliation::ReconciliationList;
+
+mod account;
+pub use account::{AccountList, StatementList};
+
+mod transfer;
+pub use transfer::TransferList;
@@ -0,0 +1,90 @@
+use crate::finance::api::reconciliation::{PromotionInput, ReconciliationSchema, ReconciliationTotalSchema};
+use dioxus::prelude::*;
+
+// その月の計画と明細の比較
+// 未記録の計画は、計画どおりの金額で明細に記録できる
+#[component]
+pub fn ReconciliationList(
+    reconciliation: Signal<ReconciliationSchema>,
+    handle_promote: Callback<PromotionInput>,
+    error: Signal<Option<String>>,
+) -> Element {
+    rsx! {
+        div {
+            id: "reconciliation-list",
+            h3 { "計画との比較" }
+            table {
+                thead {
+                    tr {
+                        th { "" }
+                        th { "名前" }
+                        th { "予定日" }
+                        th { "計画" }
+                        th { "記録日" }
+                        th { "実績" }
+                        th { "差額" }
+                        th { "" }
+                    }
+                }
+                tbody {
+                    for item in reconciliation().items {
+                        tr {
+                            td { "{item.kind.label()}" }
+                            td { "{item.name}" }
+                            td { "{item.planned_date}" }
+                            td { class: "amount", "{item.planned}" }
+                            td { "{item.recorded_date}" }
+                            td { class: "amount", "{item.recorded}" }
+                            td {
+                                class: if item.is_unfavorable { "amount unfavorable" } else { "amount" },
+                                "{item.variance}"
+                            }
+                            td {
+                                if let Some(promotion) = item.promotion {
+                                    button {
+                                        onclick: move |_| handle_promote(promotion.clone()),
+                                        "記録する"
+                                    }
+                                } else {
+                                    "{item.status}"
+                                }
+                            }
+                        }
+                    }
+                    ReconciliationTotalRow { label: "収入の合計", total: reconciliation().income }
+                    ReconciliationTotalRow { label: "支出の合計", total: reconciliation().outcome }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ReconciliationTotalRow(label: String, total: ReconciliationTotalSchema) -> Element {
+    rsx! {
+        tr {
+            class: "total",
+            td { "" }
+            td { "{label}" }
+            td { "" }
+            td { class: "amount", "{total.planned}" }
+            td { "" }
+            td {
+                class: "amount",
+                title: "計画外 {total.unplanned} を含む",
+                "{total.recorded}"
+            }
+            td {
+                class: if total.is_unfavorable { "amount unfavorable" } else { "amount" },
+                "{total.variance}"
+            }
+            td { "計画外 {total.unplanned}" }
+        }
+    }
+}
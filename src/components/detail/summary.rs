@@ -1,3 +1,5 @@
+use super::budget::BudgetList;
+use crate::finance::api::detail::{BudgetStatusSchema, BudgetTarget, CategorySchema};
 use dioxus::prelude::*;
 use rust_decimal::Decimal;
 
@@ -7,7 +9,11 @@ pub fn Summary(
     total_outcome: Signal<Decimal>,
     last_month_saving: Signal<Decimal>,
     current_month_saving: Signal<Decimal>,
-    handle_create_adjustment: Callback<String>,
+    budgets: Signal<Vec<BudgetStatusSchema>>,
+    categories: Signal<Vec<CategorySchema>>,
+    handle_add_budget: Callback<(BudgetTarget, String, bool)>,
+    handle_delete_budget: Callback<u64>,
+    budget_error: Signal<Option<String>>,
 ) -> Element {
     rsx! {
         div {
@@ -42,13 +48,8 @@ pub fn Summary(
         
         div {
             "今月の貯金: {current_month_saving()}"
-            input {
-                type: "number",
-                placeholder: "貯蓄手動入力",
-                onchange: move |e| {
-                    handle_create_adjustment(e.value());
-                },
-            }
         }
+
+        BudgetList { budgets, categories, handle_add: handle_add_budget, handle_delete: handle_delete_budget, error: budget_error }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,161 @@
+use super::account::AccountSelect;
+use crate::finance::api::detail::{AccountSchema, TransferSchema};
+use dioxus::prelude::*;
+
+#[component]
+pub fn TransferList(
+    transfers: Signal<Vec<TransferSchema>>,
+    accounts: Signal<Vec<AccountSchema>>,
+    default_account_id: u64,
+    handle_add: Callback<(u64, u64, String, String, String)>,
+    handle_delete: Callback<u64>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut open_transfer_add = use_signal(|| false);
+
+    let handle_add = move |(from_account_id, to_account_id, amount, date, memo)| {
+        handle_add((from_account_id, to_account_id, amount, date, memo));
+        // 入力が受け付けられなかった場合はフォームを開いたままにする
+        if error().is_none() {
+            open_transfer_add.set(false);
+        }
+    };
+
+    let handle_add_cancel = move |_| {
+        error.set(None);
+        open_transfer_add.set(false);
+    };
+
+    rsx! {
+        div {
+            id: "transfer-list",
+            h2 { "振替" },
+            table {
+                thead {
+                    tr {
+                        th { "日付" }
+                        th { "振替元" }
+                        th { "振替先" }
+                        th { "メモ" }
+                        th { "金額" }
+                    }
+                }
+                tbody {
+                    for transfer in transfers() {
+                        tr {
+                            td { "{transfer.date}" }
+                            td { "{transfer.from_account}" }
+                            td { "{transfer.to_account}" }
+                            td { "{transfer.memo}" }
+                            td {
+                                class: "amount",
+                                "{transfer.amount}"
+                            }
+                            td {
+                                button {
+                                    onclick: move |_| handle_delete(transfer.id),
+                                    "削除"
+                                }
+                            }
+                        }
+                    }
+                    if open_transfer_add() {
+                        TransferAdd { accounts, default_account_id, handle_add, handle_cancel: handle_add_cancel }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+            if !open_transfer_add() {
+                div {
+                    class: "transfer-add",
+                    button {
+                        onclick: move |_| open_transfer_add.set(true),
+                        "振替を追加"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TransferAdd(
+    accounts: Signal<Vec<AccountSchema>>,
+    default_account_id: u64,
+    handle_add: Callback<(u64, u64, String, String, String)>,
+    handle_cancel: EventHandler<MouseEvent>,
+) -> Element {
+    let mut date = use_signal(|| "".to_string());
+    let mut from_account_id = use_signal(|| default_account_id);
+    // 振替元と違う口座を初期値にする
+    let mut to_account_id = use_signal(|| {
+        accounts()
+            .iter()
+            .map(|account| account.id)
+            .find(|id| *id != default_account_id)
+            .unwrap_or(default_account_id)
+    });
+    let mut memo = use_signal(|| "".to_string());
+    let mut amount = use_signal(|| "".to_string());
+
+    let handle_add = move |_| {
+        handle_add((from_account_id(), to_account_id(), amount(), date(), memo()));
+    };
+
+    rsx! {
+        tr {
+            td {
+                input {
+                    type: "date",
+                    value: "{date}",
+                    onchange: move |e| date.set(e.value()),
+                }
+            }
+            td {
+                AccountSelect {
+                    accounts,
+                    value: from_account_id(),
+                    handle_change: move |id| from_account_id.set(id),
+                }
+            }
+            td {
+                AccountSelect {
+                    accounts,
+                    value: to_account_id(),
+                    handle_change: move |id| to_account_id.set(id),
+                }
+            }
+            td {
+                input {
+                    type: "text",
+                    placeholder: "メモ",
+                    value: "{memo}",
+                    onchange: move |e| memo.set(e.value()),
+                }
+            }
+            td {
+                input {
+                    type: "text",
+                    placeholder: "金額",
+                    value: "{amount}",
+                    onchange: move |e| amount.set(e.value()),
+                }
+            }
+            td {
+                button {
+                    onclick: handle_add,
+                    "追加"
+                }
+                button {
+                    onclick: handle_cancel,
+                    "キャンセル"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use dioxus::prelude::*;
+
+// id が focus の要素までスクロールして目立たせる
+// 一覧はデータを読み込んでから描かれるので、見つかるまで少し待つ
+pub fn use_focus(focus: String) {
+    use_effect(use_reactive!(|focus| {
+        if focus.is_empty() {
+            return;
+        }
+        document::eval(&format!(
+            r#"
+            const id = {:?};
+            let tries = 0;
+            const find = () => {{
+                const element = document.getElementById(id);
+                if (element) {{
+                    element.scrollIntoView({{ block: "center" }});
+                    element.classList.add("focused");
+                    setTimeout(() => element.classList.remove("focused"), 2000);
+                }} else if (tries++ < 20) {{
+                    setTimeout(find, 50);
+                }}
+            }};
+            find();
+            "#,
+            focus
+        ));
+    }));
+}
@@ -0,0 +1,465 @@
+use chrono::{Days, Months, NaiveDate};
+use dioxus::prelude::*;
+use rust_decimal::prelude::*;
+
+use crate::finance::api::plan::{ForecastSchema, FutureInspectResultSchema};
+use crate::finance::api::scenario::ScenarioComparisonSchema;
+
+// グラフの大きさ (viewBox の座標)
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 260.0;
+// 目盛りを書く余白
+const LEFT: f64 = 90.0;
+const RIGHT: f64 = 10.0;
+const TOP: f64 = 10.0;
+const BOTTOM: f64 = 30.0;
+// 重ねるシナリオの線の色 (順に使う)
+const SCENARIO_COLORS: [&str; 4] = ["#2980b9", "#8e44ad", "#d35400", "#16a085"];
+
+// 一度に表示する期間
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChartZoom {
+    Week,
+    Month,
+    Year,
+}
+
+impl ChartZoom {
+    const ALL: [ChartZoom; 3] = [ChartZoom::Week, ChartZoom::Month, ChartZoom::Year];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ChartZoom::Week => "週",
+            ChartZoom::Month => "月",
+            ChartZoom::Year => "年",
+        }
+    }
+
+    fn add(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            ChartZoom::Week => date.checked_add_days(Days::new(7)),
+            ChartZoom::Month => date.checked_add_months(Months::new(1)),
+            ChartZoom::Year => date.checked_add_months(Months::new(12)),
+        }
+    }
+
+    fn sub(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            ChartZoom::Week => date.checked_sub_days(Days::new(7)),
+            ChartZoom::Month => date.checked_sub_months(Months::new(1)),
+            ChartZoom::Year => date.checked_sub_months(Months::new(12)),
+        }
+    }
+
+    fn axis_format(&self) -> &'static str {
+        match self {
+            ChartZoom::Week | ChartZoom::Month => "%m/%d",
+            ChartZoom::Year => "%Y/%m",
+        }
+    }
+}
+
+// 次の収入か支出までは同じ残高が続く
+#[derive(Debug, PartialEq)]
+struct Step {
+    x_from: f64,
+    x_to: f64,
+    amount: Decimal,
+}
+
+// 表示する期間の座標の計算
+#[derive(Debug, PartialEq)]
+struct Scale {
+    start: NaiveDate,
+    days: f64,
+    min: Decimal,
+    max: Decimal,
+}
+
+impl Scale {
+    // 縦軸には 0 と、正なら残高の下限も入れる
+    fn new(start: NaiveDate, end: NaiveDate, amounts: impl IntoIterator<Item = Decimal>, threshold: Decimal) -> Self {
+        let mut amounts: Vec<Decimal> = amounts.into_iter().collect();
+        amounts.push(Decimal::ZERO);
+        if threshold > Decimal::ZERO {
+            amounts.push(threshold);
+        }
+        Self {
+            start,
+            days: (end - start).num_days().max(1) as f64,
+            min: amounts.iter().min().copied().unwrap_or_default(),
+            max: amounts.iter().max().copied().unwrap_or_default(),
+        }
+    }
+
+    fn x(&self, date: NaiveDate) -> f64 {
+        let days = (date - self.start).num_days() as f64;
+        LEFT + (days / self.days).clamp(0.0, 1.0) * (WIDTH - LEFT - RIGHT)
+    }
+
+    fn y(&self, amount: Decimal) -> f64 {
+        let (min, max) = (self.min.to_f64().unwrap_or_default(), self.max.to_f64().unwrap_or_default());
+        // 残高がずっと 0 なら幅 1 として描く
+        let max = if max > min { max } else { min + 1.0 };
+        let amount = amount.to_f64().unwrap_or_default();
+        TOP + (max - amount) / (max - min) * (HEIGHT - TOP - BOTTOM)
+    }
+}
+
+// グラフに重ねるシナリオの線
+struct Overlay<'a> {
+    name: &'a str,
+    color: &'static str,
+    visible: Vec<(NaiveDate, Decimal)>,
+    carried: Option<Decimal>,
+    last_date: NaiveDate,
+}
+
+// 表示する期間の点
+fn points_in_window(points: &[(NaiveDate, Decimal)], start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, Decimal)> {
+    points.iter().filter(|(date, _)| start <= *date && *date < end).copied().collect()
+}
+
+// 表示する期間の前から続いている残高
+fn carried_balance(points: &[(NaiveDate, Decimal)], start: NaiveDate) -> Option<Decimal> {
+    points.iter().rev().find(|(date, _)| *date < start).map(|(_, amount)| *amount)
+}
+
+// 表示する期間の残高の階段
+// 期間の後にも予測があれば右端まで、なければ最後の日の 1 日分だけ伸ばす
+fn build_steps(
+    scale: &Scale,
+    visible: &[(NaiveDate, Decimal)],
+    carried: Option<Decimal>,
+    last_date: NaiveDate,
+    end: NaiveDate,
+) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let mut cursor = (LEFT, carried);
+    for (date, amount) in visible {
+        let x = scale.x(*date);
+        if let (x_from, Some(amount)) = cursor {
+            steps.push(Step { x_from, x_to: x, amount });
+        }
+        cursor = (x, Some(*amount));
+    }
+    if let (x_from, Some(amount)) = cursor {
+        let x_to = if last_date >= end { WIDTH - RIGHT } else { scale.x(last_date + Days::new(1)) };
+        steps.push(Step { x_from, x_to, amount });
+    }
+    steps
+}
+
+// 階段を線の path にする
+fn step_path(scale: &Scale, steps: &[Step]) -> String {
+    let mut line = String::new();
+    for (i, step) in steps.iter().enumerate() {
+        let y = scale.y(step.amount);
+        if i == 0 {
+            line += &format!("M{:.1},{:.1}", step.x_from, y);
+        } else {
+            line += &format!("V{:.1}", y);
+        }
+        line += &format!("H{:.1}", step.x_to);
+    }
+    line
+}
+
+// 予測した残高の推移
+// 残高がマイナスになる期間を赤く塗り、点に重ねるとその日の収入と支出を表示する
+// 比べるシナリオがあれば、その残高を破線で重ねる
+#[component]
+pub fn BalanceChart(forecast: Signal<ForecastSchema>, scenarios: Signal<ScenarioComparisonSchema>) -> Element {
+    let mut zoom = use_signal(|| ChartZoom::Month);
+    // None なら予測の最初の日から
+    let mut window_start = use_signal(|| None::<NaiveDate>);
+    let mut hovered = use_signal(|| None::<usize>);
+
+    // 予測し直したら先頭に戻す
+    use_effect(move || {
+        forecast.read();
+        window_start.set(None);
+        hovered.set(None);
+    });
+
+    let forecast = forecast.read();
+    let points: Vec<(NaiveDate, Decimal)> = forecast.days.iter().map(|day| (day.date, day.amount)).collect();
+    let (Some((first_date, _)), Some((last_date, _))) = (points.first().copied(), points.last().copied()) else {
+        return rsx! {};
+    };
+    let start = window_start().unwrap_or(first_date);
+    let end = zoom().add(start).unwrap_or(start);
+
+    let visible: Vec<&FutureInspectResultSchema> = forecast.days.iter().filter(|day| start <= day.date && day.date < end).collect();
+    let visible_points = points_in_window(&points, start, end);
+    let carried = carried_balance(&points, start);
+    let scenarios = scenarios.read();
+    let overlays: Vec<Overlay> = scenarios
+        .series
+        .iter()
+        .zip(SCENARIO_COLORS.iter().cycle())
+        .map(|(series, color)| Overlay {
+            name: &series.name,
+            color,
+            visible: points_in_window(&series.points, start, end),
+            carried: carried_balance(&series.points, start),
+            last_date: series.points.last().map_or(last_date, |(date, _)| *date),
+        })
+        .collect();
+    let scale = Scale::new(
+        start,
+        end,
+        visible_points
+            .iter()
+            .map(|(_, amount)| *amount)
+            .chain(carried)
+            .chain(overlays.iter().flat_map(|overlay| overlay.visible.iter().map(|(_, amount)| *amount).chain(overlay.carried))),
+        forecast.low_balance_threshold,
+    );
+    let steps = build_steps(&scale, &visible_points, carried, last_date, end);
+    let overlay_lines: Vec<(&str, &str, String)> = overlays
+        .iter()
+        .map(|overlay| {
+            let steps = build_steps(&scale, &overlay.visible, overlay.carried, overlay.last_date, end);
+            (overlay.name, overlay.color, step_path(&scale, &steps))
+        })
+        .collect();
+
+    let zero_y = scale.y(Decimal::ZERO);
+    let line = step_path(&scale, &steps);
+    let area = steps
+        .first()
+        .map_or_else(String::new, |first| format!("{}V{:.1}H{:.1}Z", line, zero_y, first.x_from));
+    let deficits: Vec<(f64, f64, f64, f64)> = steps
+        .iter()
+        .filter(|step| step.amount < Decimal::ZERO)
+        .map(|step| (step.x_from, zero_y, step.x_to - step.x_from, scale.y(step.amount) - zero_y))
+        .collect();
+    let threshold_y = (forecast.low_balance_threshold > Decimal::ZERO).then(|| scale.y(forecast.low_balance_threshold));
+    let axis_format = zoom().axis_format();
+    let last_visible = end.pred_opt().unwrap_or(end).min(last_date);
+
+    let tooltip = hovered().and_then(|i| visible.get(i).copied()).cloned();
+    let tooltip_left = tooltip.as_ref().map_or(0.0, |day| scale.x(day.date) / WIDTH * 100.0);
+
+    rsx! {
+        div {
+            class: "balance-chart",
+            div {
+                class: "chart-controls",
+                for z in ChartZoom::ALL {
+                    button {
+                        class: if zoom() == z { "selected" } else { "" },
+                        onclick: move |_| {
+                            zoom.set(z);
+                            hovered.set(None);
+                        },
+                        "{z.label()}"
+                    }
+                }
+                button {
+                    disabled: start <= first_date,
+                    onclick: move |_| {
+                        window_start.set(zoom().sub(start).map(|date| date.max(first_date)));
+                        hovered.set(None);
+                    },
+                    "◀ 前"
+                }
+                button {
+                    disabled: end > last_date,
+                    onclick: move |_| {
+                        window_start.set(Some(end));
+                        hovered.set(None);
+                    },
+                    "次 ▶"
+                }
+                if !overlay_lines.is_empty() {
+                    span {
+                        class: "chart-legend",
+                        span { style: "color: #27ae60;", "━ 計画" }
+                        for (name, color, _) in overlay_lines.iter() {
+                            span { style: "color: {color};", "┅ {name}" }
+                        }
+                    }
+                }
+            }
+            div {
+                class: "chart-area",
+                svg {
+                    view_box: "0 0 {WIDTH} {HEIGHT}",
+                    onmouseleave: move |_| hovered.set(None),
+                    path { d: "{area}", fill: "rgba(46, 204, 113, 0.25)", stroke: "none" }
+                    for (x, y, width, height) in deficits {
+                        rect { x: "{x}", y: "{y}", width: "{width}", height: "{height}", fill: "rgba(192, 57, 43, 0.45)" }
+                    }
+                    line { x1: "{LEFT}", y1: "{zero_y}", x2: "{WIDTH - RIGHT}", y2: "{zero_y}", stroke: "#666", stroke_width: "1" }
+                    if let Some(threshold_y) = threshold_y {
+                        line {
+                            x1: "{LEFT}",
+                            y1: "{threshold_y}",
+                            x2: "{WIDTH - RIGHT}",
+                            y2: "{threshold_y}",
+                            stroke: "#e67e22",
+                            stroke_width: "1",
+                            stroke_dasharray: "4 4",
+                        }
+                    }
+                    path { d: "{line}", fill: "none", stroke: "#27ae60", stroke_width: "2" }
+                    for (_, color, overlay) in overlay_lines.iter() {
+                        path { d: "{overlay}", fill: "none", stroke: "{color}", stroke_width: "2", stroke_dasharray: "6 3" }
+                    }
+                    text { x: "{LEFT - 6.0}", y: "{TOP + 4.0}", text_anchor: "end", "{forecast.currency.format(&scale.max)}" }
+                    text { x: "{LEFT - 6.0}", y: "{HEIGHT - BOTTOM}", text_anchor: "end", "{forecast.currency.format(&scale.min)}" }
+                    if scale.min < Decimal::ZERO && scale.max > Decimal::ZERO {
+                        text { x: "{LEFT - 6.0}", y: "{zero_y + 4.0}", text_anchor: "end", "0" }
+                    }
+                    text { x: "{LEFT}", y: "{HEIGHT - 8.0}", text_anchor: "start", "{start.format(axis_format)}" }
+                    text { x: "{WIDTH - RIGHT}", y: "{HEIGHT - 8.0}", text_anchor: "end", "{last_visible.format(axis_format)}" }
+                    for (i, day) in visible.iter().enumerate() {
+                        circle {
+                            cx: "{scale.x(day.date)}",
+                            cy: "{scale.y(day.amount)}",
+                            r: if hovered() == Some(i) { "5" } else { "3" },
+                            fill: if day.amount < Decimal::ZERO { "#c0392b" } else { "#27ae60" },
+                            onmouseenter: move |_| hovered.set(Some(i)),
+                        }
+                    }
+                }
+                if let Some(day) = tooltip {
+                    div {
+                        class: "chart-tooltip",
+                        style: "left: {tooltip_left}%;",
+                        strong { "{day.date_label}" }
+                        p { "残高 {day.balance}" }
+                        for income in day.incomes {
+                            p { class: "income", "+ {income.name}: {income.amount_label}" }
+                        }
+                        for outcome in day.outcomes {
+                            p { class: "outcome", "- {outcome.name}: {outcome.amount_label}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn point(month: u32, day: u32, amount: i64) -> (NaiveDate, Decimal) {
+        (date(month, day), Decimal::from(amount))
+    }
+
+    #[test]
+    fn moves_the_window_by_the_zoom() {
+        assert_eq!(ChartZoom::Week.add(date(1, 29)), Some(date(2, 5)));
+        assert_eq!(ChartZoom::Week.sub(date(3, 3)), Some(date(2, 24)));
+        // 月末は次の月の末日に丸める
+        assert_eq!(ChartZoom::Month.add(date(1, 31)), Some(date(2, 28)));
+        assert_eq!(ChartZoom::Month.sub(date(3, 31)), Some(date(2, 28)));
+        assert_eq!(ChartZoom::Year.add(date(4, 1)), NaiveDate::from_ymd_opt(2026, 4, 1));
+        assert_eq!(ChartZoom::Year.sub(date(4, 1)), NaiveDate::from_ymd_opt(2024, 4, 1));
+    }
+
+    #[test]
+    fn scales_dates_and_amounts_into_the_plot_area() {
+        let scale = Scale::new(date(4, 1), date(5, 1), [Decimal::from(-100), Decimal::from(300)], Decimal::ZERO);
+        assert_eq!((scale.min, scale.max), (Decimal::from(-100), Decimal::from(300)));
+        assert_eq!(scale.x(date(4, 1)), LEFT);
+        assert_eq!(scale.x(date(5, 1)), WIDTH - RIGHT);
+        // 期間の外は端に寄せる
+        assert_eq!(scale.x(date(3, 1)), LEFT);
+        assert_eq!(scale.x(date(6, 1)), WIDTH - RIGHT);
+        assert_eq!(scale.y(Decimal::from(300)), TOP);
+        assert_eq!(scale.y(Decimal::from(-100)), HEIGHT - BOTTOM);
+        assert_eq!(scale.y(Decimal::ZERO), TOP + 0.75 * (HEIGHT - TOP - BOTTOM));
+    }
+
+    #[test]
+    fn includes_a_threshold_above_all_amounts() {
+        let scale = Scale::new(date(4, 1), date(5, 1), [Decimal::from(100), Decimal::from(200)], Decimal::from(500));
+        assert_eq!((scale.min, scale.max), (Decimal::ZERO, Decimal::from(500)));
+        assert_eq!(scale.y(Decimal::from(500)), TOP);
+        assert_eq!(scale.y(Decimal::ZERO), HEIGHT - BOTTOM);
+    }
+
+    #[test]
+    fn scales_an_empty_window() {
+        let scale = Scale::new(date(4, 1), date(4, 1), [], Decimal::ZERO);
+        assert_eq!(scale.days, 1.0);
+        assert_eq!((scale.min, scale.max), (Decimal::ZERO, Decimal::ZERO));
+        assert!(scale.y(Decimal::ZERO).is_finite());
+    }
+
+    #[test]
+    fn carries_the_last_balance_before_the_window() {
+        let points = [point(3, 25, 100), point(3, 27, 70), point(4, 10, 40)];
+        assert_eq!(carried_balance(&points, date(4, 1)), Some(Decimal::from(70)));
+        assert_eq!(carried_balance(&points, date(3, 27)), Some(Decimal::from(100)));
+        assert_eq!(carried_balance(&points, date(3, 25)), None);
+        assert_eq!(carried_balance(&[], date(4, 1)), None);
+    }
+
+    #[test]
+    fn picks_points_in_the_window() {
+        let points = [point(3, 31, 100), point(4, 1, 70), point(4, 30, 40), point(5, 1, 140)];
+        assert_eq!(points_in_window(&points, date(4, 1), date(5, 1)), vec![point(4, 1, 70), point(4, 30, 40)]);
+        assert_eq!(points_in_window(&points, date(6, 1), date(7, 1)), vec![]);
+    }
+
+    #[test]
+    fn steps_through_the_visible_points() {
+        let (start, end) = (date(4, 1), date(5, 1));
+        let visible = [point(4, 10, 40), point(4, 25, 140)];
+        let scale = Scale::new(start, end, [Decimal::from(70), Decimal::from(40), Decimal::from(140)], Decimal::ZERO);
+        let steps = build_steps(&scale, &visible, Some(Decimal::from(70)), date(6, 30), end);
+        assert_eq!(
+            steps,
+            vec![
+                Step { x_from: LEFT, x_to: scale.x(date(4, 10)), amount: Decimal::from(70) },
+                Step { x_from: scale.x(date(4, 10)), x_to: scale.x(date(4, 25)), amount: Decimal::from(40) },
+                Step { x_from: scale.x(date(4, 25)), x_to: WIDTH - RIGHT, amount: Decimal::from(140) },
+            ],
+        );
+        assert!(step_path(&scale, &steps).starts_with(&format!("M{:.1},", LEFT)));
+    }
+
+    #[test]
+    fn steps_an_empty_window() {
+        let (start, end) = (date(4, 1), date(5, 1));
+        let scale = Scale::new(start, end, [Decimal::from(70)], Decimal::ZERO);
+        // 前から続く残高だけを右端まで伸ばす
+        assert_eq!(
+            build_steps(&scale, &[], Some(Decimal::from(70)), date(6, 30), end),
+            vec![Step { x_from: LEFT, x_to: WIDTH - RIGHT, amount: Decimal::from(70) }],
+        );
+        assert_eq!(build_steps(&scale, &[], None, date(6, 30), end), vec![]);
+        assert_eq!(step_path(&scale, &[]), "");
+    }
+
+    #[test]
+    fn steps_a_single_point() {
+        let (start, end) = (date(4, 1), date(5, 1));
+        let scale = Scale::new(start, end, [Decimal::from(40)], Decimal::ZERO);
+        assert_eq!(
+            build_steps(&scale, &[point(4, 1, 40)], None, date(4, 1), end),
+            vec![Step { x_from: LEFT, x_to: scale.x(date(4, 2)), amount: Decimal::from(40) }],
+        );
+    }
+
+    #[test]
+    fn stops_after_the_last_date_in_a_window_past_it() {
+        let (start, end) = (date(4, 1), date(5, 1));
+        let visible = [point(4, 10, 40)];
+        let scale = Scale::new(start, end, [Decimal::from(70), Decimal::from(40)], Decimal::ZERO);
+        let steps = build_steps(&scale, &visible, Some(Decimal::from(70)), date(4, 10), end);
+        assert_eq!(steps.last().map(|step| step.x_to), Some(scale.x(date(4, 11))));
+        assert!(steps.iter().all(|step| step.x_to < WIDTH - RIGHT));
+    }
+}
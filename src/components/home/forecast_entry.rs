@@ -0,0 +1,33 @@
+use dioxus::prelude::*;
+
+use crate::finance::api::plan::{EntryPage, ForecastEntrySchema};
+use crate::Route;
+
+// その日の収入か支出の一覧
+// 押すと元の項目がある画面に移る
+#[component]
+pub fn ForecastEntryList(entries: Vec<ForecastEntrySchema>, total: String) -> Element {
+    let navigator = use_navigator();
+
+    rsx! {
+        ul {
+            class: "forecast-entries",
+            for entry in entries.iter().cloned() {
+                li {
+                    title: "{entry.source_kind.label()}",
+                    onclick: move |_| {
+                        let focus = entry.anchor.clone();
+                        navigator.push(match entry.page {
+                            EntryPage::Plan => Route::Plan { focus },
+                            EntryPage::Detail => Route::Detail { focus },
+                        });
+                    },
+                    "{entry.name}: {entry.amount_label}"
+                }
+            }
+            if entries.len() > 1 {
+                li { class: "total", "計 {total}" }
+            }
+        }
+    }
+}
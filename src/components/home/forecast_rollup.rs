@@ -0,0 +1,87 @@
+use dioxus::prelude::*;
+
+use crate::finance::api::plan::{ForecastSchema, RollupSchema};
+
+// 予測を月ごとにまとめた表
+// 年の行を押すとその年の月を開閉する
+#[component]
+pub fn ForecastRollupTable(forecast: Signal<ForecastSchema>) -> Element {
+    // 開いている年
+    let mut expanded = use_signal(Vec::<i32>::new);
+
+    // 予測し直したら最初の年だけ開く
+    use_effect(move || {
+        expanded.set(forecast.read().rollups.first().map(|rollup| rollup.year).into_iter().collect());
+    });
+
+    rsx! {
+        details {
+            id: "forecast-rollup",
+            summary { "月ごとの集計" }
+            table {
+                thead {
+                    tr {
+                        th { "" }
+                        th { "期首残高" }
+                        th { "収入" }
+                        th { "支出" }
+                        th { "収支" }
+                        th { "期末残高" }
+                        th { "最低残高" }
+                    }
+                }
+                tbody {
+                    for rollup in forecast.read().rollups.clone() {
+                        RollupRow {
+                            key: "{rollup.year}",
+                            rollup: rollup.summary.clone(),
+                            class: "year",
+                            marker: if expanded().contains(&rollup.year) { "▼" } else { "▶" },
+                            handle_click: move |_| {
+                                let mut expanded = expanded.write();
+                                if expanded.contains(&rollup.year) {
+                                    expanded.retain(|year| *year != rollup.year);
+                                } else {
+                                    expanded.push(rollup.year);
+                                }
+                            },
+                        }
+                        if expanded().contains(&rollup.year) {
+                            for month in rollup.months.clone() {
+                                RollupRow {
+                                    rollup: month,
+                                    class: "month",
+                                    marker: "",
+                                    handle_click: |_| {},
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RollupRow(rollup: RollupSchema, class: String, marker: String, handle_click: Callback<()>) -> Element {
+    rsx! {
+        tr {
+            class: "{class}",
+            onclick: move |_| handle_click(()),
+            td { "{marker} {rollup.label}" }
+            td { class: "amount", "{rollup.opening}" }
+            td { class: "amount", "{rollup.total_income}" }
+            td { class: "amount", "{rollup.total_outcome}" }
+            td {
+                class: if rollup.is_net_negative { "amount negative" } else { "amount" },
+                "{rollup.net}"
+            }
+            td { class: "amount", "{rollup.closing}" }
+            td {
+                class: if rollup.is_low_balance { "amount low-balance" } else { "amount" },
+                "{rollup.minimum}"
+            }
+        }
+    }
+}
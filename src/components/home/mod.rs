@@ -0,0 +1,6 @@
+mod balance_chart;
+mod forecast_entry;
+mod forecast_rollup;
+pub use balance_chart::BalanceChart;
+pub use forecast_entry::ForecastEntryList;
+pub use forecast_rollup::ForecastRollupTable;
@@ -1,5 +1,9 @@
 pub mod detail;
+pub mod home;
 pub mod plan;
 
+mod focus;
+pub use focus::use_focus;
+
 mod navbar;
-pub use navbar::Navbar;
\ No newline at end of file
+pub use navbar::Navbar;
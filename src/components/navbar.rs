@@ -15,13 +15,17 @@ pub fn Navbar() -> Element {
                 "Home"
             }
             Link {
-                to: Route::Detail {},
+                to: Route::Detail { focus: String::new() },
                 "明細"
             }
             Link {
-                to: Route::Plan {},
+                to: Route::Plan { focus: String::new() },
                 "計画"
             }
+            Link {
+                to: Route::Settings {},
+                "設定"
+            }
         }
 
         div {
@@ -0,0 +1,28 @@
+use crate::finance::api::detail::AccountSchema;
+use dioxus::prelude::*;
+
+// 計画を記録する口座 (空なら既定の口座)
+#[component]
+pub fn PlanAccountSelect(
+    accounts: Signal<Vec<AccountSchema>>,
+    value: Option<u64>,
+    handle_change: Callback<Option<u64>>,
+) -> Element {
+    rsx! {
+        select {
+            onchange: move |e| handle_change(e.value().parse().ok()),
+            option {
+                value: "",
+                selected: value.is_none(),
+                "既定の口座"
+            }
+            for account in accounts() {
+                option {
+                    value: "{account.id}",
+                    selected: value == Some(account.id),
+                    "{account.name}"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::finance::api::plan::HolidaySchema;
+use dioxus::prelude::*;
+
+// 同梱の祝日のほかに休みになる日 (銀行の休業日など)
+#[component]
+pub fn HolidayList(
+    holidays: Signal<Vec<HolidaySchema>>,
+    handle_add: Callback<(String, String)>,
+    handle_delete: Callback<u64>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut date = use_signal(|| "".to_string());
+    let mut name = use_signal(|| "".to_string());
+
+    let handle_add = move |_| {
+        handle_add((date(), name()));
+        if error().is_none() {
+            date.set("".to_string());
+            name.set("".to_string());
+        }
+    };
+
+    rsx! {
+        div {
+            id: "holiday-list",
+            h3 { "休日" }
+            p { class: "note", "土日と祝日は自動で休日になります" }
+            table {
+                tbody {
+                    for holiday in holidays() {
+                        tr {
+                            td { "{holiday.date}" }
+                            td { "{holiday.name}" }
+                            td {
+                                button {
+                                    onclick: move |_| handle_delete(holiday.id),
+                                    "削除"
+                                }
+                            }
+                        }
+                    }
+                    tr {
+                        td {
+                            input {
+                                type: "date",
+                                value: "{date}",
+                                onchange: move |e| date.set(e.value()),
+                            }
+                        }
+                        td {
+                            input {
+                                type: "text",
+                                placeholder: "名前",
+                                value: "{name}",
+                                onchange: move |e| name.set(e.value()),
+                            }
+                        }
+                        td {
+                            button {
+                                onclick: handle_add,
+                                "追加"
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
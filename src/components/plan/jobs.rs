@@ -1,3 +1,6 @@
+use super::account::PlanAccountSelect;
+use super::recurrence::RecurrenceRuleSelect;
+use crate::finance::api::detail::AccountSchema;
 use crate::finance::api::plan::*;
 use dioxus::prelude::*;
 
@@ -6,8 +9,41 @@ pub fn PartTimeJobIncomes(
     year: Signal<i32>,
     month: Signal<u32>,
     part_time_job_incomes: Signal<Vec<PartTimeJobIncomeSchema>>,
-    handle_edit_part_time_job: Callback<(u64, String, String, String, String)>,
+    accounts: Signal<Vec<AccountSchema>>,
+    handle_edit_part_time_job: Callback<PartTimeJobIncomeInput>,
+    handle_add_part_time_job: Callback<PartTimeJobInput>,
+    error: Signal<Option<String>>,
 ) -> Element {
+    let mut name = use_signal(|| "".to_string());
+    let mut hourly_wage = use_signal(|| "".to_string());
+    let mut month_offset = use_signal(|| 1u32);
+    let mut payment_timing = use_signal(|| RecurrenceRule::monthly(MonthDay::Last));
+    let mut missing_day = use_signal(|| MissingDayPolicy::Clamp);
+    let mut business_day_roll = use_signal(|| BusinessDayRoll::None);
+    let mut account_id = use_signal(|| None);
+
+    let handle_add = move |_| {
+        handle_add_part_time_job(PartTimeJobInput {
+            name: name(),
+            hourly_wage: hourly_wage(),
+            month_offset: month_offset(),
+            payment_timing: payment_timing(),
+            missing_day: missing_day(),
+            business_day_roll: business_day_roll(),
+            account_id: account_id(),
+        });
+        // 入力が受け付けられなかった場合は入力を残す
+        if error().is_none() {
+            name.set("".to_string());
+            hourly_wage.set("".to_string());
+            month_offset.set(1);
+            payment_timing.set(RecurrenceRule::monthly(MonthDay::Last));
+            missing_day.set(MissingDayPolicy::Clamp);
+            business_day_roll.set(BusinessDayRoll::None);
+            account_id.set(None);
+        }
+    };
+
     rsx! {
         div {
             id: "job-list",
@@ -31,6 +67,7 @@ pub fn PartTimeJobIncomes(
                 tbody {
                     for i in 0..part_time_job_incomes().len() {
                         tr {
+                            id: "part-time-job-{part_time_job_incomes()[i].part_time_job_id}-{part_time_job_incomes()[i].payment_date}",
                             td { "{part_time_job_incomes()[i].name}" }
                             td {
                                 input {
@@ -38,13 +75,14 @@ pub fn PartTimeJobIncomes(
                                     value: part_time_job_incomes()[i].hourly_wage.to_string(),
                                     onchange: move |e| {
                                         let job = &part_time_job_incomes()[i];
-                                        handle_edit_part_time_job((
-                                            job.id,
-                                            job.name.clone(),
-                                            e.value(),
-                                            job.hour.to_string(),
-                                            job.payment_date.clone(),
-                                        ))
+                                        handle_edit_part_time_job(PartTimeJobIncomeInput {
+                                            id: job.id,
+                                            part_time_job_id: job.part_time_job_id,
+                                            name: job.name.clone(),
+                                            hourly_wage: e.value(),
+                                            hour: job.hour.to_string(),
+                                            payment_date: job.payment_date.clone(),
+                                        })
                                     },
                                 }
                             }
@@ -54,13 +92,14 @@ pub fn PartTimeJobIncomes(
                                     value: part_time_job_incomes()[i].hour.to_string(),
                                     onchange: move |e| {
                                         let job = &part_time_job_incomes()[i];
-                                        handle_edit_part_time_job((
-                                            job.id,
-                                            job.name.clone(),
-                                            job.hourly_wage.to_string(),
-                                            e.value(),
-                                            job.payment_date.clone(),
-                                        ))
+                                        handle_edit_part_time_job(PartTimeJobIncomeInput {
+                                            id: job.id,
+                                            part_time_job_id: job.part_time_job_id,
+                                            name: job.name.clone(),
+                                            hourly_wage: job.hourly_wage.to_string(),
+                                            hour: e.value(),
+                                            payment_date: job.payment_date.clone(),
+                                        })
                                     },
                                 }
                             }
@@ -71,6 +110,91 @@ pub fn PartTimeJobIncomes(
                             }
                         }
                     }
+                    // 表示している月から働き始めるアルバイトを追加する
+                    tr {
+                        td {
+                            input {
+                                type: "text",
+                                placeholder: "名前",
+                                value: "{name}",
+                                onchange: move |e| name.set(e.value()),
+                            }
+                        }
+                        td {
+                            input {
+                                type: "number",
+                                placeholder: "時給",
+                                value: "{hourly_wage}",
+                                onchange: move |e| hourly_wage.set(e.value()),
+                            }
+                        }
+                        td {}
+                        td {
+                            select {
+                                onchange: move |e| month_offset.set(e.value().parse().unwrap_or(1)),
+                                for (offset, label) in [(0, "当月"), (1, "翌月"), (2, "翌々月")] {
+                                    option {
+                                        value: "{offset}",
+                                        selected: month_offset() == offset,
+                                        "{label}"
+                                    }
+                                }
+                            }
+                            RecurrenceRuleSelect {
+                                value: payment_timing(),
+                                handle_change: move |rule| payment_timing.set(rule),
+                            }
+                            // 31日や第5曜日のように、ない月がある日のときだけ選ぶ
+                            if payment_timing().can_miss_day() {
+                                select {
+                                    onchange: move |e| {
+                                        if let Some(policy) = MissingDayPolicy::ALL.into_iter().find(|p| p.label() == e.value()) {
+                                            missing_day.set(policy);
+                                        }
+                                    },
+                                    for policy in MissingDayPolicy::ALL {
+                                        option {
+                                            value: policy.label(),
+                                            selected: missing_day() == policy,
+                                            "{policy.label()}"
+                                        }
+                                    }
+                                }
+                            }
+                            select {
+                                onchange: move |e| {
+                                    if let Some(roll) = BusinessDayRoll::ALL.into_iter().find(|r| r.label() == e.value()) {
+                                        business_day_roll.set(roll);
+                                    }
+                                },
+                                for roll in BusinessDayRoll::ALL {
+                                    option {
+                                        value: roll.label(),
+                                        selected: business_day_roll() == roll,
+                                        "{roll.label()}"
+                                    }
+                                }
+                            }
+                            // 振込先
+                            PlanAccountSelect {
+                                accounts,
+                                value: account_id(),
+                                handle_change: move |id| account_id.set(id),
+                            }
+                        }
+                        td {
+                            button {
+                                onclick: handle_add,
+                                "追加"
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
                 }
             }
         }
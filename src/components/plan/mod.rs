@@ -1,5 +1,13 @@
+pub mod account;
 pub mod jobs;
 pub mod combined_list;
+pub mod monthly_template;
+pub mod recurrence;
+pub mod holiday;
+pub mod scenario;
 
 pub use jobs::*;
-pub use combined_list::*;
\ No newline at end of file
+pub use combined_list::*;
+pub use monthly_template::*;
+pub use holiday::*;
+pub use scenario::*;
@@ -0,0 +1,183 @@
+use super::account::PlanAccountSelect;
+use super::recurrence::RecurrenceRuleSelect;
+use crate::finance::api::detail::AccountSchema;
+use crate::finance::api::plan::{
+    BusinessDayRoll, MissingDayPolicy, MonthDay, MonthlyTemplateInput, MonthlyTemplateSchema, RecurrenceRule,
+};
+use dioxus::prelude::*;
+
+fn account_label(accounts: &[AccountSchema], account_id: Option<u64>) -> String {
+    account_id
+        .and_then(|id| accounts.iter().find(|account| account.id == id))
+        .map_or_else(|| "既定の口座".to_string(), |account| account.name.clone())
+}
+
+// 毎月の固定収入・固定支出の一覧と追加
+#[component]
+pub fn MonthlyTemplates(
+    title: String,
+    // 行の要素の id の前につける
+    anchor_prefix: String,
+    templates: Signal<Vec<MonthlyTemplateSchema>>,
+    accounts: Signal<Vec<AccountSchema>>,
+    handle_add: Callback<MonthlyTemplateInput>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut name = use_signal(|| "".to_string());
+    let mut amount = use_signal(|| "".to_string());
+    let mut payment_timing = use_signal(|| RecurrenceRule::monthly(MonthDay::Last));
+    let mut missing_day = use_signal(|| MissingDayPolicy::Clamp);
+    let mut business_day_roll = use_signal(|| BusinessDayRoll::None);
+    let mut start_date = use_signal(|| "".to_string());
+    let mut end_date = use_signal(|| "".to_string());
+    let mut account_id = use_signal(|| None);
+
+    let handle_add = move |_| {
+        handle_add(MonthlyTemplateInput {
+            name: name(),
+            amount: amount(),
+            payment_timing: payment_timing(),
+            missing_day: missing_day(),
+            business_day_roll: business_day_roll(),
+            start_date: start_date(),
+            end_date: end_date(),
+            account_id: account_id(),
+        });
+        // 入力が受け付けられなかった場合は入力を残す
+        if error().is_none() {
+            name.set("".to_string());
+            amount.set("".to_string());
+            payment_timing.set(RecurrenceRule::monthly(MonthDay::Last));
+            missing_day.set(MissingDayPolicy::Clamp);
+            business_day_roll.set(BusinessDayRoll::None);
+            start_date.set("".to_string());
+            end_date.set("".to_string());
+            account_id.set(None);
+        }
+    };
+
+    rsx! {
+        div {
+            class: "monthly-template-list",
+            h3 { "{title}" }
+            table {
+                thead {
+                    tr {
+                        th { "名前" }
+                        th { "支払日" }
+                        th { "休日の場合" }
+                        th { "期間" }
+                        th { "口座" }
+                        th { "金額" }
+                        th { "" }
+                    }
+                }
+                tbody {
+                    for template in templates() {
+                        tr {
+                            id: "{anchor_prefix}-{template.id}",
+                            td { "{template.name}" }
+                            td { "{template.payment_timing}" }
+                            td { "{template.business_day_roll}" }
+                            td { "{template.start_date} 〜 {template.end_date.clone().unwrap_or_default()}" }
+                            td { "{account_label(&accounts(), template.account_id)}" }
+                            td {
+                                class: "amount",
+                                "{template.amount}"
+                            }
+                            td {}
+                        }
+                    }
+                    tr {
+                        td {
+                            input {
+                                type: "text",
+                                placeholder: "名前",
+                                value: "{name}",
+                                onchange: move |e| name.set(e.value()),
+                            }
+                        }
+                        td {
+                            RecurrenceRuleSelect {
+                                value: payment_timing(),
+                                handle_change: move |rule| payment_timing.set(rule),
+                            }
+                            // 31日や第5曜日のように、ない月がある日のときだけ選ぶ
+                            if payment_timing().can_miss_day() {
+                                select {
+                                    onchange: move |e| {
+                                        if let Some(policy) = MissingDayPolicy::ALL.into_iter().find(|p| p.label() == e.value()) {
+                                            missing_day.set(policy);
+                                        }
+                                    },
+                                    for policy in MissingDayPolicy::ALL {
+                                        option {
+                                            value: policy.label(),
+                                            selected: missing_day() == policy,
+                                            "{policy.label()}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        td {
+                            select {
+                                onchange: move |e| {
+                                    if let Some(roll) = BusinessDayRoll::ALL.into_iter().find(|r| r.label() == e.value()) {
+                                        business_day_roll.set(roll);
+                                    }
+                                },
+                                for roll in BusinessDayRoll::ALL {
+                                    option {
+                                        value: roll.label(),
+                                        selected: business_day_roll() == roll,
+                                        "{roll.label()}"
+                                    }
+                                }
+                            }
+                        }
+                        td {
+                            input {
+                                type: "date",
+                                value: "{start_date}",
+                                onchange: move |e| start_date.set(e.value()),
+                            }
+                            input {
+                                type: "date",
+                                value: "{end_date}",
+                                onchange: move |e| end_date.set(e.value()),
+                            }
+                        }
+                        td {
+                            PlanAccountSelect {
+                                accounts,
+                                value: account_id(),
+                                handle_change: move |id| account_id.set(id),
+                            }
+                        }
+                        td {
+                            input {
+                                type: "text",
+                                placeholder: "金額",
+                                value: "{amount}",
+                                onchange: move |e| amount.set(e.value()),
+                            }
+                        }
+                        td {
+                            button {
+                                onclick: handle_add,
+                                "追加"
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::finance::api::plan::{weekday_label, MonthDay, RecurrenceRule, WEEKDAYS};
+use chrono::Weekday;
+use dioxus::prelude::*;
+
+// 繰り返しの規則の入力
+// 毎週・毎月・毎年を選び、それぞれの間隔や日を指定する
+#[component]
+pub fn RecurrenceRuleSelect(value: RecurrenceRule, handle_change: Callback<RecurrenceRule>) -> Element {
+    let frequency = match value {
+        RecurrenceRule::Weekly { .. } => "weekly",
+        RecurrenceRule::Monthly { .. } => "monthly",
+        RecurrenceRule::Yearly { .. } => "yearly",
+    };
+
+    rsx! {
+        span {
+            class: "recurrence-rule",
+            select {
+                onchange: move |e| {
+                    handle_change(match e.value().as_str() {
+                        "weekly" => RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Mon },
+                        "yearly" => RecurrenceRule::Yearly { month: 1, day: MonthDay::Day(1) },
+                        _ => RecurrenceRule::monthly(MonthDay::Last),
+                    })
+                },
+                option { value: "weekly", selected: frequency == "weekly", "毎週" }
+                option { value: "monthly", selected: frequency == "monthly", "毎月" }
+                option { value: "yearly", selected: frequency == "yearly", "毎年" }
+            }
+            match value {
+                RecurrenceRule::Weekly { interval, weekday } => rsx! {
+                    input {
+                        type: "number",
+                        min: "1",
+                        value: "{interval}",
+                        onchange: move |e| {
+                            if let Ok(interval) = e.value().parse() {
+                                handle_change(RecurrenceRule::Weekly { interval, weekday });
+                            }
+                        },
+                    }
+                    "週ごと"
+                    WeekdaySelect {
+                        value: weekday,
+                        handle_change: move |weekday| handle_change(RecurrenceRule::Weekly { interval, weekday }),
+                    }
+                },
+                RecurrenceRule::Monthly { interval, day } => rsx! {
+                    input {
+                        type: "number",
+                        min: "1",
+                        value: "{interval}",
+                        onchange: move |e| {
+                            if let Ok(interval) = e.value().parse() {
+                                handle_change(RecurrenceRule::Monthly { interval, day });
+                            }
+                        },
+                    }
+                    "か月ごと"
+                    MonthDaySelect {
+                        value: day,
+                        handle_change: move |day| handle_change(RecurrenceRule::Monthly { interval, day }),
+                    }
+                },
+                RecurrenceRule::Yearly { month, day } => rsx! {
+                    input {
+                        type: "number",
+                        min: "1",
+                        max: "12",
+                        value: "{month}",
+                        onchange: move |e| {
+                            if let Ok(month) = e.value().parse() {
+                                handle_change(RecurrenceRule::Yearly { month, day });
+                            }
+                        },
+                    }
+                    "月"
+                    MonthDaySelect {
+                        value: day,
+                        handle_change: move |day| handle_change(RecurrenceRule::Yearly { month, day }),
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn MonthDaySelect(value: MonthDay, handle_change: Callback<MonthDay>) -> Element {
+    let kind = match value {
+        MonthDay::Day(_) => "day",
+        MonthDay::Last => "last",
+        MonthDay::NthWeekday(..) => "nth",
+        MonthDay::LastWeekday(_) => "last_weekday",
+    };
+
+    rsx! {
+        select {
+            onchange: move |e| {
+                handle_change(match e.value().as_str() {
+                    "day" => MonthDay::Day(1),
+                    "nth" => MonthDay::NthWeekday(1, Weekday::Mon),
+                    "last_weekday" => MonthDay::LastWeekday(Weekday::Mon),
+                    _ => MonthDay::Last,
+                })
+            },
+            option { value: "day", selected: kind == "day", "日付" }
+            option { value: "last", selected: kind == "last", "末日" }
+            option { value: "nth", selected: kind == "nth", "第N曜日" }
+            option { value: "last_weekday", selected: kind == "last_weekday", "最終曜日" }
+        }
+        match value {
+            MonthDay::Day(day) => rsx! {
+                input {
+                    type: "number",
+                    min: "1",
+                    max: "31",
+                    value: "{day}",
+                    onchange: move |e| {
+                        if let Ok(day) = e.value().parse() {
+                            handle_change(MonthDay::Day(day));
+                        }
+                    },
+                }
+                "日"
+            },
+            MonthDay::Last => rsx! {},
+            MonthDay::NthWeekday(n, weekday) => rsx! {
+                "第"
+                input {
+                    type: "number",
+                    min: "1",
+                    max: "5",
+                    value: "{n}",
+                    onchange: move |e| {
+                        if let Ok(n) = e.value().parse() {
+                            handle_change(MonthDay::NthWeekday(n, weekday));
+                        }
+                    },
+                }
+                WeekdaySelect {
+                    value: weekday,
+                    handle_change: move |weekday| handle_change(MonthDay::NthWeekday(n, weekday)),
+                }
+            },
+            MonthDay::LastWeekday(weekday) => rsx! {
+                WeekdaySelect {
+                    value: weekday,
+                    handle_change: move |weekday| handle_change(MonthDay::LastWeekday(weekday)),
+                }
+            },
+        }
+    }
+}
+
+#[component]
+fn WeekdaySelect(value: Weekday, handle_change: Callback<Weekday>) -> Element {
+    rsx! {
+        select {
+            onchange: move |e| {
+                if let Some(weekday) = WEEKDAYS.into_iter().find(|w| weekday_label(*w) == e.value()) {
+                    handle_change(weekday);
+                }
+            },
+            for weekday in WEEKDAYS {
+                option {
+                    value: weekday_label(weekday),
+                    selected: value == weekday,
+                    "{weekday_label(weekday)}曜日"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use super::recurrence::RecurrenceRuleSelect;
+use crate::finance::api::plan::{
+    BusinessDayRoll, MissingDayPolicy, MonthDay, MonthlyTemplateSchema, PartTimeJobIncomeSchema, RecurrenceRule,
+};
+use crate::finance::api::scenario::{ScenarioChangeInput, ScenarioChangeType, ScenarioSchema, TemplateKind};
+use dioxus::prelude::*;
+
+// 計画に変更を重ねて試す「もしも」のシナリオの一覧と編集
+#[component]
+pub fn ScenarioList(
+    scenarios: Signal<Vec<ScenarioSchema>>,
+    income_templates: Signal<Vec<MonthlyTemplateSchema>>,
+    outcome_templates: Signal<Vec<MonthlyTemplateSchema>>,
+    part_time_job_incomes: Signal<Vec<PartTimeJobIncomeSchema>>,
+    handle_add: Callback<String>,
+    handle_delete: Callback<u64>,
+    handle_add_change: Callback<(u64, ScenarioChangeInput)>,
+    handle_delete_change: Callback<u64>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut name = use_signal(|| "".to_string());
+
+    let handle_add = move |_| {
+        handle_add(name());
+        if error().is_none() {
+            name.set("".to_string());
+        }
+    };
+
+    rsx! {
+        div {
+            id: "scenario-list",
+            h3 { "シナリオ" }
+            p { class: "note", "計画は変えずに、変更を加えた場合の残高をホームで比べられます" }
+            for scenario in scenarios() {
+                div {
+                    key: "{scenario.id}",
+                    class: "scenario",
+                    h4 {
+                        "{scenario.name}"
+                        button {
+                            onclick: move |_| handle_delete(scenario.id),
+                            "削除"
+                        }
+                    }
+                    table {
+                        tbody {
+                            for change in scenario.changes {
+                                tr {
+                                    id: "scenario-change-{change.id}",
+                                    td { "{change.start_date} から" }
+                                    td { "{change.description}" }
+                                    td {
+                                        button {
+                                            onclick: move |_| handle_delete_change(change.id),
+                                            "削除"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    ScenarioChangeForm {
+                        income_templates,
+                        outcome_templates,
+                        part_time_job_incomes,
+                        handle_add: move |input| handle_add_change((scenario.id, input)),
+                        error,
+                    }
+                }
+            }
+            div {
+                input {
+                    type: "text",
+                    placeholder: "シナリオの名前",
+                    value: "{name}",
+                    onchange: move |e| name.set(e.value()),
+                }
+                button {
+                    onclick: handle_add,
+                    "追加"
+                }
+            }
+            if let Some(message) = error() {
+                p {
+                    class: "error",
+                    "{message}"
+                }
+            }
+        }
+    }
+}
+
+// シナリオに加える変更の入力
+#[component]
+fn ScenarioChangeForm(
+    income_templates: Signal<Vec<MonthlyTemplateSchema>>,
+    outcome_templates: Signal<Vec<MonthlyTemplateSchema>>,
+    part_time_job_incomes: Signal<Vec<PartTimeJobIncomeSchema>>,
+    handle_add: Callback<ScenarioChangeInput>,
+    error: Signal<Option<String>>,
+) -> Element {
+    let mut change_type = use_signal(|| ScenarioChangeType::AdjustMonthlyOutcome);
+    let mut target_id = use_signal(|| None::<u64>);
+    let mut name = use_signal(|| "".to_string());
+    let mut amount = use_signal(|| "".to_string());
+    let mut hour = use_signal(|| "".to_string());
+    let mut payment_timing = use_signal(|| RecurrenceRule::monthly(MonthDay::Last));
+    let mut missing_day = use_signal(|| MissingDayPolicy::Clamp);
+    let mut business_day_roll = use_signal(|| BusinessDayRoll::None);
+    let mut start_date = use_signal(|| "".to_string());
+
+    // 選んだ種類で変更できるもの (ID と名前)
+    let targets = move || -> Vec<(u64, String)> {
+        if change_type().is_part_time_job() {
+            return part_time_job_incomes()
+                .into_iter()
+                .map(|income| (income.part_time_job_id, income.name))
+                .collect();
+        }
+        let templates = match change_type().template_kind() {
+            Some(TemplateKind::Income) => income_templates(),
+            _ => outcome_templates(),
+        };
+        templates.into_iter().map(|template| (template.id, template.name)).collect()
+    };
+
+    let handle_add = move |_| {
+        // 対象を選び直していなければ先頭のものを使う
+        let target_id = target_id()
+            .filter(|id| targets().iter().any(|(target, _)| target == id))
+            .or_else(|| targets().first().map(|(id, _)| *id));
+        handle_add(ScenarioChangeInput {
+            change_type: change_type(),
+            target_id: if change_type().needs_target() { target_id } else { None },
+            name: name(),
+            amount: amount(),
+            hour: hour(),
+            payment_timing: payment_timing(),
+            missing_day: missing_day(),
+            business_day_roll: business_day_roll(),
+            start_date: start_date(),
+        });
+        if error().is_none() {
+            name.set("".to_string());
+            amount.set("".to_string());
+            hour.set("".to_string());
+        }
+    };
+
+    rsx! {
+        div {
+            class: "scenario-change-form",
+            select {
+                onchange: move |e| {
+                    if let Some(selected) = ScenarioChangeType::ALL.into_iter().find(|t| t.label() == e.value()) {
+                        change_type.set(selected);
+                        target_id.set(None);
+                    }
+                },
+                for t in ScenarioChangeType::ALL {
+                    option {
+                        value: t.label(),
+                        selected: change_type() == t,
+                        "{t.label()}"
+                    }
+                }
+            }
+            if change_type().needs_target() {
+                select {
+                    onchange: move |e| target_id.set(e.value().parse().ok()),
+                    for (id, target_name) in targets() {
+                        option {
+                            value: "{id}",
+                            selected: target_id() == Some(id),
+                            "{target_name}"
+                        }
+                    }
+                }
+            }
+            if change_type().needs_name() {
+                input {
+                    type: "text",
+                    placeholder: "名前",
+                    value: "{name}",
+                    onchange: move |e| name.set(e.value()),
+                }
+            }
+            if change_type().needs_payment_timing() {
+                RecurrenceRuleSelect {
+                    value: payment_timing(),
+                    handle_change: move |rule| payment_timing.set(rule),
+                }
+                // 31日や第5曜日のように、ない月がある日のときだけ選ぶ
+                if payment_timing().can_miss_day() {
+                    select {
+                        onchange: move |e| {
+                            if let Some(policy) = MissingDayPolicy::ALL.into_iter().find(|p| p.label() == e.value()) {
+                                missing_day.set(policy);
+                            }
+                        },
+                        for policy in MissingDayPolicy::ALL {
+                            option {
+                                value: policy.label(),
+                                selected: missing_day() == policy,
+                                "{policy.label()}"
+                            }
+                        }
+                    }
+                }
+                select {
+                    onchange: move |e| {
+                        if let Some(roll) = BusinessDayRoll::ALL.into_iter().find(|r| r.label() == e.value()) {
+                            business_day_roll.set(roll);
+                        }
+                    },
+                    for roll in BusinessDayRoll::ALL {
+                        option {
+                            value: roll.label(),
+                            selected: business_day_roll() == roll,
+                            "{roll.label()}"
+                        }
+                    }
+                }
+            }
+            if change_type().needs_amount() {
+                input {
+                    type: "text",
+                    class: "amount",
+                    placeholder: match change_type() {
+                        ScenarioChangeType::ChangePartTimeJob => "時給",
+                        ScenarioChangeType::AdjustMonthlyIncome | ScenarioChangeType::AdjustMonthlyOutcome => "増減する金額",
+                        _ => "金額",
+                    },
+                    value: "{amount}",
+                    onchange: move |e| amount.set(e.value()),
+                }
+            }
+            if change_type() == ScenarioChangeType::ChangePartTimeJob {
+                input {
+                    type: "text",
+                    class: "amount",
+                    placeholder: "1 回の入金分の時間",
+                    value: "{hour}",
+                    onchange: move |e| hour.set(e.value()),
+                }
+            }
+            input {
+                type: "date",
+                value: "{start_date}",
+                onchange: move |e| start_date.set(e.value()),
+            }
+            button {
+                onclick: handle_add,
+                "変更を追加"
+            }
+        }
+    }
+}
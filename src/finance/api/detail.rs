@@ -1,21 +1,38 @@
-use crate::finance::detail::update_saving;
-
 use super::detail_service;
-use super::detail_service::{
-    get_adjustment_repo, get_income_repo, get_outcome_repo, get_saving_repo,
-    Income, IncomeRepo, Outcome, OutcomeRepo, SavingRepo,
-};
-use crate::finance::setting::get_opening_and_closing_date;
-use chrono::{DateTime, Datelike, Local, NaiveDate};
+use super::setting::get_accounting_period;
+use super::detail_service::{Account, Budget, BusinessCalendar, CardCycle, BudgetWarning, Category, Income, Outcome, Transfer};
+use crate::finance::context::RepoContext;
+use crate::finance::error::FinanceError;
+use crate::util::get_prev_ym;
+use chrono::{DateTime, Local, NaiveDate};
 use dioxus::logger::tracing;
 use rust_decimal::prelude::*;
 
+pub use super::detail_service::{AccountKind, BudgetTarget, LedgerFilter};
+
+// 収入・支出の入力フォームの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntryInput {
+    pub name: String,
+    pub amount: String,
+    pub date: String,
+    pub account_id: u64,
+    pub category_id: Option<u64>,
+    // カンマ区切り
+    pub tags: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct IncomeSchema {
     pub id: u64,
     pub name: String,
     pub amount: Decimal,
     pub date: String,
+    pub account_id: u64,
+    pub account: String,
+    pub category_id: Option<u64>,
+    pub category: String,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,18 +41,77 @@ pub struct OutcomeSchema {
     pub name: String,
     pub amount: Decimal,
     pub date: String,
+    pub account_id: u64,
+    pub account: String,
+    pub category_id: Option<u64>,
+    pub category: String,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
-pub struct SavingSchema {
-    pub year: i32,
-    pub month: u32,
+pub struct TransferSchema {
+    pub id: u64,
+    pub from_account: String,
+    pub to_account: String,
     pub amount: Decimal,
+    pub date: String,
+    pub memo: String,
 }
 
-pub fn get_incomes(year: i32, month: u32) -> Vec<IncomeSchema> {
-    let repo = get_income_repo();
-    let (opening_date, closing_date) = match get_opening_and_closing_date(year, month) {
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSchema {
+    pub id: u64,
+    pub name: String,
+    pub kind: AccountKind,
+}
+
+// 口座の追加フォームの値
+// 締め日・引き落とし日・支払い口座はクレジットカードのときだけ使う
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountInput {
+    pub name: String,
+    pub kind: AccountKind,
+    pub closing_day: String,
+    pub payment_day: String,
+    pub payment_account_id: u64,
+}
+
+// 口座ごとの先月末と今月の残高
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSavingSchema {
+    pub account_id: u64,
+    pub name: String,
+    pub kind: AccountKind,
+    // 15日締め 翌月10日払い
+    pub card_cycle: Option<String>,
+    pub last_month: Decimal,
+    pub current_month: Decimal,
+}
+
+// その月に締めるクレジットカードの請求
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementSchema {
+    pub account_id: u64,
+    pub account: String,
+    pub payment_account: String,
+    pub opening_date: String,
+    pub closing_date: String,
+    pub payment_date: String,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategorySchema {
+    pub id: u64,
+    pub name: String,
+    pub parent_id: Option<u64>,
+    // 食費 > 外食
+    pub path: String,
+}
+
+pub fn get_incomes(ctx: &RepoContext, year: i32, month: u32, filter: &LedgerFilter) -> Vec<IncomeSchema> {
+    let repo = ctx.income.as_ref();
+    let (opening_date, closing_date) = match get_accounting_period(ctx).get_opening_and_closing_date(year, month) {
         Ok(x) => x,
         Err(e) => {
             tracing::error!("Failed to get opening and closing date: {}", e);
@@ -49,174 +125,68 @@ pub fn get_incomes(year: i32, month: u32) -> Vec<IncomeSchema> {
             return vec![];
         }
     };
+    let categories = list_categories(ctx);
+    let accounts = list_accounts(ctx);
     incomes
         .into_iter()
+        .filter(|income| filter.matches(income.account_id, income.category_id, &income.tags, &categories))
         .map(|income| IncomeSchema {
             id: income.id.unwrap(),
             name: income.name,
             amount: income.amount,
             date: format_date(&income.date),
+            account_id: income.account_id,
+            account: get_account_name(income.account_id, &accounts),
+            category_id: income.category_id,
+            category: income.category_id
+                .map(|id| detail_service::get_category_path(id, &categories))
+                .unwrap_or_default(),
+            tags: income.tags,
         })
         .collect()
 }
 
-pub fn store_income(name: String, amount: String, date: String) {
-    let repo = get_income_repo();
-    let parsed_date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
-        Ok(parsed_date) => parsed_date,
-        Err(e) => {
-            tracing::error!("Failed to parse date: {}", e);
-            return;
-        }
-    };
-    let income = match Income::try_new(
-        name,
-        amount,
-        parsed_date.year(),
-        parsed_date.month0() + 1,
-        parsed_date.day0() + 1,
-    ) {
-        Ok(income) => income,
-        Err(e) => {
-            tracing::error!("Failed to create income: {}", e);
-            return;
-        }
-    };
-    match repo.store(income.clone()) {
-        Ok(_) => {
-            let saving_repo = get_saving_repo();
-            match update_saving(
-                (income.date.year(), income.date.month()),
-                income.amount,
-                &saving_repo,
-            ) {
-                Ok(_) => (),
-                Err(e) => {
-                    tracing::error!("Failed to update saving: {}", e);
-                    return;
-                }
-            };
-        }
-        Err(e) => {
-            tracing::error!("Failed to store income: {}", e);
-            return;
-        }
-    }
+pub fn store_income(ctx: &RepoContext, input: LedgerEntryInput) -> Result<(), FinanceError> {
+    ensure_account_exists(ctx, input.account_id)?;
+    let income = Income::try_new(
+        input.name,
+        input.amount,
+        parse_date(&input.date)?,
+        input.account_id,
+        input.category_id,
+        detail_service::parse_tags(&input.tags),
+    )?;
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::store_income(income, &period, ctx.income.as_ref(), ctx.saving.as_ref()))
+        .map(|_| ())
+        .inspect_err(|e| tracing::error!("Failed to store income: {}", e))
 }
 
-pub fn delete_income(id: u64) {
-    let repo = get_income_repo();
-    match repo.get_by_id(id) {
-        Ok(None) => {
-            return;
-        }
-        Ok(Some(prev)) => match repo.delete_by_id(id) {
-            Ok(_) => {
-                let saving_repo = get_saving_repo();
-                match update_saving(
-                    (prev.date.year(), prev.date.month()),
-                    -prev.amount,
-                    &saving_repo,
-                ) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        tracing::error!("Failed to update saving: {}", e);
-                        return;
-                    }
-                };
-            }
-            Err(_) => return,
-        },
-        Err(_) => return,
-    }
+pub fn delete_income(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::delete_income(id, &period, ctx.income.as_ref(), ctx.saving.as_ref()))
+        .inspect_err(|e| tracing::error!("Failed to delete income: {}", e))
 }
 
-pub fn update_income(id: u64, name: String, amount: String, date: String) {
-    let repo = get_income_repo();
-    match repo.get_by_id(id) {
-        Ok(None) => return,
-        Ok(Some(prev)) => {
-            let parsed_date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
-                Ok(parsed_date) => parsed_date,
-                Err(e) => {
-                    tracing::error!("Failed to parse date: {}", e);
-                    return;
-                }
-            };
-            let new_income = match Income::try_update(
-                id,
-                name,
-                amount,
-                parsed_date.year(),
-                parsed_date.month0() + 1,
-                parsed_date.day0() + 1,
-            ) {
-                Ok(income) => income,
-                Err(e) => {
-                    tracing::error!("Failed to update income: {}", e);
-                    return;
-                }
-            };
-            match repo.update(new_income.clone()) {
-                Ok(_) => {
-                    let saving_repo = get_saving_repo();
-                    if prev.date.year() == new_income.date.year()
-                    && prev.date.month() == new_income.date.month()
-                    {
-                        let amount_diff = new_income.amount - prev.amount;
-                        match update_saving(
-                            (new_income.date.year(), new_income.date.month()),
-                            amount_diff,
-                            &saving_repo,
-                        ) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                tracing::error!("Failed to update saving: {}", e);
-                                return;
-                            }
-                        };
-                        return;
-                    } else {
-                        match update_saving(
-                            (prev.date.year(), prev.date.month()),
-                            -prev.amount,
-                            &saving_repo,
-                        ) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                tracing::error!("Failed to update saving: {}", e);
-                                return;
-                            }
-                        };
-                        match update_saving(
-                            (new_income.date.year(), new_income.date.month()),
-                            new_income.amount,
-                            &saving_repo,
-                        ) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                tracing::error!("Failed to update saving: {}", e);
-                                return;
-                            }
-                        };
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to update income: {}", e);
-                    return;
-                }
-            }
-        }
-        Err(e) => {
-            tracing::error!("Failed to get income: {}", e);
-            return;
-        }
-    }
+pub fn update_income(ctx: &RepoContext, id: u64, input: LedgerEntryInput) -> Result<(), FinanceError> {
+    ensure_account_exists(ctx, input.account_id)?;
+    let new_income = Income::try_update(
+        id,
+        input.name,
+        input.amount,
+        parse_date(&input.date)?,
+        input.account_id,
+        input.category_id,
+        detail_service::parse_tags(&input.tags),
+    )?;
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::update_income(new_income, &period, ctx.income.as_ref(), ctx.saving.as_ref()))
+        .inspect_err(|e| tracing::error!("Failed to update income: {}", e))
 }
 
-pub fn get_outcomes(year: i32, month: u32) -> Vec<OutcomeSchema> {
-    let repo = get_outcome_repo();
-    let (opening_date, closing_date) = match get_opening_and_closing_date(year, month) {
+pub fn get_outcomes(ctx: &RepoContext, year: i32, month: u32, filter: &LedgerFilter) -> Vec<OutcomeSchema> {
+    let repo = ctx.outcome.as_ref();
+    let (opening_date, closing_date) = match get_accounting_period(ctx).get_opening_and_closing_date(year, month) {
         Ok(x) => x,
         Err(e) => {
             tracing::error!("Failed to get opening and closing date: {}", e);
@@ -227,228 +197,377 @@ pub fn get_outcomes(year: i32, month: u32) -> Vec<OutcomeSchema> {
         Ok(outcomes) => outcomes,
         Err(e) => {
             tracing::error!("Failed to get outcomes: {}", e);
-            vec![]
+            return vec![];
         }
     };
+    let categories = list_categories(ctx);
+    let accounts = list_accounts(ctx);
     outcomes
         .into_iter()
+        .filter(|outcome| filter.matches(outcome.account_id, outcome.category_id, &outcome.tags, &categories))
         .map(|outcome| OutcomeSchema {
             id: outcome.id.unwrap(),
             name: outcome.name,
             amount: outcome.amount,
             date: format_date(&outcome.date),
+            account_id: outcome.account_id,
+            account: get_account_name(outcome.account_id, &accounts),
+            category_id: outcome.category_id,
+            category: outcome.category_id
+                .map(|id| detail_service::get_category_path(id, &categories))
+                .unwrap_or_default(),
+            tags: outcome.tags,
         })
         .collect()
 }
 
-pub fn store_outcome(name: String, amount: String, date: String) {
-    let repo = get_outcome_repo();
-    let parsed_date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
-        Ok(parsed_date) => parsed_date,
+pub fn store_outcome(ctx: &RepoContext, input: LedgerEntryInput) -> Result<(), FinanceError> {
+    ensure_account_exists(ctx, input.account_id)?;
+    let outcome = Outcome::try_new(
+        input.name,
+        input.amount,
+        parse_date(&input.date)?,
+        input.account_id,
+        input.category_id,
+        detail_service::parse_tags(&input.tags),
+    )?;
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::store_outcome(outcome, &period, ctx.outcome.as_ref(), ctx.saving.as_ref()))
+        .map(|_| ())
+        .inspect_err(|e| tracing::error!("Failed to store outcome: {}", e))
+}
+
+pub fn delete_outcome(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::delete_outcome(id, &period, ctx.outcome.as_ref(), ctx.saving.as_ref()))
+        .inspect_err(|e| tracing::error!("Failed to delete outcome: {}", e))
+}
+
+pub fn update_outcome(ctx: &RepoContext, id: u64, input: LedgerEntryInput) -> Result<(), FinanceError> {
+    ensure_account_exists(ctx, input.account_id)?;
+    let new_outcome = Outcome::try_update(
+        id,
+        input.name,
+        input.amount,
+        parse_date(&input.date)?,
+        input.account_id,
+        input.category_id,
+        detail_service::parse_tags(&input.tags),
+    )?;
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::update_outcome(new_outcome, &period, ctx.outcome.as_ref(), ctx.saving.as_ref()))
+        .inspect_err(|e| tracing::error!("Failed to update outcome: {}", e))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetStatusSchema {
+    pub id: u64,
+    pub label: String,
+    pub rollover: bool,
+    pub limit: Decimal,
+    pub actual: Decimal,
+    pub remaining: Decimal,
+    pub warning: Option<String>,
+}
+
+pub fn get_budget_statuses(ctx: &RepoContext, year: i32, month: u32) -> Vec<BudgetStatusSchema> {
+    let categories = list_categories(ctx);
+    let statuses = match detail_service::get_budget_statuses(
+        year,
+        month,
+        &Local::now(),
+        &get_accounting_period(ctx),
+        ctx.budget.as_ref(),
+        ctx.outcome.as_ref(),
+        &categories,
+    ) {
+        Ok(statuses) => statuses,
         Err(e) => {
-            tracing::error!("Failed to parse date: {}", e);
-            return;
+            tracing::error!("Failed to get budget statuses: {}", e);
+            return vec![];
         }
     };
-    let outcome = match Outcome::try_new(
-        name,
-        amount,
-        parsed_date.year(),
-        parsed_date.month0() + 1,
-        parsed_date.day0() + 1,
-    ) {
-        Ok(outcome) => outcome,
+    statuses
+        .into_iter()
+        .map(|status| BudgetStatusSchema {
+            id: status.budget.id.unwrap(),
+            label: status.budget.label(&categories),
+            rollover: status.budget.rollover,
+            limit: status.limit,
+            actual: status.actual,
+            remaining: status.remaining,
+            warning: status.warning.map(|warning| match warning {
+                BudgetWarning::Exceeded => format!("予算を {} 超えています", -status.remaining),
+                BudgetWarning::OverPace => "予算を超えるペースで使っています".to_string(),
+            }),
+        })
+        .collect()
+}
+
+// year, month から毎月適用する
+pub fn store_budget(
+    ctx: &RepoContext,
+    target: BudgetTarget,
+    amount: String,
+    rollover: bool,
+    year: i32,
+    month: u32,
+) -> Result<(), FinanceError> {
+    let budget = Budget::try_new(target, amount, rollover, (year, month))?;
+    ctx.budget
+        .store(budget)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store budget: {}", e))
+}
+
+pub fn delete_budget(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    ctx.budget
+        .delete_by_id(id)
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to delete budget: {}", e))
+}
+
+pub fn get_categories(ctx: &RepoContext) -> Vec<CategorySchema> {
+    let categories = list_categories(ctx);
+    let mut schemas: Vec<CategorySchema> = categories
+        .iter()
+        .map(|category| CategorySchema {
+            id: category.id.unwrap(),
+            name: category.name.clone(),
+            parent_id: category.parent_id,
+            path: detail_service::get_category_path(category.id.unwrap(), &categories),
+        })
+        .collect();
+    // 親の直後に子が並ぶようにする
+    schemas.sort_by(|a, b| a.path.cmp(&b.path));
+    schemas
+}
+
+pub fn store_category(ctx: &RepoContext, name: String, parent_id: Option<u64>) -> Result<(), FinanceError> {
+    let category = Category::try_new(name, parent_id)?;
+    detail_service::store_category(category, ctx.category.as_ref())
+        .map(|_| ())
+        .inspect_err(|e| tracing::error!("Failed to store category: {}", e))
+}
+
+pub fn create_adjustment(
+    ctx: &RepoContext,
+    account_id: u64,
+    saving_input: String,
+    year: i32,
+    month: u32,
+) -> Result<(), FinanceError> {
+    let saving_input = Decimal::from_str(&saving_input)
+        .map_err(|_| FinanceError::InvalidAmount(saving_input))?;
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| {
+        detail_service::create_adjustment(
+            saving_input,
+            (account_id, year, month),
+            &period,
+            ctx.income.as_ref(),
+            ctx.outcome.as_ref(),
+            ctx.saving.as_ref(),
+            ctx.adjustment.as_ref(),
+        )
+    })
+    .inspect_err(|e| tracing::error!("Failed to create adjustment: {}", e))
+}
+
+// 振替は口座の絞り込みだけに従う
+pub fn get_transfers(ctx: &RepoContext, year: i32, month: u32, filter: &LedgerFilter) -> Vec<TransferSchema> {
+    let (opening_date, closing_date) = match get_accounting_period(ctx).get_opening_and_closing_date(year, month) {
+        Ok(x) => x,
         Err(e) => {
-            tracing::error!("Failed to create outcome: {}", e);
-            return;
+            tracing::error!("Failed to get opening and closing date: {}", e);
+            return vec![];
         }
     };
-    match repo.store(outcome.clone()) {
-        Ok(_) => {
-            let saving_repo = get_saving_repo();
-            match update_saving(
-                (outcome.date.year(), outcome.date.month()),
-                -outcome.amount,
-                &saving_repo,
-            ) {
-                Ok(_) => (),
-                Err(e) => {
-                    tracing::error!("Failed to update saving: {}", e);
-                    return;
-                }
-            };
-        }
+    let transfers = match ctx.transfer.list(&opening_date, &closing_date) {
+        Ok(transfers) => transfers,
         Err(e) => {
-            tracing::error!("Failed to store outcome: {}", e);
-            return;
+            tracing::error!("Failed to get transfers: {}", e);
+            return vec![];
         }
-    }
+    };
+    let accounts = list_accounts(ctx);
+    transfers
+        .into_iter()
+        .filter(|transfer| {
+            filter.account_id.is_none_or(|account_id| {
+                transfer.from_account_id == account_id || transfer.to_account_id == account_id
+            })
+        })
+        .map(|transfer| TransferSchema {
+            id: transfer.id.unwrap(),
+            from_account: get_account_name(transfer.from_account_id, &accounts),
+            to_account: get_account_name(transfer.to_account_id, &accounts),
+            amount: transfer.amount,
+            date: format_date(&transfer.date),
+            memo: transfer.memo,
+        })
+        .collect()
 }
 
-pub fn delete_outcome(id: u64) {
-    let repo = get_outcome_repo();
-    match repo.get_by_id(id) {
-        Ok(None) => {
-            return;
-        }
-        Ok(Some(prev)) => match repo.delete_by_id(id) {
-            Ok(_) => {
-                let saving_repo = get_saving_repo();
-                match update_saving(
-                    (prev.date.year(), prev.date.month()),
-                    prev.amount,
-                    &saving_repo,
-                ) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        tracing::error!("Failed to update saving: {}", e);
-                        return;
-                    }
-                };
-            }
-            Err(_) => return,
-        },
-        Err(_) => return,
-    }
+pub fn store_transfer(
+    ctx: &RepoContext,
+    from_account_id: u64,
+    to_account_id: u64,
+    amount: String,
+    date: String,
+    memo: String,
+) -> Result<(), FinanceError> {
+    ensure_account_exists(ctx, from_account_id)?;
+    ensure_account_exists(ctx, to_account_id)?;
+    let transfer = Transfer::try_new(from_account_id, to_account_id, amount, parse_date(&date)?, memo)?;
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::store_transfer(transfer, &period, ctx.transfer.as_ref(), ctx.saving.as_ref()))
+        .map(|_| ())
+        .inspect_err(|e| tracing::error!("Failed to store transfer: {}", e))
+}
+
+pub fn delete_transfer(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    let period = get_accounting_period(ctx);
+    ctx.transaction(|| detail_service::delete_transfer(id, &period, ctx.transfer.as_ref(), ctx.saving.as_ref()))
+        .inspect_err(|e| tracing::error!("Failed to delete transfer: {}", e))
+}
+
+pub fn get_accounts(ctx: &RepoContext) -> Vec<AccountSchema> {
+    list_accounts(ctx)
+        .into_iter()
+        .map(|account| AccountSchema {
+            id: account.id.unwrap(),
+            name: account.name,
+            kind: account.kind,
+        })
+        .collect()
 }
 
-pub fn update_outcome(id: u64, name: String, amount: String, date: String) {
-    let repo = get_outcome_repo();
-    match repo.get_by_id(id) {
-        Ok(None) => return,
-        Ok(Some(prev)) => {
-            let parsed_date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
-                Ok(parsed_date) => parsed_date,
-                Err(e) => {
-                    tracing::error!("Failed to parse date: {}", e);
-                    return;
-                }
-            };
-            let new_outcome = match Outcome::try_update(
-                id,
-                name,
-                amount,
-                parsed_date.year(),
-                parsed_date.month0() + 1,
-                parsed_date.day0() + 1,
-            ) {
-                Ok(outcome) => outcome,
-                Err(e) => {
-                    tracing::error!("Failed to update outcome: {}", e);
-                    return;
-                }
-            };
-            match repo.update(new_outcome.clone()) {
-                Ok(_) => {
-                    let saving_repo = get_saving_repo();
-                    if prev.date.year() == new_outcome.date.year()
-                        && prev.date.month() == new_outcome.date.month()
-                    {
-                        let amount_diff = new_outcome.amount - prev.amount;
-                        match update_saving(
-                            (new_outcome.date.year(), new_outcome.date.month()),
-                            -amount_diff,
-                            &saving_repo,
-                        ) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                tracing::error!("Failed to update saving: {}", e);
-                                return;
-                            }
-                        };
-                        return;
-                    } else {
-                        match update_saving(
-                            (prev.date.year(), prev.date.month()),
-                            prev.amount,
-                            &saving_repo,
-                        ) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                tracing::error!("Failed to update saving: {}", e);
-                                return;
-                            }
-                        };
-                        match update_saving(
-                            (new_outcome.date.year(), new_outcome.date.month()),
-                            -new_outcome.amount,
-                            &saving_repo,
-                        ) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                tracing::error!("Failed to update saving: {}", e);
-                                return;
-                            }
-                        };
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to update outcome: {}", e);
-                    return;
-                }
+pub fn store_account(ctx: &RepoContext, input: AccountInput) -> Result<(), FinanceError> {
+    let card = if input.kind == AccountKind::CreditCard {
+        ensure_account_exists(ctx, input.payment_account_id)?;
+        Some(CardCycle::try_new(input.closing_day, input.payment_day, input.payment_account_id)?)
+    } else {
+        None
+    };
+    let account = Account::try_new(input.name, input.kind, card)?;
+    ctx.account
+        .store(account)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store account: {}", e))
+}
+
+pub fn get_account_savings(ctx: &RepoContext, year: i32, month: u32) -> Vec<AccountSavingSchema> {
+    let last_ym = get_prev_ym((year, month));
+    list_accounts(ctx)
+        .into_iter()
+        .map(|account| {
+            let account_id = account.id.unwrap();
+            AccountSavingSchema {
+                account_id,
+                name: account.name,
+                kind: account.kind,
+                card_cycle: account.card.map(|card| card.label()),
+                last_month: get_saving_amount(ctx, account_id, last_ym),
+                current_month: get_saving_amount(ctx, account_id, (year, month)),
             }
+        })
+        .collect()
+}
+
+pub fn get_statements(ctx: &RepoContext, year: i32, month: u32) -> Vec<StatementSchema> {
+    let accounts = list_accounts(ctx);
+    let calendar = get_business_calendar(ctx);
+    let mut statements = vec![];
+    for account in &accounts {
+        match detail_service::get_statement(account, year, month, &calendar, ctx.income.as_ref(), ctx.outcome.as_ref()) {
+            Ok(Some(statement)) => statements.push(StatementSchema {
+                account_id: account.id.unwrap(),
+                account: account.name.clone(),
+                payment_account: get_account_name(statement.payment_account_id, &accounts),
+                opening_date: format_date(&statement.opening_date),
+                closing_date: format_date(&statement.closing_date),
+                payment_date: format_date(&statement.payment_date),
+                amount: statement.amount,
+            }),
+            Ok(None) => (),
+            Err(e) => tracing::error!("Failed to get statement: {}", e),
         }
+    }
+    statements
+}
+
+// すべての口座の合計
+pub fn get_total_saving(ctx: &RepoContext, year: i32, month: u32) -> Decimal {
+    match detail_service::get_total_saving((year, month), ctx.account.as_ref(), ctx.saving.as_ref()) {
+        Ok(amount) => amount,
         Err(e) => {
-            tracing::error!("Failed to get outcome: {}", e);
-            return;
+            tracing::error!("Failed to get total saving: {}", e);
+            Decimal::ZERO
         }
     }
 }
 
-pub fn create_adjustment(saving_input: String, year: i32, month: u32) {
-    let i_repo = get_income_repo();
-    let o_repo = get_outcome_repo();
-    let s_repo = get_saving_repo();
-    let a_repo = get_adjustment_repo();
-
-    let saving_input = match Decimal::from_str(&saving_input) {
-        Ok(balance_input) => balance_input,
+fn get_saving_amount(ctx: &RepoContext, account_id: u64, key: (i32, u32)) -> Decimal {
+    match ctx.saving.get(account_id, &key) {
+        Ok(Some(saving)) => saving.amount,
+        Ok(None) => Decimal::ZERO,
         Err(e) => {
-            tracing::error!("Failed to parse balance input: {}", e);
-            return;
+            tracing::error!("Failed to get saving: {}", e);
+            Decimal::ZERO
         }
-    };
-    match detail_service::create_adjustment(
-        saving_input,
-        year,
-        month,
-        &i_repo,
-        &o_repo,
-        &s_repo,
-        &a_repo,
-    ) {
-        Ok(_) => (),
+    }
+}
+
+// 利用者が追加した休日が読めなければ同梱の祝日だけで営業日を決める
+pub(super) fn get_business_calendar(ctx: &RepoContext) -> BusinessCalendar {
+    BusinessCalendar::load(ctx.holiday.as_ref()).unwrap_or_else(|e| {
+        tracing::error!("Failed to get holidays: {}", e);
+        BusinessCalendar::japanese()
+    })
+}
+
+pub(super) fn ensure_account_exists(ctx: &RepoContext, account_id: u64) -> Result<(), FinanceError> {
+    ctx.account
+        .get_by_id(account_id)?
+        .map(|_| ())
+        .ok_or(FinanceError::NotFound { entity: "口座", id: account_id })
+}
+
+fn list_accounts(ctx: &RepoContext) -> Vec<Account> {
+    match ctx.account.list() {
+        Ok(accounts) => accounts,
         Err(e) => {
-            tracing::error!("Failed to create adjustment: {}", e);
-            return;
+            tracing::error!("Failed to get accounts: {}", e);
+            vec![]
         }
     }
 }
 
-pub fn get_saving(year: i32, month: u32) -> SavingSchema {
-    let s_repo = get_saving_repo();
-    match s_repo.get(&(year, month)) {
-        Ok(Some(saving)) => SavingSchema {
-            year: saving.key.0,
-            month: saving.key.1,
-            amount: saving.amount,
-        },
-        Ok(None) => SavingSchema {
-            year,
-            month,
-            amount: Decimal::ZERO,
-        },
+fn get_account_name(id: u64, accounts: &[Account]) -> String {
+    accounts
+        .iter()
+        .find(|account| account.id == Some(id))
+        .map(|account| account.name.clone())
+        .unwrap_or_default()
+}
+
+fn list_categories(ctx: &RepoContext) -> Vec<Category> {
+    match ctx.category.list() {
+        Ok(categories) => categories,
         Err(e) => {
-            tracing::error!("Failed to get saving: {}", e);
-            SavingSchema {
-                year,
-                month,
-                amount: Decimal::ZERO,
-            }
+            tracing::error!("Failed to get categories: {}", e);
+            vec![]
         }
     }
 }
 
+fn parse_date(date: &str) -> Result<NaiveDate, FinanceError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| FinanceError::InvalidDate(date.to_string()))
+}
+
 fn format_date(date: &DateTime<Local>) -> String {
     let date_string = date.to_string();
     date_string
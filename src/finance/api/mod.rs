@@ -1,5 +1,8 @@
 pub mod detail;
 pub mod plan;
+pub mod reconciliation;
+pub mod scenario;
+pub mod setting;
 
 use super::detail as detail_service;
-use super::plan as plan_service;
\ No newline at end of file
+use super::plan as plan_service;
@@ -1,25 +1,35 @@
 use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
 use dioxus::logger::tracing;
 use rust_decimal::Decimal;
+use std::rc::Rc;
 use std::str::FromStr;
 
-use super::plan_service;
+use super::detail::{ensure_account_exists, get_business_calendar};
+use super::setting::{get_accounting_period, get_settings, Currency};
 use super::plan_service::future_inspector;
-use super::plan_service::future_inspector::{BalanceStatus, InspectResult};
+use super::plan_service::future_inspector::{yearly_rollups, BalanceSummary, Forecast, InspectResult, MonthlyRollup, Rollup};
+use super::plan_service::scenario::Scenario;
+use super::plan_service::source::EntrySource;
 use super::plan_service::{
-    get_part_time_job_repo,
-    PartTimeJobRepo,
-    get_monthly_outcome_repo,
-    get_temporary_outcome_repo,
-    income::job::get_or_create_part_time_job_incomes,
+    income::job,
+    income::job::{create_part_time_job_incomes, project_part_time_job_incomes, PartTimeJob, PartTimeJobIncome, PaymentTiming},
+    income::monthly_income::{project_monthly_incomes, MonthlyIncomeTemplate},
+    monthly_template::create_monthly_entries,
     outcome::{
-        monthly_outcome::get_or_create_monthly_outcomes,
+        budget_outcome::get_budget_outcomes,
+        card_outcome::get_card_statement_outcomes,
+        monthly_outcome::{project_monthly_outcomes, MonthlyOutcomeTemplate},
         temporary_outcome::get_temporary_outcomes,
     },
 };
-use crate::finance::detail::get_saving_repo;
-use crate::finance::plan::outcome::monthly_outcome::MonthlyOutcomeRepo;
-use crate::finance::setting::get_opening_and_closing_date;
+use crate::finance::context::RepoContext;
+use crate::util::get_next_ym;
+use crate::finance::detail::calendar::Holiday;
+pub use crate::finance::detail::calendar::BusinessDayRoll;
+pub use super::plan_service::recurrence::{weekday_label, MissingDayPolicy, MonthDay, RecurrenceRule, WEEKDAYS};
+pub use super::plan_service::source::SourceKind;
+use crate::finance::error::FinanceError;
+use crate::finance::setting::Settings;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct IncomeSchema {
@@ -30,7 +40,9 @@ pub struct IncomeSchema {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PartTimeJobIncomeSchema {
-    pub id: u64,
+    // まだ保存されていない見込みなら None
+    pub id: Option<u64>,
+    pub part_time_job_id: u64,
     pub name: String,
     pub hourly_wage: Decimal,
     pub hour: Decimal,
@@ -38,50 +50,259 @@ pub struct PartTimeJobIncomeSchema {
     pub total: Decimal,
 }
 
+// アルバイト収入の編集フォームの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartTimeJobIncomeInput {
+    pub id: Option<u64>,
+    pub part_time_job_id: u64,
+    pub name: String,
+    pub hourly_wage: String,
+    pub hour: String,
+    pub payment_date: String,
+}
+
+// アルバイトの追加フォームの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartTimeJobInput {
+    pub name: String,
+    pub hourly_wage: String,
+    // 働いた月の何か月後に振り込まれるか
+    pub month_offset: u32,
+    // 入金される月の中での入金日 (毎週・隔週なら月に何回か)
+    pub payment_timing: RecurrenceRule,
+    // 入金日がない月がある規則のときだけ意味がある
+    pub missing_day: MissingDayPolicy,
+    pub business_day_roll: BusinessDayRoll,
+    // 空なら既定の口座
+    pub account_id: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MonthlyOutcomeSchema {
-    pub id: u64,
+    // まだ保存されていない見込みなら None
+    pub id: Option<u64>,
     pub name: String,
     pub amount: Decimal,
     pub payment_date: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct FutureInspectResultSchema {
+pub struct MonthlyTemplateSchema {
+    pub id: u64,
+    pub name: String,
+    pub amount: Decimal,
+    // 毎月25日, 隔週金曜日, 毎月31日 (ない月は月末)
+    pub payment_timing: String,
+    // 前営業日, 翌営業日
+    pub business_day_roll: String,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub account_id: Option<u64>,
+}
+
+// 毎月の収入・支出の入力フォームの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyTemplateInput {
+    pub name: String,
+    pub amount: String,
+    pub payment_timing: RecurrenceRule,
+    // 支払日がない月がある規則のときだけ意味がある
+    pub missing_day: MissingDayPolicy,
+    pub business_day_roll: BusinessDayRoll,
+    pub start_date: String,
+    // 空なら終わりなし
+    pub end_date: String,
+    // 空なら既定の口座
+    pub account_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HolidaySchema {
+    pub id: u64,
     pub date: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FutureInspectResultSchema {
+    // グラフの横軸に使う
+    pub date: NaiveDate,
+    pub date_label: String,
     pub amount: Decimal,
-    pub incomes: String,
-    pub outcomes: String,
+    // 設定の通貨で表示する残高
+    pub balance: String,
+    // 設定の残高の下限を下回っている
+    pub is_low_balance: bool,
+    pub incomes: Vec<ForecastEntrySchema>,
+    pub outcomes: Vec<ForecastEntrySchema>,
+    // その日の収入・支出の合計 (設定の通貨で表示)
+    pub total_income: String,
+    pub total_outcome: String,
 }
 
-pub fn get_incomes(year: i32, month: u32) -> Vec<IncomeSchema> {
-    let repo = get_part_time_job_repo();
-    let (start_date, end_date) = match get_opening_and_closing_date(year, month) {
-        Ok((start_date, end_date)) => (start_date, end_date),
-        Err(e) => {
-            tracing::error!("Failed to get opening and closing date: {}", e);
-            return vec![];
+// 予測の収入・支出の元が表示されている画面
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryPage {
+    Plan,
+    Detail,
+}
+
+// 予測の収入・支出 1 件
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastEntrySchema {
+    pub name: String,
+    pub amount: Decimal,
+    // 設定の通貨で表示する金額
+    pub amount_label: String,
+    pub source_kind: SourceKind,
+    pub source_id: Option<u64>,
+    pub page: EntryPage,
+    // 元の項目の要素の id (page の画面で探す)
+    pub anchor: String,
+}
+
+impl ForecastEntrySchema {
+    fn new(name: String, amount: Decimal, source: EntrySource, currency: &Currency) -> Self {
+        let page = match source.kind {
+            SourceKind::Budget | SourceKind::CardStatement => EntryPage::Detail,
+            _ => EntryPage::Plan,
+        };
+        let prefix = match source.kind {
+            SourceKind::PartTimeJob => "part-time-job",
+            SourceKind::MonthlyIncome => "monthly-income",
+            SourceKind::MonthlyOutcome => "monthly-outcome",
+            SourceKind::TemporaryOutcome => "combined-list",
+            SourceKind::Budget => "budget",
+            SourceKind::CardStatement => "statement",
+            SourceKind::Scenario => "scenario-change",
+        };
+        // 臨時の支出は個別の行がないので一覧を指す
+        let anchor = match (source.kind, source.id) {
+            (SourceKind::TemporaryOutcome, _) | (_, None) => prefix.to_string(),
+            (_, Some(id)) => format!("{}-{}", prefix, id),
+        };
+        Self {
+            name,
+            amount,
+            amount_label: currency.format(&amount),
+            source_kind: source.kind,
+            source_id: source.id,
+            page,
+            anchor,
+        }
+    }
+}
+
+// 月か年の集計 (金額は設定の通貨で表示)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollupSchema {
+    pub label: String,
+    pub opening: String,
+    pub total_income: String,
+    pub total_outcome: String,
+    pub net: String,
+    pub is_net_negative: bool,
+    pub closing: String,
+    pub minimum: String,
+    // 最低残高が設定の残高の下限を下回っている
+    pub is_low_balance: bool,
+}
+
+// 年の集計とその年の月ごとの集計
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearlyRollupSchema {
+    pub year: i32,
+    pub summary: RollupSchema,
+    pub months: Vec<RollupSchema>,
+}
+
+// ある日の残高
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatedBalanceSchema {
+    pub date: String,
+    pub balance: String,
+}
+
+// 予測期間の残高についての警告
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BalanceAlertSchema {
+    pub first_deficit: Option<DatedBalanceSchema>,
+    // 下限が 0 以下なら残高がマイナスになる日と同じなので出さない
+    pub first_below_threshold: Option<DatedBalanceSchema>,
+    pub minimum: Option<DatedBalanceSchema>,
+    pub threshold: String,
+}
+
+impl BalanceAlertSchema {
+    pub fn has_warning(&self) -> bool {
+        self.first_deficit.is_some() || self.first_below_threshold.is_some()
+    }
+}
+
+// 予測期間全体の日ごとの残高と集計、警告
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastSchema {
+    // 予測した開始月と月数 (シナリオも同じ期間で予測する)
+    pub start_ym: (i32, u32),
+    pub horizon_months: u32,
+    pub days: Vec<FutureInspectResultSchema>,
+    pub rollups: Vec<YearlyRollupSchema>,
+    // シナリオとの比較に使う
+    pub month_end_balances: Vec<((i32, u32), Decimal)>,
+    pub alert: BalanceAlertSchema,
+    pub low_balance_threshold: Decimal,
+    // グラフの目盛りの表示に使う
+    pub currency: Currency,
+}
+
+impl Default for ForecastSchema {
+    fn default() -> Self {
+        Self {
+            start_ym: (0, 1),
+            horizon_months: 0,
+            days: vec![],
+            rollups: vec![],
+            month_end_balances: vec![],
+            alert: BalanceAlertSchema::default(),
+            low_balance_threshold: Decimal::ZERO,
+            currency: Currency::Jpy,
+        }
+    }
+}
+
+// 保存されていない月は見込みを返す
+pub fn get_incomes(ctx: &RepoContext, year: i32, month: u32) -> Vec<IncomeSchema> {
+    let period = get_accounting_period(ctx);
+    let calendar = get_business_calendar(ctx);
+    let incomes = project_part_time_job_incomes(year, month, &period, &calendar, ctx.part_time_job.as_ref())
+        .and_then(|mut incomes| {
+            incomes.extend(project_monthly_incomes(year, month, &period, &calendar, ctx.monthly_income.as_ref())?);
+            Ok(incomes)
+        });
+    match incomes {
+        Ok(mut incomes) => {
+            incomes.sort_by_key(|income| income.date);
+            incomes
+                .into_iter()
+                .map(|income| IncomeSchema {
+                    name: income.name,
+                    amount: income.amount,
+                    date: income.date.date_naive().to_string(),
+                })
+                .collect()
         }
-    };
-    match plan_service::get_incomes(vec![&repo], &start_date, &end_date) {
-        Ok(incomes) => incomes
-            .into_iter()
-            .map(|income| IncomeSchema {
-                name: income.name,
-                amount: income.amount,
-                date: income.date.date_naive().to_string(),
-            })
-            .collect(),
         Err(e) => {
             tracing::error!("Failed to get incomes: {}", e);
-            return vec![];
+            vec![]
         }
     }
 }
 
-pub fn get_part_time_job_incomes(year: i32, month: u32) -> Vec<PartTimeJobIncomeSchema> {
-    let repo = get_part_time_job_repo();
-    let (start_date, end_date) = match get_opening_and_closing_date(year, month) {
+pub fn get_part_time_job_incomes(ctx: &RepoContext, year: i32, month: u32) -> Vec<PartTimeJobIncomeSchema> {
+    let repo = ctx.part_time_job.as_ref();
+    let calendar = get_business_calendar(ctx);
+    let period = get_accounting_period(ctx);
+    let (start_date, end_date) = match period.get_opening_and_closing_date(year, month) {
         Ok((start_date, end_date)) => (start_date, end_date),
         Err(e) => {
             tracing::error!("Failed to get opening and closing date: {}", e);
@@ -97,212 +318,461 @@ pub fn get_part_time_job_incomes(year: i32, month: u32) -> Vec<PartTimeJobIncome
     };
     part_time_jobs
         .into_iter()
-        .map(|job| {
-            let job_payment_date = job.get_payment_date(year, month).unwrap();
-            if let Ok(Some(income)) = &repo.get_part_time_job_income_by_part_time_job_id(
-                job.id.unwrap(),
-                job_payment_date.year(),
-                job_payment_date.month(),
-            ) {
-                return PartTimeJobIncomeSchema {
-                    id: income.id.unwrap(),
-                    name: income.name.clone(),
-                    hourly_wage: income.hourly_wage,
-                    hour: income.hour,
-                    payment_date: income.payment_date.date_naive().to_string(),
-                    total: income.hourly_wage * income.hour,
-                };
-            } else {
-                let income = job
-                    .to_part_time_job_income(year, month, Decimal::ZERO, &repo)
-                    .unwrap();
-                return PartTimeJobIncomeSchema {
-                    id: income.id.unwrap(),
-                    name: income.name,
-                    hourly_wage: income.hourly_wage,
-                    hour: income.hour,
-                    payment_date: income.payment_date.date_naive().to_string(),
-                    total: income.hourly_wage * income.hour,
-                };
-            }
+        .flat_map(|job| {
+            job.project_part_time_job_incomes(year, month, &calendar, repo)
+                .inspect_err(|e| tracing::error!("Failed to get part-time job incomes of {}: {}", job.name, e))
+                .unwrap_or_default()
+        })
+        .map(|income| PartTimeJobIncomeSchema {
+            id: income.id,
+            part_time_job_id: income.part_time_job_id,
+            name: income.name,
+            hourly_wage: income.hourly_wage,
+            hour: income.hour,
+            payment_date: income.payment_date.date_naive().to_string(),
+            total: income.hourly_wage * income.hour,
         })
         .collect()
 }
 
-pub fn update_part_time_job_income(
-    id: u64,
-    name: String,
-    hourly_wage: String,
-    hour: String,
-    payment_date: String,
-) {
-    let hourly_wage = match Decimal::from_str(&hourly_wage) {
-        Ok(hourly_wage) => hourly_wage,
-        Err(e) => {
-            tracing::error!("Invalid hourly wage '{}': {}", hourly_wage, e);
-            return;
+// 見込みの給料を編集したら、その月の分として保存する
+pub fn update_part_time_job_income(ctx: &RepoContext, input: PartTimeJobIncomeInput) -> Result<(), FinanceError> {
+    let hourly_wage = Decimal::from_str(&input.hourly_wage)
+        .map_err(|_| FinanceError::InvalidAmount(input.hourly_wage))?;
+    let hour = Decimal::from_str(&input.hour)
+        .map_err(|_| FinanceError::InvalidHour(input.hour))?;
+    let payment_date = NaiveDate::parse_from_str(&input.payment_date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| {
+            Local
+                .with_ymd_and_hms(date.year(), date.month0() + 1, date.day0() + 1, 0, 0, 0)
+                .single()
+        })
+        .ok_or(FinanceError::InvalidDate(input.payment_date))?;
+
+    let repo = ctx.part_time_job.as_ref();
+    let result = match input.id {
+        Some(id) => {
+            let income = repo
+                .get_part_time_job_income_by_id(id)?
+                .ok_or(FinanceError::NotFound { entity: "アルバイト収入", id })?
+                .update(input.name, hourly_wage, hour, payment_date);
+            repo.update_part_time_job_income(income)
         }
+        None => repo
+            .store_part_time_job_income(PartTimeJobIncome {
+                id: None,
+                part_time_job_id: input.part_time_job_id,
+                name: input.name,
+                hourly_wage,
+                hour,
+                payment_date,
+            })
+            .map(|_| ()),
     };
-    let hour = match Decimal::from_str(&hour) {
-        Ok(hour) => hour,
+    result
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to update part-time job income: {}", e))
+}
+
+// year 年 month 月から働き始めるアルバイトを追加する
+pub fn store_part_time_job(ctx: &RepoContext, year: i32, month: u32, input: PartTimeJobInput) -> Result<(), FinanceError> {
+    let hourly_wage = Decimal::from_str(&input.hourly_wage)
+        .map_err(|_| FinanceError::InvalidAmount(input.hourly_wage))?;
+    let start_date = NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| FinanceError::InvalidDate(format!("{}-{:02}-01", year, month)))?;
+    let payment_timing = PaymentTiming {
+        month_offset: input.month_offset,
+        rule: input.payment_timing,
+        missing_day: input.missing_day,
+    };
+    if let Some(account_id) = input.account_id {
+        ensure_account_exists(ctx, account_id)?;
+    }
+    let mut part_time_job = PartTimeJob::try_new(input.name, payment_timing, input.business_day_roll, start_date)?;
+    part_time_job.account_id = input.account_id;
+    ctx.transaction(|| job::store_part_time_job(part_time_job, hourly_wage, ctx.part_time_job.as_ref()))
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store part-time job: {}", e))
+}
+
+pub fn get_monthly_outcomes(ctx: &RepoContext, year: i32, month: u32) -> Vec<MonthlyOutcomeSchema> {
+    let repo = ctx.monthly_outcome.as_ref();
+    let calendar = get_business_calendar(ctx);
+    let period = get_accounting_period(ctx);
+    let (start_date, end_date) = match period.get_opening_and_closing_date(year, month) {
+        Ok((start_date, end_date)) => (start_date, end_date),
         Err(e) => {
-            tracing::error!("Invalid hour '{}': {}", hour, e);
-            return;
+            tracing::error!("Failed to get opening and closing date: {}", e);
+            return vec![];
         }
     };
-    let payment_date = match NaiveDate::parse_from_str(&payment_date, "%Y-%m-%d") {
-        Ok(payment_date) => match Local
-            .with_ymd_and_hms(
-                payment_date.year(),
-                payment_date.month0() + 1,
-                payment_date.day0() + 1,
-                0,
-                0,
-                0,
-            )
-            .single()
-        {
-            Some(payment_date) => payment_date,
-            None => {
-                tracing::error!("Invalid payment date '{}'", payment_date);
-                return;
-            }
-        },
+    let templates = match repo.list_monthly_templates(&start_date, &end_date) {
+        Ok(templates) => templates,
         Err(e) => {
-            tracing::error!("Invalid payment date '{}': {}", payment_date, e);
-            return;
+            tracing::error!("Failed to get monthly outcome templates: {}", e);
+            return vec![];
         }
     };
-
-    let repo = get_part_time_job_repo();
-    let income = match repo.get_part_time_job_income_by_id(id) {
-        Ok(Some(income)) => income.update(name, hourly_wage, hour, payment_date),
-        Ok(None) => {
-            tracing::error!("Part-time job income not found: {}", id);
-            return;
+    let mut outcomes = Vec::new();
+    for template in templates {
+        match template.project_entries(year, month, &period, &calendar, repo) {
+            Ok(monthly_outcomes) => outcomes.extend(monthly_outcomes.into_iter().map(|outcome| MonthlyOutcomeSchema {
+                id: outcome.id,
+                name: outcome.name,
+                amount: outcome.amount,
+                payment_date: outcome.payment_date.date_naive().to_string(),
+            })),
+            Err(e) => tracing::error!("Failed to get monthly outcomes of {}: {}", template.name, e),
         }
+    }
+    outcomes
+}
+
+// 支払日がない月がある規則なら、その月の扱いも添える
+pub(super) fn get_payment_timing_label(payment_timing: &RecurrenceRule, missing_day: MissingDayPolicy) -> String {
+    if !payment_timing.can_miss_day() {
+        return payment_timing.label();
+    }
+    let note = match missing_day {
+        MissingDayPolicy::Clamp => "ない月は月末",
+        MissingDayPolicy::Skip => "ない月はなし",
+    };
+    format!("{} ({})", payment_timing.label(), note)
+}
+
+fn parse_period(input: &MonthlyTemplateInput) -> Result<(NaiveDate, Option<NaiveDate>), FinanceError> {
+    let end_date = if input.end_date.trim().is_empty() { None } else { Some(parse_date(&input.end_date)?) };
+    Ok((parse_date(&input.start_date)?, end_date))
+}
+
+pub fn get_monthly_income_templates(ctx: &RepoContext, year: i32, month: u32) -> Vec<MonthlyTemplateSchema> {
+    let (start_date, end_date) = match get_accounting_period(ctx).get_opening_and_closing_date(year, month) {
+        Ok((start_date, end_date)) => (start_date, end_date),
         Err(e) => {
-            tracing::error!("Failed to get part-time job income: {}", e);
-            return;
+            tracing::error!("Failed to get opening and closing date: {}", e);
+            return vec![];
         }
     };
-    if let Err(e) = repo.update_part_time_job_income(income) {
-        tracing::error!("Failed to update part-time job income: {}", e);
+    match ctx.monthly_income.list_monthly_templates(&start_date, &end_date) {
+        Ok(templates) => templates
+            .into_iter()
+            .map(|template| MonthlyTemplateSchema {
+                id: template.id.unwrap(),
+                name: template.name,
+                amount: template.amount,
+                payment_timing: get_payment_timing_label(&template.payment_timing, template.missing_day),
+                business_day_roll: template.business_day_roll.label().to_string(),
+                start_date: template.start_date.date_naive().to_string(),
+                end_date: template.end_date.map(|date| date.date_naive().to_string()),
+                account_id: template.account_id,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::error!("Failed to get monthly income templates: {}", e);
+            vec![]
+        }
     }
 }
 
-pub fn get_monthly_outcomes(year: i32, month: u32) -> Vec<MonthlyOutcomeSchema> {
-    let repo = get_monthly_outcome_repo();
-    let (start_date, end_date) = match get_opening_and_closing_date(year, month) {
+pub fn store_monthly_income_template(ctx: &RepoContext, input: MonthlyTemplateInput) -> Result<(), FinanceError> {
+    let (start_date, end_date) = parse_period(&input)?;
+    if let Some(account_id) = input.account_id {
+        ensure_account_exists(ctx, account_id)?;
+    }
+    let mut template = MonthlyIncomeTemplate::try_new(
+        input.name,
+        input.amount,
+        input.payment_timing,
+        input.missing_day,
+        input.business_day_roll,
+        start_date,
+        end_date,
+    )?;
+    template.account_id = input.account_id;
+    ctx.monthly_income
+        .store_monthly_template(template)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store monthly income template: {}", e))
+}
+
+pub fn get_monthly_outcome_templates(ctx: &RepoContext, year: i32, month: u32) -> Vec<MonthlyTemplateSchema> {
+    let (start_date, end_date) = match get_accounting_period(ctx).get_opening_and_closing_date(year, month) {
         Ok((start_date, end_date)) => (start_date, end_date),
         Err(e) => {
             tracing::error!("Failed to get opening and closing date: {}", e);
             return vec![];
         }
     };
-    let templates = match repo.list_monthly_outcome_template(&start_date, &end_date) {
-        Ok(templates) => templates,
+    match ctx.monthly_outcome.list_monthly_templates(&start_date, &end_date) {
+        Ok(templates) => templates
+            .into_iter()
+            .map(|template| MonthlyTemplateSchema {
+                id: template.id.unwrap(),
+                name: template.name,
+                amount: template.amount,
+                payment_timing: get_payment_timing_label(&template.payment_timing, template.missing_day),
+                business_day_roll: template.business_day_roll.label().to_string(),
+                start_date: template.start_date.date_naive().to_string(),
+                end_date: template.end_date.map(|date| date.date_naive().to_string()),
+                account_id: template.account_id,
+            })
+            .collect(),
         Err(e) => {
             tracing::error!("Failed to get monthly outcome templates: {}", e);
-            return vec![];
+            vec![]
         }
-    };
-    templates
-        .into_iter()
-        .map(|template| {
-            let payment_date = template.get_payment_date(year, month).unwrap();
-            if let Ok(Some(outcome)) = &repo.get_monthly_outcome_by_template_id(
-                template.id.unwrap(),
-                payment_date.year(),
-                payment_date.month(),
-            ) {
-                return MonthlyOutcomeSchema {
-                    id: outcome.id.unwrap(),
-                    name: outcome.name.clone(),
-                    amount: outcome.amount,
-                    payment_date: outcome.payment_date.date_naive().to_string(),
-                };
-            } else {
-                let outcome = template
-                    .to_monthly_outcome(year, month, &repo)
-                    .unwrap();
-                return MonthlyOutcomeSchema {
-                    id: outcome.id.unwrap(),
-                    name: outcome.name,
-                    amount: outcome.amount,
-                    payment_date: outcome.payment_date.date_naive().to_string(),
-                };
-            }
-        })
-        .collect()
+    }
+}
+
+pub fn store_monthly_outcome_template(ctx: &RepoContext, input: MonthlyTemplateInput) -> Result<(), FinanceError> {
+    let (start_date, end_date) = parse_period(&input)?;
+    if let Some(account_id) = input.account_id {
+        ensure_account_exists(ctx, account_id)?;
+    }
+    let mut template = MonthlyOutcomeTemplate::try_new(
+        input.name,
+        input.amount,
+        input.payment_timing,
+        input.missing_day,
+        input.business_day_roll,
+        start_date,
+        end_date,
+    )?;
+    template.account_id = input.account_id;
+    ctx.monthly_outcome
+        .store_monthly_template(template)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store monthly outcome template: {}", e))
 }
 
-pub fn get_future_inspect(year: i32, month: u32) -> Vec<FutureInspectResultSchema> {
-    let part_time_job_repo = get_part_time_job_repo();
-    let monthly_outcome_repo = get_monthly_outcome_repo();
-    let temporary_outcome_repo = get_temporary_outcome_repo();
-    let saving_repo = get_saving_repo();
+// 同梱の祝日に加えて休みになる日
+pub fn get_holidays(ctx: &RepoContext) -> Vec<HolidaySchema> {
+    match ctx.holiday.list() {
+        Ok(holidays) => holidays
+            .into_iter()
+            .map(|holiday| HolidaySchema {
+                id: holiday.id.unwrap(),
+                date: holiday.date.to_string(),
+                name: holiday.name,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::error!("Failed to get holidays: {}", e);
+            vec![]
+        }
+    }
+}
+
+pub fn store_holiday(ctx: &RepoContext, date: String, name: String) -> Result<(), FinanceError> {
+    let holiday = Holiday::try_new(parse_date(&date)?, name)?;
+    ctx.holiday
+        .store(holiday)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store holiday: {}", e))
+}
+
+pub fn delete_holiday(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    ctx.holiday
+        .delete_by_id(id)
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to delete holiday: {}", e))
+}
+
+fn parse_date(input: &str) -> Result<NaiveDate, FinanceError> {
+    NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| FinanceError::InvalidDate(input.to_string()))
+}
+
+// 今月の固定収入・固定支出・アルバイト収入を保存する
+// 予測では保存しないので、月が始まってから開いたときに確定させる
+pub fn create_current_month_plans(ctx: &RepoContext) -> Result<(), FinanceError> {
+    let period = get_accounting_period(ctx);
+    let (year, month) = period.get_year_month(&Local::now());
+    let calendar = get_business_calendar(ctx);
+    ctx.transaction(|| {
+        create_part_time_job_incomes(year, month, &period, &calendar, ctx.part_time_job.as_ref())?;
+        create_monthly_entries(year, month, &period, &calendar, ctx.monthly_income.as_ref())?;
+        create_monthly_entries(year, month, &period, &calendar, ctx.monthly_outcome.as_ref())
+    })
+    .map_err(FinanceError::from)
+    .inspect_err(|e| tracing::error!("Failed to create plans of current month: {}", e))
+}
+
+// year 年 month 月分から horizon_months か月分の予測を作る
+// シナリオを渡すと、その変更を重ねた計画で予測する
+pub(super) fn build_forecast(
+    ctx: &RepoContext,
+    year: i32,
+    month: u32,
+    horizon_months: u32,
+    scenario: Option<Rc<Scenario>>,
+) -> Result<Forecast, anyhow::Error> {
+    let part_time_job_repo = ctx.part_time_job.clone();
+    let monthly_income_repo = ctx.monthly_income.clone();
+    let monthly_outcome_repo = ctx.monthly_outcome.clone();
+    let temporary_outcome_repo = ctx.temporary_outcome.clone();
+    let budget_repo = ctx.budget.clone();
+    let ledger_outcome_repo = ctx.outcome.clone();
+    let category_repo = ctx.category.clone();
+    let card_account_repo = ctx.account.clone();
+    let card_income_repo = ctx.income.clone();
+    let card_outcome_repo = ctx.outcome.clone();
+    let calendar = get_business_calendar(ctx);
+    let part_time_job_calendar = calendar.clone();
+    let monthly_income_calendar = calendar.clone();
+    let monthly_outcome_calendar = calendar.clone();
+    let card_calendar = calendar;
+    let period = get_accounting_period(ctx);
+    let today = Local::now();
+    let end_ym = (1..horizon_months.max(1)).fold((year, month), |ym, _| get_next_ym(ym));
+    let (part_time_job_scenario, monthly_income_scenario, monthly_outcome_scenario, one_off_scenario) =
+        (scenario.clone(), scenario.clone(), scenario.clone(), scenario);
 
-    match future_inspector::inspect(
+    future_inspector::inspect(
         (year, month),
-        (year + 2, month),
-        &saving_repo,
-        vec![Box::new(move |year, month| get_or_create_part_time_job_incomes(year, month, &part_time_job_repo))],
+        end_ym,
+        period.get_year_month(&today),
+        ctx.account.as_ref(),
+        ctx.saving.as_ref(),
         vec![
-            Box::new(move |year, month| get_or_create_monthly_outcomes(year, month, &monthly_outcome_repo)),
-            Box::new(move |year, month| get_temporary_outcomes(year, month, &temporary_outcome_repo)),
+            Box::new(move |year, month| match &part_time_job_scenario {
+                Some(scenario) => scenario.project_part_time_job_incomes(year, month, &period, &part_time_job_calendar, part_time_job_repo.as_ref()),
+                None => project_part_time_job_incomes(year, month, &period, &part_time_job_calendar, part_time_job_repo.as_ref()),
+            }),
+            Box::new(move |year, month| match &monthly_income_scenario {
+                Some(scenario) => scenario.project_monthly_incomes(year, month, &period, &monthly_income_calendar, monthly_income_repo.as_ref()),
+                None => project_monthly_incomes(year, month, &period, &monthly_income_calendar, monthly_income_repo.as_ref()),
+            }),
         ],
-    ) {
-        Ok(results) => {
-            results
-                .into_iter()
-                .map(|result| {
-                    match result {
-                        InspectResult {
-                            date,
-                            balance_status: BalanceStatus::Surplus(amount),
-                            incomes,
-                            outcomes,
-                        } => FutureInspectResultSchema {
-                            date: date.date_naive().to_string(),
-                            amount,
-                            incomes: incomes
-                                .iter()
-                                .map(|income| income.name.clone() + ": " + &income.amount.to_string())
-                                .collect::<Vec<String>>()
-                                .join(", "),
-                            outcomes: outcomes
-                                .iter()
-                                .map(|outcome| outcome.name.clone() + ": " + &outcome.amount.to_string())
-                                .collect::<Vec<String>>()
-                                .join(", "),
-                        },
-                        InspectResult {
-                            date,
-                            balance_status: BalanceStatus::Deficit(amount),
-                            incomes,
-                            outcomes,
-                        } => FutureInspectResultSchema {
-                            date: date.date_naive().to_string(),
-                            amount: -amount,
-                            incomes: incomes
-                                .iter()
-                                .map(|income| income.name.clone() + ": " + &income.amount.to_string())
-                                .collect::<Vec<String>>()
-                                .join(", "),
-                            outcomes: outcomes
-                                .iter()
-                                .map(|outcome| outcome.name.clone() + ": " + &outcome.amount.to_string())
-                                .collect::<Vec<String>>()
-                                .join(", "),
-                        }
-                    }
-                })
-                .collect()
-        },
+        // 予算は同じ月の予測済みの支出を差し引くので、まとめて作る
+        vec![Box::new(move |year, month| {
+            let mut outcomes = match &monthly_outcome_scenario {
+                Some(scenario) => scenario.project_monthly_outcomes(year, month, &period, &monthly_outcome_calendar, monthly_outcome_repo.as_ref())?,
+                None => project_monthly_outcomes(year, month, &period, &monthly_outcome_calendar, monthly_outcome_repo.as_ref())?,
+            };
+            if let Some(scenario) = &one_off_scenario {
+                outcomes.extend(scenario.get_one_off_outcomes(year, month, &period)?);
+            }
+            outcomes.extend(get_temporary_outcomes(year, month, &period, temporary_outcome_repo.as_ref())?);
+            outcomes.extend(get_card_statement_outcomes(
+                year,
+                month,
+                &today,
+                &card_calendar,
+                card_account_repo.as_ref(),
+                card_income_repo.as_ref(),
+                card_outcome_repo.as_ref(),
+            )?);
+            let budget_outcomes = get_budget_outcomes(
+                (year, month),
+                &today,
+                &period,
+                &outcomes,
+                budget_repo.as_ref(),
+                ledger_outcome_repo.as_ref(),
+                category_repo.as_ref(),
+            )?;
+            outcomes.extend(budget_outcomes);
+            Ok(outcomes)
+        })],
+    )
+}
+
+fn to_result_schema(result: InspectResult, settings: &Settings) -> FutureInspectResultSchema {
+    let amount = result.balance();
+    let total_income = result.total_income();
+    let total_outcome = result.total_outcome();
+    let InspectResult { date, incomes, outcomes, .. } = result;
+    FutureInspectResultSchema {
+        date: date.date_naive(),
+        date_label: settings.locale.format_date(&date.date_naive()),
+        amount,
+        balance: settings.currency.format(&amount),
+        is_low_balance: settings.is_low_balance(&amount),
+        incomes: incomes
+            .into_iter()
+            .map(|income| ForecastEntrySchema::new(income.name, income.amount, income.source, &settings.currency))
+            .collect(),
+        outcomes: outcomes
+            .into_iter()
+            .map(|outcome| ForecastEntrySchema::new(outcome.name, outcome.amount, outcome.source, &settings.currency))
+            .collect(),
+        total_income: settings.currency.format(&total_income),
+        total_outcome: settings.currency.format(&total_outcome),
+    }
+}
+
+// 残高が足りなくなる日と最も少なくなる日
+fn to_alert_schema(summary: BalanceSummary, settings: &Settings) -> BalanceAlertSchema {
+    let to_schema = |(date, balance): (DateTime<Local>, Decimal)| DatedBalanceSchema {
+        date: settings.locale.format_date(&date.date_naive()),
+        balance: settings.currency.format(&balance),
+    };
+    BalanceAlertSchema {
+        first_deficit: summary.first_deficit.map(to_schema),
+        first_below_threshold: summary
+            .first_below_threshold
+            .filter(|_| settings.low_balance_threshold > Decimal::ZERO)
+            .map(to_schema),
+        minimum: summary.minimum.map(to_schema),
+        threshold: settings.currency.format(&settings.low_balance_threshold),
+    }
+}
+
+// 月ごとの集計を年ごとにまとめ、年の中に月を並べる
+fn to_rollup_schemas(monthly: &[MonthlyRollup], settings: &Settings) -> Vec<YearlyRollupSchema> {
+    let to_schema = |label: String, rollup: &Rollup| RollupSchema {
+        label,
+        opening: settings.currency.format(&rollup.opening),
+        total_income: settings.currency.format(&rollup.total_income),
+        total_outcome: settings.currency.format(&rollup.total_outcome),
+        net: settings.currency.format(&rollup.net()),
+        is_net_negative: rollup.net() < Decimal::ZERO,
+        closing: settings.currency.format(&rollup.closing),
+        minimum: settings.currency.format(&rollup.minimum),
+        is_low_balance: settings.is_low_balance(&rollup.minimum),
+    };
+    yearly_rollups(monthly)
+        .into_iter()
+        .map(|(year, rollup)| YearlyRollupSchema {
+            year,
+            summary: to_schema(format!("{}年", year), &rollup),
+            months: monthly
+                .iter()
+                .filter(|((y, _), _)| *y == year)
+                .map(|((_, m), rollup)| to_schema(format!("{}月", m), rollup))
+                .collect(),
+        })
+        .collect()
+}
+
+// year 年 month 月分から horizon_months か月分を一度だけ予測し、日ごとの残高と集計、警告を返す
+// 表は何日分を表示していても、グラフと同じこの結果から切り出す
+pub fn get_forecast(ctx: &RepoContext, year: i32, month: u32, horizon_months: u32) -> ForecastSchema {
+    let settings = get_settings(ctx);
+    let period = get_accounting_period(ctx);
+    let forecast = match build_forecast(ctx, year, month, horizon_months, None) {
+        Ok(forecast) => forecast,
         Err(e) => {
             tracing::error!("Failed to inspect future: {}", e);
-            vec![]
-        },
+            return ForecastSchema { start_ym: (year, month), horizon_months, ..ForecastSchema::default() };
+        }
+    };
+    let monthly = forecast.monthly_rollups(&period);
+    let rollups = to_rollup_schemas(&monthly, &settings);
+    let alert = to_alert_schema(BalanceSummary::summarize(&forecast.results, settings.low_balance_threshold), &settings);
+    ForecastSchema {
+        start_ym: (year, month),
+        horizon_months,
+        days: forecast.results.into_iter().map(|result| to_result_schema(result, &settings)).collect(),
+        rollups,
+        month_end_balances: monthly.into_iter().map(|(ym, rollup)| (ym, rollup.closing)).collect(),
+        alert,
+        low_balance_threshold: settings.low_balance_threshold,
+        currency: settings.currency,
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,310 @@
+use chrono::{DateTime, Local};
+use dioxus::logger::tracing;
+use rust_decimal::Decimal;
+
+use super::detail::{ensure_account_exists, get_business_calendar};
+use super::detail_service;
+use super::plan_service::income::job::project_part_time_job_incomes_paid_between;
+use super::plan_service::income::ToIncome;
+use super::plan_service::income::monthly_income::project_monthly_incomes;
+use super::plan_service::outcome::monthly_outcome::project_monthly_outcomes;
+use super::plan_service::reconciliation::{exclude_adjustments, reconcile, PlannedEntry, ReconciledEntry, RecordedEntry};
+use super::plan_service::source::{EntrySource, SourceKind};
+use super::setting::{get_accounting_period, get_settings};
+use crate::finance::context::RepoContext;
+use crate::finance::error::FinanceError;
+use crate::finance::setting::Settings;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReconciliationKind {
+    Income,
+    Outcome,
+}
+
+impl ReconciliationKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReconciliationKind::Income => "収入",
+            ReconciliationKind::Outcome => "支出",
+        }
+    }
+}
+
+// まだ記録していない計画を明細に記録するときの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromotionInput {
+    pub kind: ReconciliationKind,
+    pub name: String,
+    pub amount: Decimal,
+    pub date: DateTime<Local>,
+    // None なら既定の口座
+    pub account_id: Option<u64>,
+}
+
+// 計画した項目 1 件と、それに対応する明細
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconciliationItemSchema {
+    pub kind: ReconciliationKind,
+    pub name: String,
+    pub planned_date: String,
+    pub planned: String,
+    // 記録がなければ空
+    pub recorded_date: String,
+    pub recorded: String,
+    pub variance: String,
+    // 収入が少ないか支出が多い
+    pub is_unfavorable: bool,
+    pub status: &'static str,
+    // 未記録で金額が決まっていれば明細に記録できる
+    pub promotion: Option<PromotionInput>,
+}
+
+// 収入か支出の合計 (金額は設定の通貨で表示)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReconciliationTotalSchema {
+    pub planned: String,
+    pub recorded: String,
+    // 計画にない明細の合計 (recorded に含む)
+    pub unplanned: String,
+    pub variance: String,
+    pub is_unfavorable: bool,
+}
+
+// 月の計画と実績の比較
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReconciliationSchema {
+    pub items: Vec<ReconciliationItemSchema>,
+    pub income: ReconciliationTotalSchema,
+    pub outcome: ReconciliationTotalSchema,
+}
+
+// 収入が少ないか支出が多ければ不利な差
+fn is_unfavorable(kind: ReconciliationKind, variance: Decimal) -> bool {
+    match kind {
+        ReconciliationKind::Income => variance < Decimal::ZERO,
+        ReconciliationKind::Outcome => variance > Decimal::ZERO,
+    }
+}
+
+fn to_item_schema(kind: ReconciliationKind, entry: &ReconciledEntry, planned: &PlannedEntry, settings: &Settings) -> ReconciliationItemSchema {
+    let variance = entry.variance();
+    // 時間を入れていないアルバイトなどは 0 円の見込みなので、記録させずに知らせる
+    let is_undecided = planned.amount.is_zero();
+    let status = match &entry.recorded {
+        None if is_undecided && planned.source.kind == SourceKind::PartTimeJob => "時間未入力",
+        None if is_undecided => "金額未定",
+        None => "未記録",
+        Some(_) if variance.is_zero() => "一致",
+        Some(_) => "差額あり",
+    };
+    ReconciliationItemSchema {
+        kind,
+        name: planned.name.clone(),
+        planned_date: settings.locale.format_date(&planned.date.date_naive()),
+        planned: settings.currency.format(&planned.amount),
+        recorded_date: entry
+            .recorded
+            .as_ref()
+            .map_or_else(String::new, |recorded| settings.locale.format_date(&recorded.date.date_naive())),
+        recorded: entry
+            .recorded
+            .as_ref()
+            .map_or_else(String::new, |recorded| settings.currency.format(&recorded.amount)),
+        variance: settings.currency.format(&variance),
+        is_unfavorable: is_unfavorable(kind, variance),
+        status,
+        promotion: (entry.recorded.is_none() && !is_undecided).then(|| PromotionInput {
+            kind,
+            name: planned.name.clone(),
+            amount: planned.amount,
+            date: planned.date,
+            account_id: planned.account_id,
+        }),
+    }
+}
+
+// 計画した項目の行と、収入か支出の合計
+fn summarize(
+    kind: ReconciliationKind,
+    reconciled: Vec<ReconciledEntry>,
+    settings: &Settings,
+) -> (Vec<ReconciliationItemSchema>, ReconciliationTotalSchema) {
+    let mut items = Vec::new();
+    let (mut planned_total, mut recorded_total, mut unplanned_total) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+    for entry in &reconciled {
+        let recorded = entry.recorded.as_ref().map_or(Decimal::ZERO, |recorded| recorded.amount);
+        recorded_total += recorded;
+        match &entry.planned {
+            Some(planned) => {
+                planned_total += planned.amount;
+                items.push(to_item_schema(kind, entry, planned, settings));
+            }
+            None => unplanned_total += recorded,
+        }
+    }
+    let variance = recorded_total - planned_total;
+    let total = ReconciliationTotalSchema {
+        planned: settings.currency.format(&planned_total),
+        recorded: settings.currency.format(&recorded_total),
+        unplanned: settings.currency.format(&unplanned_total),
+        variance: settings.currency.format(&variance),
+        is_unfavorable: is_unfavorable(kind, variance),
+    };
+    (items, total)
+}
+
+// アルバイトとテンプレートごとの記録する口座
+fn list_plan_accounts(
+    ctx: &RepoContext,
+    start_date: &DateTime<Local>,
+    end_date: &DateTime<Local>,
+) -> Result<Vec<(EntrySource, Option<u64>)>, anyhow::Error> {
+    let mut accounts = Vec::new();
+    // 辞めた後に入金されるアルバイトもある
+    let since = DateTime::UNIX_EPOCH.with_timezone(&Local);
+    for job in ctx.part_time_job.list_part_time_jobs(&since, end_date)? {
+        accounts.push((EntrySource::new(SourceKind::PartTimeJob, job.id), job.account_id));
+    }
+    for template in ctx.monthly_income.list_monthly_templates(start_date, end_date)? {
+        accounts.push((EntrySource::new(SourceKind::MonthlyIncome, template.id), template.account_id));
+    }
+    for template in ctx.monthly_outcome.list_monthly_templates(start_date, end_date)? {
+        accounts.push((EntrySource::new(SourceKind::MonthlyOutcome, template.id), template.account_id));
+    }
+    Ok(accounts)
+}
+
+// その月の調整金として記録した収入と支出の ID
+fn list_adjustment_ids(ctx: &RepoContext, year: i32, month: u32) -> Result<(Vec<u64>, Vec<u64>), anyhow::Error> {
+    let (mut income_ids, mut outcome_ids) = (Vec::new(), Vec::new());
+    for account in ctx.account.list()? {
+        let Some(account_id) = account.id else { continue };
+        match ctx.adjustment.get(&(account_id, year, month))?.map(|adjustment| adjustment.kind) {
+            Some(detail_service::AdjustmentKind::Income(id)) => income_ids.push(id),
+            Some(detail_service::AdjustmentKind::Outcome(id)) => outcome_ids.push(id),
+            None => {}
+        }
+    }
+    Ok((income_ids, outcome_ids))
+}
+
+fn reconcile_month(ctx: &RepoContext, year: i32, month: u32, settings: &Settings) -> Result<ReconciliationSchema, anyhow::Error> {
+    let period = get_accounting_period(ctx);
+    let calendar = get_business_calendar(ctx);
+    let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+
+    // アルバイトは働いた月ではなく、この月に入金される分と比べる
+    let mut planned_incomes: Vec<PlannedEntry> =
+        project_part_time_job_incomes_paid_between(&start_date, &end_date, &calendar, ctx.part_time_job.as_ref())?
+            .iter()
+            .map(|income| PlannedEntry::from(income.to_income()))
+            .collect();
+    planned_incomes.extend(
+        project_monthly_incomes(year, month, &period, &calendar, ctx.monthly_income.as_ref())?
+            .into_iter()
+            .map(PlannedEntry::from),
+    );
+    let mut planned_outcomes: Vec<PlannedEntry> =
+        project_monthly_outcomes(year, month, &period, &calendar, ctx.monthly_outcome.as_ref())?
+            .into_iter()
+            .map(PlannedEntry::from)
+            .collect();
+    let accounts = list_plan_accounts(ctx, &start_date, &end_date)?;
+    for entry in planned_incomes.iter_mut().chain(planned_outcomes.iter_mut()) {
+        entry.account_id = accounts
+            .iter()
+            .find(|(source, _)| *source == entry.source)
+            .and_then(|(_, account_id)| *account_id);
+    }
+    let (adjustment_income_ids, adjustment_outcome_ids) = list_adjustment_ids(ctx, year, month)?;
+
+    let recorded_incomes = ctx
+        .income
+        .list(&start_date, &end_date)?
+        .into_iter()
+        .map(|income| RecordedEntry {
+            id: income.id.unwrap_or_default(),
+            name: income.name,
+            amount: income.amount,
+            date: income.date,
+        })
+        .collect();
+    let recorded_outcomes = ctx
+        .outcome
+        .list(&start_date, &end_date)?
+        .into_iter()
+        .map(|outcome| RecordedEntry {
+            id: outcome.id.unwrap_or_default(),
+            name: outcome.name,
+            amount: outcome.amount,
+            date: outcome.date,
+        })
+        .collect();
+
+    let (mut items, income) = summarize(
+        ReconciliationKind::Income,
+        reconcile(planned_incomes, exclude_adjustments(recorded_incomes, &adjustment_income_ids)),
+        settings,
+    );
+    let (outcome_items, outcome) = summarize(
+        ReconciliationKind::Outcome,
+        reconcile(planned_outcomes, exclude_adjustments(recorded_outcomes, &adjustment_outcome_ids)),
+        settings,
+    );
+    items.extend(outcome_items);
+    Ok(ReconciliationSchema { items, income, outcome })
+}
+
+// year 年 month 月の計画 (アルバイト、毎月の収入・支出) と明細を比べる
+// 明細は名前が同じものを計画に対応させる (調整金は除く)
+pub fn get_reconciliation(ctx: &RepoContext, year: i32, month: u32) -> ReconciliationSchema {
+    let settings = get_settings(ctx);
+    reconcile_month(ctx, year, month, &settings).unwrap_or_else(|e| {
+        tracing::error!("Failed to reconcile plans: {}", e);
+        ReconciliationSchema::default()
+    })
+}
+
+// 計画どおりの金額と日付で、計画の口座 (なければ既定の口座) の明細に記録する
+pub fn promote_planned_entry(ctx: &RepoContext, input: PromotionInput) -> Result<(), FinanceError> {
+    let account_id = input.account_id.unwrap_or_else(|| get_settings(ctx).default_account_id);
+    ensure_account_exists(ctx, account_id)?;
+    let period = get_accounting_period(ctx);
+    let result = match input.kind {
+        ReconciliationKind::Income => {
+            let income = detail_service::Income::new(input.name, input.amount, input.date, account_id);
+            ctx.transaction(|| detail_service::store_income(income, &period, ctx.income.as_ref(), ctx.saving.as_ref()))
+        }
+        ReconciliationKind::Outcome => {
+            let outcome = detail_service::Outcome::new(input.name, input.amount, input.date, account_id);
+            ctx.transaction(|| detail_service::store_outcome(outcome, &period, ctx.outcome.as_ref(), ctx.saving.as_ref()))
+        }
+    };
+    result
+        .map(|_| ())
+        .inspect_err(|e| tracing::error!("Failed to promote planned entry: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::context::Backend;
+
+    #[test]
+    fn compares_job_incomes_paid_in_the_month() {
+        let ctx = RepoContext::open(&Backend::Dummy).unwrap();
+        let settings = get_settings(&ctx);
+        let reconciliation = reconcile_month(&ctx, 2025, 4, &settings).unwrap();
+        let item = |name: &str| reconciliation.items.iter().find(|item| item.name == name).unwrap().clone();
+
+        // 3月に 8 時間働いた分が 4月 21日に入る
+        let saved = item("アルバイト1");
+        assert_eq!((saved.planned.as_str(), saved.status), ("¥12,000", "未記録"));
+        assert_eq!(saved.promotion.map(|promotion| promotion.amount), Some(Decimal::from(12000)));
+
+        // 時間を入れていなければ 0 円の収入として記録させない
+        let unset = item("アルバイト2");
+        assert_eq!((unset.planned.as_str(), unset.status), ("¥0", "時間未入力"));
+        assert!(unset.promotion.is_none());
+    }
+}
@@ -0,0 +1,403 @@
+use chrono::{DateTime, Local, NaiveDate};
+use dioxus::logger::tracing;
+use rust_decimal::Decimal;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use super::plan::build_forecast;
+use super::plan::{get_payment_timing_label, BusinessDayRoll, ForecastSchema};
+use super::plan_service::future_inspector::MonthEndBalance;
+use super::plan_service::recurrence::{to_local_datetime, MissingDayPolicy, RecurrenceRule};
+use super::plan_service::scenario::{Scenario, ScenarioChange, ScenarioChangeKind};
+use super::setting::{get_accounting_period, get_settings};
+use crate::finance::context::RepoContext;
+use crate::finance::error::FinanceError;
+use crate::finance::setting::Settings;
+
+pub use super::plan_service::scenario::TemplateKind;
+
+// 変更の種類 (フォームで選ぶもの)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScenarioChangeType {
+    AdjustMonthlyIncome,
+    AdjustMonthlyOutcome,
+    RemoveMonthlyIncome,
+    RemoveMonthlyOutcome,
+    AddMonthlyIncome,
+    AddMonthlyOutcome,
+    ChangePartTimeJob,
+    RemovePartTimeJob,
+    AddOneOffOutcome,
+}
+
+impl ScenarioChangeType {
+    pub const ALL: [ScenarioChangeType; 9] = [
+        ScenarioChangeType::AdjustMonthlyIncome,
+        ScenarioChangeType::AdjustMonthlyOutcome,
+        ScenarioChangeType::RemoveMonthlyIncome,
+        ScenarioChangeType::RemoveMonthlyOutcome,
+        ScenarioChangeType::AddMonthlyIncome,
+        ScenarioChangeType::AddMonthlyOutcome,
+        ScenarioChangeType::ChangePartTimeJob,
+        ScenarioChangeType::RemovePartTimeJob,
+        ScenarioChangeType::AddOneOffOutcome,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScenarioChangeType::AdjustMonthlyIncome => "毎月の収入を増減する",
+            ScenarioChangeType::AdjustMonthlyOutcome => "毎月の支出を増減する",
+            ScenarioChangeType::RemoveMonthlyIncome => "毎月の収入をやめる",
+            ScenarioChangeType::RemoveMonthlyOutcome => "毎月の支出をやめる",
+            ScenarioChangeType::AddMonthlyIncome => "毎月の収入を加える",
+            ScenarioChangeType::AddMonthlyOutcome => "毎月の支出を加える",
+            ScenarioChangeType::ChangePartTimeJob => "アルバイトの時給と時間を変える",
+            ScenarioChangeType::RemovePartTimeJob => "アルバイトをやめる",
+            ScenarioChangeType::AddOneOffOutcome => "一度だけ支払う",
+        }
+    }
+
+    // 毎月の収入・支出のどちらを対象にするか
+    pub fn template_kind(&self) -> Option<TemplateKind> {
+        match self {
+            ScenarioChangeType::AdjustMonthlyIncome
+            | ScenarioChangeType::RemoveMonthlyIncome
+            | ScenarioChangeType::AddMonthlyIncome => Some(TemplateKind::Income),
+            ScenarioChangeType::AdjustMonthlyOutcome
+            | ScenarioChangeType::RemoveMonthlyOutcome
+            | ScenarioChangeType::AddMonthlyOutcome => Some(TemplateKind::Outcome),
+            _ => None,
+        }
+    }
+
+    // 既存のテンプレートかアルバイトを選ぶ
+    pub fn needs_target(&self) -> bool {
+        !matches!(
+            self,
+            ScenarioChangeType::AddMonthlyIncome | ScenarioChangeType::AddMonthlyOutcome | ScenarioChangeType::AddOneOffOutcome
+        )
+    }
+
+    pub fn is_part_time_job(&self) -> bool {
+        matches!(self, ScenarioChangeType::ChangePartTimeJob | ScenarioChangeType::RemovePartTimeJob)
+    }
+
+    pub fn needs_name(&self) -> bool {
+        !self.needs_target()
+    }
+
+    pub fn needs_amount(&self) -> bool {
+        !matches!(
+            self,
+            ScenarioChangeType::RemoveMonthlyIncome | ScenarioChangeType::RemoveMonthlyOutcome | ScenarioChangeType::RemovePartTimeJob
+        )
+    }
+
+    pub fn needs_payment_timing(&self) -> bool {
+        matches!(self, ScenarioChangeType::AddMonthlyIncome | ScenarioChangeType::AddMonthlyOutcome)
+    }
+}
+
+// シナリオの変更の入力フォームの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioChangeInput {
+    pub change_type: ScenarioChangeType,
+    // 変更するテンプレートかアルバイト
+    pub target_id: Option<u64>,
+    pub name: String,
+    // 増減する額、加える額、時給のいずれか
+    pub amount: String,
+    pub hour: String,
+    pub payment_timing: RecurrenceRule,
+    // 支払日がない月がある規則のときだけ意味がある
+    pub missing_day: MissingDayPolicy,
+    pub business_day_roll: BusinessDayRoll,
+    pub start_date: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioChangeSchema {
+    pub id: u64,
+    // 2025-04-01 から
+    pub start_date: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioSchema {
+    pub id: u64,
+    pub name: String,
+    pub changes: Vec<ScenarioChangeSchema>,
+}
+
+// 計画とシナリオの月末の残高を並べたもの
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScenarioComparisonSchema {
+    // 先頭は計画そのもの
+    pub names: Vec<String>,
+    pub rows: Vec<ScenarioComparisonRowSchema>,
+    // 予測期間の中で最も少ない月末の残高
+    pub minimums: Vec<String>,
+    // 計画のグラフに重ねるシナリオの日ごとの残高
+    pub series: Vec<ScenarioSeriesSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioSeriesSchema {
+    pub name: String,
+    pub points: Vec<(NaiveDate, Decimal)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioComparisonRowSchema {
+    pub month: String,
+    // 設定の通貨で表示する残高と、下限を下回っているか
+    pub balances: Vec<(String, bool)>,
+}
+
+fn parse_amount(input: &str) -> Result<Decimal, FinanceError> {
+    Decimal::from_str(input.trim()).map_err(|_| FinanceError::InvalidAmount(input.to_string()))
+}
+
+fn parse_start_date(input: &str) -> Result<DateTime<Local>, FinanceError> {
+    let date = NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| FinanceError::InvalidDate(input.to_string()))?;
+    Ok(to_local_datetime(date)?)
+}
+
+fn parse_change(input: ScenarioChangeInput) -> Result<ScenarioChange, FinanceError> {
+    let change_type = input.change_type;
+    let target_id = || input.target_id.ok_or(FinanceError::NoTarget);
+    let name = || {
+        let name = input.name.trim();
+        if name.is_empty() { Err(FinanceError::EmptyName) } else { Ok(name.to_string()) }
+    };
+    let template_kind = change_type.template_kind();
+    let kind = match change_type {
+        ScenarioChangeType::AdjustMonthlyIncome | ScenarioChangeType::AdjustMonthlyOutcome => ScenarioChangeKind::AdjustMonthly {
+            template_kind: template_kind.unwrap(),
+            template_id: target_id()?,
+            delta: parse_amount(&input.amount)?,
+        },
+        ScenarioChangeType::RemoveMonthlyIncome | ScenarioChangeType::RemoveMonthlyOutcome => ScenarioChangeKind::RemoveMonthly {
+            template_kind: template_kind.unwrap(),
+            template_id: target_id()?,
+        },
+        ScenarioChangeType::AddMonthlyIncome | ScenarioChangeType::AddMonthlyOutcome => {
+            input.payment_timing.validate()?;
+            ScenarioChangeKind::AddMonthly {
+                template_kind: template_kind.unwrap(),
+                name: name()?,
+                amount: parse_amount(&input.amount)?,
+                payment_timing: input.payment_timing,
+                missing_day: input.missing_day,
+                business_day_roll: input.business_day_roll,
+            }
+        }
+        ScenarioChangeType::ChangePartTimeJob => ScenarioChangeKind::ChangePartTimeJob {
+            part_time_job_id: target_id()?,
+            hourly_wage: parse_amount(&input.amount)?,
+            hour: Decimal::from_str(input.hour.trim()).map_err(|_| FinanceError::InvalidHour(input.hour.clone()))?,
+        },
+        ScenarioChangeType::RemovePartTimeJob => ScenarioChangeKind::RemovePartTimeJob { part_time_job_id: target_id()? },
+        ScenarioChangeType::AddOneOffOutcome => ScenarioChangeKind::AddOneOffOutcome {
+            name: name()?,
+            amount: parse_amount(&input.amount)?,
+        },
+    };
+    Ok(ScenarioChange {
+        id: None,
+        start_date: parse_start_date(&input.start_date)?,
+        kind,
+    })
+}
+
+fn get_template_name(ctx: &RepoContext, kind: TemplateKind, id: u64) -> String {
+    let name = match kind {
+        TemplateKind::Income => ctx.monthly_income.get_monthly_template_by_id(id).map(|template| template.map(|template| template.name)),
+        TemplateKind::Outcome => ctx.monthly_outcome.get_monthly_template_by_id(id).map(|template| template.map(|template| template.name)),
+    };
+    match name {
+        Ok(Some(name)) => name,
+        Ok(None) => format!("ID: {}", id),
+        Err(e) => {
+            tracing::error!("Failed to get monthly template: {}", e);
+            format!("ID: {}", id)
+        }
+    }
+}
+
+fn get_part_time_job_name(ctx: &RepoContext, id: u64) -> String {
+    match ctx.part_time_job.get_part_time_job_by_id(id) {
+        Ok(Some(job)) => job.name,
+        Ok(None) => format!("ID: {}", id),
+        Err(e) => {
+            tracing::error!("Failed to get part time job: {}", e);
+            format!("ID: {}", id)
+        }
+    }
+}
+
+fn template_kind_label(kind: TemplateKind) -> &'static str {
+    match kind {
+        TemplateKind::Income => "毎月の収入",
+        TemplateKind::Outcome => "毎月の支出",
+    }
+}
+
+// 毎月の支出「家賃」を +¥10,000
+fn describe_change(ctx: &RepoContext, settings: &Settings, change: &ScenarioChange) -> String {
+    let format = |amount: &Decimal| settings.currency.format(amount);
+    match &change.kind {
+        ScenarioChangeKind::AdjustMonthly { template_kind, template_id, delta } => {
+            let sign = if *delta > Decimal::ZERO { "+" } else { "" };
+            let name = get_template_name(ctx, *template_kind, *template_id);
+            format!("{}「{}」を {}{}", template_kind_label(*template_kind), name, sign, format(delta))
+        }
+        ScenarioChangeKind::RemoveMonthly { template_kind, template_id } => {
+            let name = get_template_name(ctx, *template_kind, *template_id);
+            format!("{}「{}」をやめる", template_kind_label(*template_kind), name)
+        }
+        ScenarioChangeKind::AddMonthly { template_kind, name, amount, payment_timing, missing_day, .. } => {
+            let payment_timing = get_payment_timing_label(payment_timing, *missing_day);
+            format!("{}「{}」{} を加える ({})", template_kind_label(*template_kind), name, format(amount), payment_timing)
+        }
+        ScenarioChangeKind::ChangePartTimeJob { part_time_job_id, hourly_wage, hour } => {
+            let name = get_part_time_job_name(ctx, *part_time_job_id);
+            format!("アルバイト「{}」を時給 {}・月 {} 時間にする", name, format(hourly_wage), hour)
+        }
+        ScenarioChangeKind::RemovePartTimeJob { part_time_job_id } => {
+            format!("アルバイト「{}」をやめる", get_part_time_job_name(ctx, *part_time_job_id))
+        }
+        ScenarioChangeKind::AddOneOffOutcome { name, amount } => format!("「{}」{} を支払う", name, format(amount)),
+    }
+}
+
+pub fn get_scenarios(ctx: &RepoContext) -> Vec<ScenarioSchema> {
+    let settings = get_settings(ctx);
+    match ctx.scenario.list_scenarios() {
+        Ok(scenarios) => scenarios
+            .into_iter()
+            .map(|scenario| ScenarioSchema {
+                id: scenario.id.unwrap(),
+                changes: scenario
+                    .changes
+                    .iter()
+                    .map(|change| ScenarioChangeSchema {
+                        id: change.id.unwrap(),
+                        start_date: change.start_date.date_naive().to_string(),
+                        description: describe_change(ctx, &settings, change),
+                    })
+                    .collect(),
+                name: scenario.name,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::error!("Failed to get scenarios: {}", e);
+            vec![]
+        }
+    }
+}
+
+pub fn store_scenario(ctx: &RepoContext, name: String) -> Result<(), FinanceError> {
+    let scenario = Scenario::try_new(name)?;
+    ctx.scenario
+        .store_scenario(scenario)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store scenario: {}", e))
+}
+
+pub fn delete_scenario(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    ctx.scenario
+        .delete_scenario(id)
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to delete scenario: {}", e))
+}
+
+pub fn store_scenario_change(ctx: &RepoContext, scenario_id: u64, input: ScenarioChangeInput) -> Result<(), FinanceError> {
+    let change = parse_change(input)?;
+    if ctx.scenario.get_scenario_by_id(scenario_id)?.is_none() {
+        return Err(FinanceError::NotFound { entity: "シナリオ", id: scenario_id });
+    }
+    ctx.scenario
+        .store_scenario_change(scenario_id, change)
+        .map(|_| ())
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to store scenario change: {}", e))
+}
+
+pub fn delete_scenario_change(ctx: &RepoContext, id: u64) -> Result<(), FinanceError> {
+    ctx.scenario
+        .delete_scenario_change(id)
+        .map_err(FinanceError::from)
+        .inspect_err(|e| tracing::error!("Failed to delete scenario change: {}", e))
+}
+
+// 計画の予測と同じ期間で選んだシナリオを予測し、月末の残高を並べる
+// シナリオの表の列とグラフの線は、シナリオごとに一度だけ予測した同じ結果から作る
+pub fn get_scenario_comparison(ctx: &RepoContext, plan: &ForecastSchema, scenario_ids: &[u64]) -> ScenarioComparisonSchema {
+    let settings = get_settings(ctx);
+    let period = get_accounting_period(ctx);
+    let ((year, month), horizon_months) = (plan.start_ym, plan.horizon_months);
+    let to_minimum = |balances: &[MonthEndBalance]| {
+        balances
+            .iter()
+            .map(|(_, balance)| *balance)
+            .min()
+            .map_or_else(String::new, |minimum| settings.currency.format(&minimum))
+    };
+
+    let mut comparison = ScenarioComparisonSchema {
+        names: vec!["計画".to_string()],
+        minimums: vec![to_minimum(&plan.month_end_balances)],
+        ..ScenarioComparisonSchema::default()
+    };
+    let mut columns = vec![plan.month_end_balances.clone()];
+    for id in scenario_ids {
+        let scenario = match ctx.scenario.get_scenario_by_id(*id) {
+            Ok(Some(scenario)) => scenario,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::error!("Failed to get scenario: {}", e);
+                continue;
+            }
+        };
+        let name = scenario.name.clone();
+        match build_forecast(ctx, year, month, horizon_months, Some(Rc::new(scenario))) {
+            Ok(forecast) => {
+                let balances = forecast.month_end_balances(&period);
+                comparison.minimums.push(to_minimum(&balances));
+                comparison.series.push(ScenarioSeriesSchema {
+                    name: name.clone(),
+                    points: forecast
+                        .results
+                        .iter()
+                        .map(|result| (result.date.date_naive(), result.balance()))
+                        .collect(),
+                });
+                comparison.names.push(name);
+                columns.push(balances);
+            }
+            Err(e) => tracing::error!("Failed to inspect future of {}: {}", name, e),
+        }
+    }
+
+    // どの列も同じ月の並びになる
+    if let Some(first) = columns.first() {
+        comparison.rows = first
+            .iter()
+            .enumerate()
+            .map(|(i, ((year, month), _))| ScenarioComparisonRowSchema {
+                month: format!("{}年{}月", year, month),
+                balances: columns
+                    .iter()
+                    .map(|column| {
+                        let balance = column[i].1;
+                        (settings.currency.format(&balance), settings.is_low_balance(&balance))
+                    })
+                    .collect(),
+            })
+            .collect();
+    }
+    comparison
+}
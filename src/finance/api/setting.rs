@@ -0,0 +1,101 @@
+use chrono::Local;
+use dioxus::logger::tracing;
+
+use super::detail::ensure_account_exists;
+use super::detail_service;
+use crate::finance::context::RepoContext;
+use crate::finance::error::FinanceError;
+use crate::finance::setting::{AccountingPeriod, Settings};
+
+pub use crate::finance::setting::{Currency, Locale};
+
+// 設定画面のフォームの値
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsInput {
+    pub currency: Currency,
+    pub period_start_day: String,
+    pub forecast_horizon_months: String,
+    pub default_account_id: u64,
+    pub locale: Locale,
+    pub low_balance_threshold: String,
+}
+
+// 設定が読めなければ既定値で動かす
+pub(super) fn get_settings(ctx: &RepoContext) -> Settings {
+    ctx.setting.get_settings().unwrap_or_else(|e| {
+        tracing::error!("Failed to get settings: {}", e);
+        Settings::default()
+    })
+}
+
+pub(super) fn get_accounting_period(ctx: &RepoContext) -> AccountingPeriod {
+    get_settings(ctx).period
+}
+
+pub fn get_settings_input(ctx: &RepoContext) -> SettingsInput {
+    let settings = get_settings(ctx);
+    SettingsInput {
+        currency: settings.currency,
+        period_start_day: settings.period.start_day.to_string(),
+        forecast_horizon_months: settings.forecast_horizon_months.to_string(),
+        default_account_id: settings.default_account_id,
+        locale: settings.locale,
+        low_balance_threshold: settings.low_balance_threshold.to_string(),
+    }
+}
+
+// 月の区切りが変わると残高の年月も変わるので、記録から作り直す
+pub fn store_settings(ctx: &RepoContext, input: SettingsInput) -> Result<(), FinanceError> {
+    let settings = Settings::try_new(
+        input.currency,
+        input.period_start_day,
+        input.forecast_horizon_months,
+        input.default_account_id,
+        input.locale,
+        input.low_balance_threshold,
+    )?;
+    ensure_account_exists(ctx, settings.default_account_id)?;
+    let period_changed = settings.period != get_accounting_period(ctx);
+    ctx.transaction(|| {
+        ctx.setting.store_settings(&settings)?;
+        if period_changed {
+            detail_service::rebuild_savings(
+                &settings.period,
+                ctx.account.as_ref(),
+                ctx.income.as_ref(),
+                ctx.outcome.as_ref(),
+                ctx.transfer.as_ref(),
+                ctx.saving.as_ref(),
+            )?;
+        }
+        Ok::<(), anyhow::Error>(())
+    })
+    .map_err(FinanceError::from)
+    .inspect_err(|e| tracing::error!("Failed to store settings: {}", e))
+}
+
+pub fn get_default_account_id(ctx: &RepoContext) -> u64 {
+    get_settings(ctx).default_account_id
+}
+
+pub fn get_forecast_horizon_months(ctx: &RepoContext) -> u32 {
+    get_settings(ctx).forecast_horizon_months
+}
+
+// 今日が含まれる月
+pub fn get_current_year_month(ctx: &RepoContext) -> (i32, u32) {
+    get_accounting_period(ctx).get_year_month(&Local::now())
+}
+
+// 2025-01-25 〜 2025-02-24
+pub fn get_period_label(ctx: &RepoContext, year: i32, month: u32) -> String {
+    match get_accounting_period(ctx).get_opening_and_closing_date(year, month) {
+        Ok((opening_date, closing_date)) => {
+            format!("{} 〜 {}", opening_date.date_naive(), closing_date.date_naive())
+        }
+        Err(e) => {
+            tracing::error!("Failed to get opening and closing date: {}", e);
+            String::new()
+        }
+    }
+}
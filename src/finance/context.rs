@@ -0,0 +1,166 @@
+#[cfg(feature = "sqlite")]
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[cfg(feature = "sqlite")]
+use crate::finance::database;
+use crate::finance::detail::{
+    AccountRepo, AdjustmentRepo, BudgetRepo, CategoryRepo, HolidayRepo, IncomeRepo, OutcomeRepo, SavingRepo, TransferRepo,
+    DummyAccountRepo, DummyAdjustmentRepo, DummyBudgetRepo, DummyCategoryRepo, DummyHolidayRepo, DummyIncomeRepo, DummyOutcomeRepo, DummySavingRepo, DummyTransferRepo,
+};
+#[cfg(feature = "sqlite")]
+use crate::finance::detail::{
+    SqliteAccountRepo, SqliteAdjustmentRepo, SqliteBudgetRepo, SqliteCategoryRepo, SqliteHolidayRepo, SqliteIncomeRepo, SqliteOutcomeRepo, SqliteSavingRepo, SqliteTransferRepo,
+};
+use crate::finance::plan::income::job::PartTimeJobRepo;
+use crate::finance::plan::income::monthly_income::MonthlyIncomeRepo;
+use crate::finance::plan::outcome::monthly_outcome::MonthlyOutcomeRepo;
+use crate::finance::plan::outcome::temporary_outcome::TemporaryOutcomeRepo;
+use crate::finance::plan::scenario::ScenarioRepo;
+use crate::finance::plan::{
+    DummyMonthlyIncomeRepo, DummyMonthlyOutcomeRepo, DummyPartTimeJobRepo, DummyScenarioRepo, DummyTemporaryOutcomeRepo,
+};
+#[cfg(feature = "sqlite")]
+use crate::finance::plan::{
+    SqliteMonthlyIncomeRepo, SqliteMonthlyOutcomeRepo, SqlitePartTimeJobRepo, SqliteScenarioRepo, SqliteTemporaryOutcomeRepo,
+};
+use crate::finance::setting::{DummySettingRepo, SettingRepo};
+#[cfg(feature = "sqlite")]
+use crate::finance::setting::SqliteSettingRepo;
+#[cfg(feature = "sqlite")]
+use crate::finance::transaction::SqliteUnitOfWork;
+use crate::finance::transaction::{self, DummyUnitOfWork, UnitOfWork};
+
+// バックエンドを指定する環境変数 (sqlite, memory, dummy)
+const BACKEND_ENV: &str = "ZAIMU_BACKEND";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    // データベースファイル
+    #[cfg(feature = "sqlite")]
+    Sqlite(PathBuf),
+    // 終了時に破棄されるデータベース
+    #[cfg(feature = "sqlite")]
+    Memory,
+    // サンプルデータ入りのスレッドローカルなコレクション
+    Dummy,
+}
+
+impl Backend {
+    // `--backend <sqlite|memory|dummy>` と `--database <path>` を解釈する
+    // 指定がなければ環境変数、それもなければデフォルトのデータベースファイルを使う
+    // sqlite の機能がなければ dummy だけ
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, anyhow::Error> {
+        let mut backend = std::env::var(BACKEND_ENV).ok();
+        let mut path = None::<String>;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--backend" => {
+                    backend = Some(args.next().ok_or_else(|| anyhow::anyhow!("Missing value for --backend"))?);
+                }
+                "--database" => {
+                    path = Some(args.next().ok_or_else(|| anyhow::anyhow!("Missing value for --database"))?);
+                }
+                _ => (),
+            }
+        }
+        match backend.as_deref() {
+            #[cfg(feature = "sqlite")]
+            None | Some("sqlite") => Ok(Backend::Sqlite(path.map_or_else(database::get_default_database_path, PathBuf::from))),
+            #[cfg(feature = "sqlite")]
+            Some("memory") => Ok(Backend::Memory),
+            #[cfg(not(feature = "sqlite"))]
+            None => match path {
+                Some(_) => Err(anyhow::anyhow!("--database requires the sqlite feature")),
+                None => Ok(Backend::Dummy),
+            },
+            Some("dummy") => Ok(Backend::Dummy),
+            Some(backend) => Err(anyhow::anyhow!("Unknown backend: {}", backend)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RepoContext {
+    pub income: Rc<dyn IncomeRepo>,
+    pub outcome: Rc<dyn OutcomeRepo>,
+    pub adjustment: Rc<dyn AdjustmentRepo>,
+    pub saving: Rc<dyn SavingRepo>,
+    pub category: Rc<dyn CategoryRepo>,
+    pub budget: Rc<dyn BudgetRepo>,
+    pub account: Rc<dyn AccountRepo>,
+    pub transfer: Rc<dyn TransferRepo>,
+    pub holiday: Rc<dyn HolidayRepo>,
+    pub part_time_job: Rc<dyn PartTimeJobRepo>,
+    pub monthly_income: Rc<dyn MonthlyIncomeRepo>,
+    pub monthly_outcome: Rc<dyn MonthlyOutcomeRepo>,
+    pub temporary_outcome: Rc<dyn TemporaryOutcomeRepo>,
+    pub scenario: Rc<dyn ScenarioRepo>,
+    pub setting: Rc<dyn SettingRepo>,
+    pub unit_of_work: Rc<dyn UnitOfWork>,
+}
+
+impl RepoContext {
+    pub fn open(backend: &Backend) -> Result<Self, anyhow::Error> {
+        match backend {
+            #[cfg(feature = "sqlite")]
+            Backend::Sqlite(path) => Ok(Self::sqlite(database::open(path)?)),
+            #[cfg(feature = "sqlite")]
+            Backend::Memory => Ok(Self::sqlite(database::open_in_memory()?)),
+            Backend::Dummy => Ok(Self::dummy()),
+        }
+    }
+
+    // f の中で行ったリポジトリ操作は、すべて反映されるかすべて取り消される
+    pub fn transaction<T, E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<anyhow::Error> + std::fmt::Display,
+    {
+        transaction::transaction(self.unit_of_work.as_ref(), f)
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn sqlite(conn: rusqlite::Connection) -> Self {
+        let conn = Rc::new(conn);
+        Self {
+            income: Rc::new(SqliteIncomeRepo::new(conn.clone())),
+            outcome: Rc::new(SqliteOutcomeRepo::new(conn.clone())),
+            adjustment: Rc::new(SqliteAdjustmentRepo::new(conn.clone())),
+            saving: Rc::new(SqliteSavingRepo::new(conn.clone())),
+            category: Rc::new(SqliteCategoryRepo::new(conn.clone())),
+            budget: Rc::new(SqliteBudgetRepo::new(conn.clone())),
+            account: Rc::new(SqliteAccountRepo::new(conn.clone())),
+            transfer: Rc::new(SqliteTransferRepo::new(conn.clone())),
+            holiday: Rc::new(SqliteHolidayRepo::new(conn.clone())),
+            part_time_job: Rc::new(SqlitePartTimeJobRepo::new(conn.clone())),
+            monthly_income: Rc::new(SqliteMonthlyIncomeRepo::new(conn.clone())),
+            monthly_outcome: Rc::new(SqliteMonthlyOutcomeRepo::new(conn.clone())),
+            temporary_outcome: Rc::new(SqliteTemporaryOutcomeRepo::new(conn.clone())),
+            scenario: Rc::new(SqliteScenarioRepo::new(conn.clone())),
+            setting: Rc::new(SqliteSettingRepo::new(conn.clone())),
+            unit_of_work: Rc::new(SqliteUnitOfWork::new(conn)),
+        }
+    }
+
+    fn dummy() -> Self {
+        Self {
+            income: Rc::new(DummyIncomeRepo::new()),
+            outcome: Rc::new(DummyOutcomeRepo::new()),
+            adjustment: Rc::new(DummyAdjustmentRepo::new()),
+            saving: Rc::new(DummySavingRepo::new()),
+            category: Rc::new(DummyCategoryRepo::new()),
+            budget: Rc::new(DummyBudgetRepo::new()),
+            account: Rc::new(DummyAccountRepo::new()),
+            transfer: Rc::new(DummyTransferRepo::new()),
+            holiday: Rc::new(DummyHolidayRepo::new()),
+            part_time_job: Rc::new(DummyPartTimeJobRepo::new()),
+            monthly_income: Rc::new(DummyMonthlyIncomeRepo::new()),
+            monthly_outcome: Rc::new(DummyMonthlyOutcomeRepo::new()),
+            temporary_outcome: Rc::new(DummyTemporaryOutcomeRepo::new()),
+            scenario: Rc::new(DummyScenarioRepo::new()),
+            setting: Rc::new(DummySettingRepo::new()),
+            unit_of_work: Rc::new(DummyUnitOfWork::new()),
+        }
+    }
+}
@@ -0,0 +1,620 @@
+use rusqlite::{params, Connection};
+use std::fmt;
+
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+// バージョン順に並べる。一度リリースしたマイグレーションは書き換えずに追加する
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "明細 (収入, 支出, 貯金, 調整金)",
+        sql: "
+        CREATE TABLE incomes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            date INTEGER NOT NULL
+        );
+        CREATE INDEX incomes_date ON incomes (date);
+        CREATE TABLE outcomes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            date INTEGER NOT NULL
+        );
+        CREATE INDEX outcomes_date ON outcomes (date);
+        CREATE TABLE savings (
+            year INTEGER NOT NULL,
+            month INTEGER NOT NULL,
+            amount TEXT NOT NULL,
+            PRIMARY KEY (year, month)
+        );
+        CREATE TABLE adjustments (
+            year INTEGER NOT NULL,
+            month INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            entry_id INTEGER NOT NULL,
+            amount TEXT NOT NULL,
+            date INTEGER NOT NULL,
+            PRIMARY KEY (year, month)
+        );
+        ",
+    },
+    Migration {
+        version: 2,
+        description: "計画 (アルバイト, 時給, 月次支出, 臨時支出)",
+        sql: "
+        CREATE TABLE part_time_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            payment_timing TEXT NOT NULL,
+            start_date INTEGER NOT NULL,
+            end_date INTEGER
+        );
+        CREATE TABLE part_time_job_hourly_wages (
+            part_time_job_id INTEGER NOT NULL REFERENCES part_time_jobs (id) ON DELETE CASCADE,
+            hourly_wage TEXT NOT NULL,
+            start_year INTEGER NOT NULL,
+            start_month INTEGER NOT NULL,
+            PRIMARY KEY (part_time_job_id, start_year, start_month)
+        );
+        CREATE TABLE part_time_job_incomes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            part_time_job_id INTEGER NOT NULL REFERENCES part_time_jobs (id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            hourly_wage TEXT NOT NULL,
+            hour TEXT NOT NULL,
+            payment_date INTEGER NOT NULL
+        );
+        CREATE INDEX part_time_job_incomes_payment_date ON part_time_job_incomes (payment_date);
+        CREATE TABLE monthly_outcome_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            payment_timing TEXT NOT NULL,
+            start_date INTEGER NOT NULL,
+            end_date INTEGER
+        );
+        CREATE TABLE monthly_outcomes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            monthly_outcome_template_id INTEGER NOT NULL REFERENCES monthly_outcome_templates (id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            payment_date INTEGER NOT NULL
+        );
+        CREATE INDEX monthly_outcomes_payment_date ON monthly_outcomes (payment_date);
+        CREATE TABLE temporary_outcomes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            date INTEGER NOT NULL
+        );
+        CREATE INDEX temporary_outcomes_date ON temporary_outcomes (date);
+        ",
+    },
+    Migration {
+        version: 3,
+        description: "カテゴリとタグ",
+        sql: "
+        CREATE TABLE categories (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            parent_id INTEGER NULL REFERENCES categories (id)
+        );
+        ALTER TABLE incomes ADD COLUMN category_id INTEGER NULL REFERENCES categories (id);
+        ALTER TABLE outcomes ADD COLUMN category_id INTEGER NULL REFERENCES categories (id);
+        CREATE TABLE income_tags (
+            income_id INTEGER NOT NULL REFERENCES incomes (id) ON DELETE CASCADE,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (income_id, tag)
+        );
+        CREATE TABLE outcome_tags (
+            outcome_id INTEGER NOT NULL REFERENCES outcomes (id) ON DELETE CASCADE,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (outcome_id, tag)
+        );
+        CREATE INDEX income_tags_tag ON income_tags (tag);
+        CREATE INDEX outcome_tags_tag ON outcome_tags (tag);
+        ",
+    },
+    Migration {
+        version: 4,
+        description: "予算",
+        sql: "
+        CREATE TABLE budgets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            target TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            rollover INTEGER NOT NULL,
+            start_year INTEGER NOT NULL,
+            start_month INTEGER NOT NULL
+        );
+        ",
+    },
+    Migration {
+        version: 5,
+        description: "口座 (既存の明細と貯金はメイン口座に移す)",
+        sql: "
+        CREATE TABLE accounts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            kind TEXT NOT NULL
+        );
+        INSERT INTO accounts (id, name, kind) VALUES (1, 'メイン口座', 'bank');
+        -- 既定値のある列を追加するときは外部キー制約を付けられない
+        ALTER TABLE incomes ADD COLUMN account_id INTEGER NOT NULL DEFAULT 1;
+        ALTER TABLE outcomes ADD COLUMN account_id INTEGER NOT NULL DEFAULT 1;
+        CREATE TABLE account_savings (
+            account_id INTEGER NOT NULL DEFAULT 1 REFERENCES accounts (id),
+            year INTEGER NOT NULL,
+            month INTEGER NOT NULL,
+            amount TEXT NOT NULL,
+            PRIMARY KEY (account_id, year, month)
+        );
+        INSERT INTO account_savings (account_id, year, month, amount)
+            SELECT 1, year, month, amount FROM savings;
+        DROP TABLE savings;
+        ALTER TABLE account_savings RENAME TO savings;
+        CREATE TABLE account_adjustments (
+            account_id INTEGER NOT NULL DEFAULT 1 REFERENCES accounts (id),
+            year INTEGER NOT NULL,
+            month INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            entry_id INTEGER NOT NULL,
+            amount TEXT NOT NULL,
+            date INTEGER NOT NULL,
+            PRIMARY KEY (account_id, year, month)
+        );
+        INSERT INTO account_adjustments (account_id, year, month, kind, entry_id, amount, date)
+            SELECT 1, year, month, kind, entry_id, amount, date FROM adjustments;
+        DROP TABLE adjustments;
+        ALTER TABLE account_adjustments RENAME TO adjustments;
+        ",
+    },
+    Migration {
+        version: 6,
+        description: "口座間の振替",
+        sql: "
+        CREATE TABLE transfers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            from_account_id INTEGER NOT NULL REFERENCES accounts (id),
+            to_account_id INTEGER NOT NULL REFERENCES accounts (id),
+            amount TEXT NOT NULL,
+            date INTEGER NOT NULL,
+            memo TEXT NOT NULL
+        );
+        ",
+    },
+    Migration {
+        version: 7,
+        description: "クレジットカードの締め日と引き落とし日",
+        sql: "
+        ALTER TABLE accounts ADD COLUMN closing_day INTEGER;
+        ALTER TABLE accounts ADD COLUMN payment_day INTEGER;
+        ALTER TABLE accounts ADD COLUMN payment_account_id INTEGER REFERENCES accounts (id);
+        ",
+    },
+    Migration {
+        version: 8,
+        description: "毎月の固定収入",
+        sql: "
+        CREATE TABLE monthly_income_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            payment_timing TEXT NOT NULL,
+            start_date INTEGER NOT NULL,
+            end_date INTEGER
+        );
+        CREATE TABLE monthly_incomes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            monthly_income_template_id INTEGER NOT NULL REFERENCES monthly_income_templates (id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            payment_date INTEGER NOT NULL
+        );
+        CREATE INDEX monthly_incomes_payment_date ON monthly_incomes (payment_date);
+        ",
+    },
+    Migration {
+        version: 9,
+        description: "休日と支払日の営業日調整",
+        sql: "
+        CREATE TABLE holidays (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            date INTEGER NOT NULL,
+            name TEXT NOT NULL
+        );
+        ALTER TABLE part_time_jobs ADD COLUMN business_day_roll TEXT NOT NULL DEFAULT 'none';
+        ALTER TABLE monthly_income_templates ADD COLUMN business_day_roll TEXT NOT NULL DEFAULT 'none';
+        ALTER TABLE monthly_outcome_templates ADD COLUMN business_day_roll TEXT NOT NULL DEFAULT 'none';
+        ",
+    },
+    Migration {
+        version: 10,
+        description: "支払日がない月の扱い",
+        sql: "
+        ALTER TABLE part_time_jobs ADD COLUMN missing_day_policy TEXT NOT NULL DEFAULT 'clamp';
+        ALTER TABLE monthly_income_templates ADD COLUMN missing_day_policy TEXT NOT NULL DEFAULT 'clamp';
+        ALTER TABLE monthly_outcome_templates ADD COLUMN missing_day_policy TEXT NOT NULL DEFAULT 'clamp';
+        ",
+    },
+    Migration {
+        version: 11,
+        description: "設定",
+        sql: "
+        CREATE TABLE settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        ",
+    },
+    Migration {
+        version: 12,
+        description: "シナリオ",
+        sql: "
+        CREATE TABLE scenarios (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL
+        );
+        CREATE TABLE scenario_changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            scenario_id INTEGER NOT NULL REFERENCES scenarios (id) ON DELETE CASCADE,
+            start_date INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            template_kind TEXT,
+            target_id INTEGER,
+            name TEXT,
+            amount TEXT,
+            hour TEXT,
+            payment_timing TEXT
+        );
+        ",
+    },
+    Migration {
+        version: 13,
+        description: "計画を記録する口座",
+        sql: "
+        ALTER TABLE part_time_jobs ADD COLUMN account_id INTEGER REFERENCES accounts (id) ON DELETE SET NULL;
+        ALTER TABLE monthly_income_templates ADD COLUMN account_id INTEGER REFERENCES accounts (id) ON DELETE SET NULL;
+        ALTER TABLE monthly_outcome_templates ADD COLUMN account_id INTEGER REFERENCES accounts (id) ON DELETE SET NULL;
+        ",
+    },
+    Migration {
+        version: 14,
+        description: "シナリオで加える毎月の収入・支出の支払日の扱い",
+        sql: "
+        ALTER TABLE scenario_changes ADD COLUMN missing_day_policy TEXT;
+        ALTER TABLE scenario_changes ADD COLUMN business_day_roll TEXT;
+        ",
+    },
+];
+
+// アプリより新しいバージョンで作られたデータベース
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedSchemaVersion {
+    pub found: u32,
+    pub supported: u32,
+}
+
+impl fmt::Display for UnsupportedSchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Database schema version {} is newer than the supported version {}",
+            self.found, self.supported,
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedSchemaVersion {}
+
+pub fn get_schema_version(conn: &Connection) -> Result<u32, anyhow::Error> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+pub fn migrate(conn: &mut Connection) -> Result<(), anyhow::Error> {
+    migrate_with(conn, MIGRATIONS)
+}
+
+fn migrate_with(conn: &mut Connection, migrations: &[Migration]) -> Result<(), anyhow::Error> {
+    let current = get_schema_version(conn)?;
+    let supported = migrations.last().map_or(0, |migration| migration.version);
+    if current > supported {
+        return Err(UnsupportedSchemaVersion { found: current, supported }.into());
+    }
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        );",
+    )?;
+    for migration in migrations.iter().filter(|migration| migration.version > current) {
+        // 各ステップは独立したトランザクションで適用し、失敗したら前のバージョンのまま残す
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)
+            .map_err(|e| anyhow::anyhow!("Migration {} failed: {}", migration.version, e))?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, description, applied_at) VALUES (?1, ?2, strftime('%s', 'now'))",
+            params![migration.version, migration.description],
+        )?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LATEST_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+    // そのバージョンのスキーマで書き込まれたデータ
+    fn insert_fixture(conn: &Connection, version: u32) {
+        let sql = match version {
+            1 => "
+                INSERT INTO incomes (name, amount, date) VALUES ('給料', '200000', 1735657200);
+                INSERT INTO outcomes (name, amount, date) VALUES ('家賃', '80000.5', 1735657200);
+                INSERT INTO savings (year, month, amount) VALUES (2025, 1, '119999.5');
+                INSERT INTO adjustments (year, month, kind, entry_id, amount, date)
+                    VALUES (2025, 1, 'outcome', 1, '0.5', 1735657200);
+            ",
+            2 => "
+                INSERT INTO part_time_jobs (name, payment_timing, start_date, end_date)
+                    VALUES ('アルバイト1', 'next_month_mid:21', 1735657200, NULL);
+                INSERT INTO part_time_job_hourly_wages (part_time_job_id, hourly_wage, start_year, start_month)
+                    VALUES (1, '1500', 2025, 1);
+                INSERT INTO part_time_job_incomes (part_time_job_id, name, hourly_wage, hour, payment_date)
+                    VALUES (1, 'アルバイト1', '1500', '8', 1735657200);
+                INSERT INTO monthly_outcome_templates (name, amount, payment_timing, start_date, end_date)
+                    VALUES ('支出1', '10000', 'mid:15', 1735657200, NULL);
+                INSERT INTO monthly_outcomes (monthly_outcome_template_id, name, amount, payment_date)
+                    VALUES (1, '支出1', '10000', 1735657200);
+                INSERT INTO temporary_outcomes (name, amount, date) VALUES ('臨時支出1', '5000', 1735657200);
+            ",
+            3 => "
+                INSERT INTO categories (name, parent_id) VALUES ('食費', NULL);
+                INSERT INTO categories (name, parent_id) VALUES ('外食', 1);
+                INSERT INTO outcomes (name, amount, date, category_id) VALUES ('ランチ', '1200', 1735657200, 2);
+                INSERT INTO outcome_tags (outcome_id, tag) VALUES (last_insert_rowid(), '友人');
+            ",
+            4 => "
+                INSERT INTO budgets (target, amount, rollover, start_year, start_month)
+                    VALUES ('category:1', '30000', 1, 2025, 1);
+            ",
+            5 => "
+                INSERT INTO accounts (name, kind) VALUES ('Suica', 'e_money');
+                INSERT INTO outcomes (name, amount, date, account_id) VALUES ('電車', '210', 1735657200, 2);
+                INSERT INTO savings (account_id, year, month, amount) VALUES (2, 2025, 1, '2790');
+            ",
+            6 => "
+                INSERT INTO transfers (from_account_id, to_account_id, amount, date, memo)
+                    VALUES (1, 2, '3000', 1735657200, 'チャージ');
+            ",
+            7 => "
+                INSERT INTO accounts (name, kind, closing_day, payment_day, payment_account_id)
+                    VALUES ('カード', 'credit_card', 15, 10, 1);
+            ",
+            8 => "
+                INSERT INTO monthly_income_templates (name, amount, payment_timing, start_date, end_date)
+                    VALUES ('仕送り', '50000', 'mid:25', 1735657200, NULL);
+                INSERT INTO monthly_incomes (monthly_income_template_id, name, amount, payment_date)
+                    VALUES (1, '仕送り', '50000', 1737730800);
+            ",
+            9 => "
+                INSERT INTO holidays (date, name) VALUES (1767106800, '年末休業');
+                UPDATE monthly_income_templates SET business_day_roll = 'previous' WHERE id = 1;
+            ",
+            10 => "
+                INSERT INTO monthly_outcome_templates (name, amount, payment_timing, start_date, end_date, missing_day_policy)
+                    VALUES ('家賃', '80000', 'mid:31', 1735657200, NULL, 'skip');
+            ",
+            11 => "
+                INSERT INTO settings (key, value) VALUES ('period_start_day', '25');
+            ",
+            12 => "
+                INSERT INTO scenarios (name) VALUES ('引っ越し');
+                INSERT INTO scenario_changes (scenario_id, start_date, kind, template_kind, target_id, amount)
+                    VALUES (1, 1743433200, 'adjust_monthly', 'outcome', 1, '10000');
+            ",
+            13 => "
+                UPDATE monthly_outcome_templates SET account_id = 2 WHERE name = '家賃';
+            ",
+            14 => "
+                INSERT INTO scenario_changes
+                    (scenario_id, start_date, kind, template_kind, name, amount, payment_timing, missing_day_policy, business_day_roll)
+                    VALUES (1, 1743433200, 'add_monthly', 'income', '副業', '30000', 'mid:31', 'skip', 'previous');
+            ",
+            _ => "",
+        };
+        conn.execute_batch(sql).unwrap();
+    }
+
+    // 以降のマイグレーションを経てもデータが読めること
+    fn assert_fixture(conn: &Connection, version: u32) {
+        let query = |sql: &str| -> String { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        match version {
+            1 => {
+                assert_eq!(query("SELECT name || ':' || amount || ':' || date FROM incomes"), "給料:200000:1735657200");
+                assert_eq!(query("SELECT name || ':' || amount FROM outcomes WHERE id = 1"), "家賃:80000.5");
+                assert_eq!(query("SELECT amount FROM savings WHERE year = 2025 AND month = 1 ORDER BY rowid LIMIT 1"), "119999.5");
+                assert_eq!(query("SELECT kind || ':' || entry_id || ':' || amount FROM adjustments"), "outcome:1:0.5");
+            }
+            2 => {
+                assert_eq!(query("SELECT name || ':' || payment_timing FROM part_time_jobs"), "アルバイト1:next_month_mid:21");
+                assert_eq!(query("SELECT hourly_wage FROM part_time_job_hourly_wages WHERE part_time_job_id = 1"), "1500");
+                assert_eq!(query("SELECT hourly_wage || 'x' || hour FROM part_time_job_incomes"), "1500x8");
+                assert_eq!(query("SELECT name || ':' || payment_timing FROM monthly_outcome_templates WHERE id = 1"), "支出1:mid:15");
+                assert_eq!(query("SELECT name || ':' || amount FROM monthly_outcomes"), "支出1:10000");
+                assert_eq!(query("SELECT name || ':' || amount FROM temporary_outcomes"), "臨時支出1:5000");
+            }
+            3 => {
+                assert_eq!(
+                    query("SELECT p.name || ' > ' || c.name FROM categories c JOIN categories p ON c.parent_id = p.id"),
+                    "食費 > 外食",
+                );
+                assert_eq!(
+                    query("SELECT o.name || ':' || t.tag FROM outcomes o JOIN outcome_tags t ON t.outcome_id = o.id"),
+                    "ランチ:友人",
+                );
+            }
+            4 => {
+                assert_eq!(
+                    query("SELECT target || ':' || amount || ':' || rollover FROM budgets"),
+                    "category:1:30000:1",
+                );
+            }
+            5 => {
+                assert_eq!(
+                    query("SELECT a.name || ':' || o.name FROM outcomes o JOIN accounts a ON o.account_id = a.id WHERE a.id = 2"),
+                    "Suica:電車",
+                );
+                assert_eq!(query("SELECT amount FROM savings WHERE account_id = 2 AND year = 2025 AND month = 1"), "2790");
+            }
+            6 => {
+                assert_eq!(
+                    query("SELECT from_account_id || '->' || to_account_id || ':' || amount || ':' || memo FROM transfers"),
+                    "1->2:3000:チャージ",
+                );
+            }
+            7 => {
+                assert_eq!(
+                    query("SELECT closing_day || ':' || payment_day || ':' || payment_account_id FROM accounts WHERE kind = 'credit_card'"),
+                    "15:10:1",
+                );
+            }
+            8 => {
+                assert_eq!(query("SELECT name || ':' || payment_timing FROM monthly_income_templates"), "仕送り:mid:25");
+                assert_eq!(query("SELECT name || ':' || amount FROM monthly_incomes"), "仕送り:50000");
+            }
+            9 => {
+                assert_eq!(query("SELECT date || ':' || name FROM holidays"), "1767106800:年末休業");
+                assert_eq!(query("SELECT business_day_roll FROM monthly_income_templates WHERE id = 1"), "previous");
+                assert_eq!(query("SELECT business_day_roll FROM part_time_jobs WHERE id = 1"), "none");
+            }
+            10 => {
+                assert_eq!(
+                    query("SELECT payment_timing || ':' || missing_day_policy FROM monthly_outcome_templates WHERE name = '家賃'"),
+                    "mid:31:skip",
+                );
+                assert_eq!(query("SELECT missing_day_policy FROM part_time_jobs WHERE id = 1"), "clamp");
+            }
+            11 => {
+                assert_eq!(query("SELECT value FROM settings WHERE key = 'period_start_day'"), "25");
+            }
+            12 => {
+                assert_eq!(
+                    query("SELECT s.name || ':' || c.kind || ':' || c.amount FROM scenario_changes c JOIN scenarios s ON c.scenario_id = s.id"),
+                    "引っ越し:adjust_monthly:10000",
+                );
+            }
+            13 => {
+                assert_eq!(query("SELECT account_id || '' FROM monthly_outcome_templates WHERE name = '家賃'"), "2");
+                assert_eq!(query("SELECT IFNULL(account_id, 'none') FROM part_time_jobs WHERE id = 1"), "none");
+            }
+            14 => {
+                assert_eq!(
+                    query("SELECT missing_day_policy || ':' || business_day_roll FROM scenario_changes WHERE kind = 'add_monthly'"),
+                    "skip:previous",
+                );
+                assert_eq!(query("SELECT IFNULL(missing_day_policy, 'none') FROM scenario_changes WHERE kind = 'adjust_monthly'"), "none");
+            }
+            _ => (),
+        }
+    }
+
+    fn open_at(version: u32) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_with(&mut conn, &MIGRATIONS[..version as usize]).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), version);
+        conn
+    }
+
+    #[test]
+    fn versions_are_sequential() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1);
+        }
+        assert_eq!(LATEST_VERSION, MIGRATIONS.len() as u32);
+    }
+
+    #[test]
+    fn migrate_fresh_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), LATEST_VERSION);
+        let applied: u32 = conn
+            .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(applied, LATEST_VERSION);
+    }
+
+    #[test]
+    fn migrate_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), LATEST_VERSION);
+    }
+
+    #[test]
+    fn upgrade_fixture_through_each_step() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            migrate_with(&mut conn, &MIGRATIONS[..=i]).unwrap();
+            assert_eq!(get_schema_version(&conn).unwrap(), migration.version);
+            for version in 1..migration.version {
+                assert_fixture(&conn, version);
+            }
+            insert_fixture(&conn, migration.version);
+            assert_fixture(&conn, migration.version);
+        }
+    }
+
+    #[test]
+    fn upgrade_from_each_version_to_latest() {
+        for start in 0..=LATEST_VERSION {
+            let mut conn = open_at(start);
+            for version in 1..=start {
+                insert_fixture(&conn, version);
+            }
+            migrate(&mut conn).unwrap();
+            assert_eq!(get_schema_version(&conn).unwrap(), LATEST_VERSION);
+            for version in 1..=start {
+                assert_fixture(&conn, version);
+            }
+        }
+    }
+
+    #[test]
+    fn refuse_newer_database() {
+        let mut conn = open_at(LATEST_VERSION);
+        conn.pragma_update(None, "user_version", LATEST_VERSION + 1).unwrap();
+        let err = migrate(&mut conn).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<UnsupportedSchemaVersion>(),
+            Some(&UnsupportedSchemaVersion { found: LATEST_VERSION + 1, supported: LATEST_VERSION }),
+        );
+    }
+
+    #[test]
+    fn failed_migration_is_rolled_back() {
+        let migrations = [
+            Migration { version: 1, description: "ok", sql: "CREATE TABLE a (id INTEGER);" },
+            Migration { version: 2, description: "broken", sql: "CREATE TABLE b (id INTEGER); INSERT INTO missing VALUES (1);" },
+        ];
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert!(migrate_with(&mut conn, &migrations).is_err());
+        assert_eq!(get_schema_version(&conn).unwrap(), 1);
+        let exists: u32 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'b'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(exists, 0);
+    }
+}
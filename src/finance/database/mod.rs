@@ -0,0 +1,59 @@
+use chrono::{DateTime, Local, TimeZone};
+use rusqlite::{types::Type, Connection, Row};
+use rust_decimal::prelude::*;
+use std::path::{Path, PathBuf};
+
+mod migration;
+pub use migration::migrate;
+
+// データベースファイルのパスを上書きする環境変数
+const DATABASE_PATH_ENV: &str = "ZAIMU_DATABASE_PATH";
+const DATABASE_FILE_NAME: &str = "zaimu.sqlite3";
+
+pub fn get_default_database_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(DATABASE_PATH_ENV) {
+        return PathBuf::from(path);
+    }
+    match dirs::data_dir() {
+        Some(dir) => dir.join("zaimu").join(DATABASE_FILE_NAME),
+        None => PathBuf::from(DATABASE_FILE_NAME),
+    }
+}
+
+pub fn open(path: &Path) -> Result<Connection, anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut conn = Connection::open(path)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+pub fn open_in_memory() -> Result<Connection, anyhow::Error> {
+    let mut conn = Connection::open_in_memory()?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+pub(crate) fn to_timestamp(date: &DateTime<Local>) -> i64 {
+    date.timestamp()
+}
+
+pub(crate) fn get_decimal(row: &Row, idx: usize) -> rusqlite::Result<Decimal> {
+    let text: String = row.get(idx)?;
+    Decimal::from_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
+}
+
+pub(crate) fn get_datetime(row: &Row, idx: usize) -> rusqlite::Result<DateTime<Local>> {
+    let timestamp: i64 = row.get(idx)?;
+    Local.timestamp_opt(timestamp, 0)
+        .single()
+        .ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+            idx,
+            Type::Integer,
+            format!("Invalid timestamp: {}", timestamp).into(),
+        ))
+}
@@ -0,0 +1,85 @@
+use crate::finance::error::FinanceError;
+
+// 口座の種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountKind {
+    Bank,
+    Cash,
+    CreditCard,
+    EMoney,
+}
+
+impl AccountKind {
+    pub const ALL: [AccountKind; 4] = [AccountKind::Bank, AccountKind::Cash, AccountKind::CreditCard, AccountKind::EMoney];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AccountKind::Bank => "銀行口座",
+            AccountKind::Cash => "現金",
+            AccountKind::CreditCard => "クレジットカード",
+            AccountKind::EMoney => "電子マネー",
+        }
+    }
+}
+
+// クレジットカードの締め日と引き落とし日
+// 締め日までの利用分を、翌月の引き落とし日に支払い口座からまとめて引き落とす
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardCycle {
+    pub closing_day: u32,
+    pub payment_day: u32,
+    pub payment_account_id: u64,
+}
+
+impl CardCycle {
+    pub fn try_new(closing_day: String, payment_day: String, payment_account_id: u64) -> Result<Self, FinanceError> {
+        let parse_day = |input: String| {
+            input
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|day| (1..=31).contains(day))
+                .ok_or(FinanceError::InvalidDate(input))
+        };
+        Ok(Self {
+            closing_day: parse_day(closing_day)?,
+            payment_day: parse_day(payment_day)?,
+            payment_account_id,
+        })
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}日締め 翌月{}日払い", self.closing_day, self.payment_day)
+    }
+}
+
+// お金の置き場所
+// 収入・支出はすべてどれかの口座に記録され、貯金は口座ごとに管理する
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub id: Option<u64>,
+    pub name: String,
+    pub kind: AccountKind,
+    // クレジットカードのときだけ持つ
+    pub card: Option<CardCycle>,
+}
+
+impl Account {
+    pub fn try_new(name: String, kind: AccountKind, card: Option<CardCycle>) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
+        let card = card.filter(|_| kind == AccountKind::CreditCard);
+        Ok(Self { id: None, name: name.trim().to_string(), kind, card })
+    }
+}
+
+// マイグレーションで作られる口座
+// 口座の概念がなかった頃の明細はすべてここに入る
+pub const DEFAULT_ACCOUNT_ID: u64 = 1;
+
+pub trait AccountRepo {
+    fn list(&self) -> Result<Vec<Account>, anyhow::Error>;
+    fn get_by_id(&self, id: u64) -> Result<Option<Account>, anyhow::Error>;
+    fn store(&self, account: Account) -> Result<u64, anyhow::Error>;
+}
@@ -3,7 +3,8 @@ use rust_decimal::Decimal;
 use super::{
     income::{Income, IncomeRepo}, outcome::{Outcome, OutcomeRepo}, saving::{Saving, SavingRepo, update_saving},
 };
-use crate::finance::setting::get_opening_and_closing_date;
+use crate::finance::error::FinanceError;
+use crate::finance::setting::AccountingPeriod;
 
 #[derive(Debug, Clone)]
 pub enum AdjustmentKind {
@@ -11,7 +12,8 @@ pub enum AdjustmentKind {
     Outcome(u64),
 }
 
-pub type AdjustmentKey = (i32, u32);
+// (口座, 年, 月)
+pub type AdjustmentKey = (u64, i32, u32);
 
 #[derive(Debug, Clone)]
 pub struct Adjustment {
@@ -26,45 +28,44 @@ impl Adjustment {
     }
 }
 
-pub fn create_adjustment<I, O, S, A>(
+// 口座の月末残高を saving_input に合わせるための調整金を記録する
+pub fn create_adjustment(
     saving_input: Decimal,
-    year: i32,
-    month: u32,
-    income_repo: &I,
-    outcome_repo: &O,
-    saving_repo: &S,
-    adjustment_repo: &A,
-)
-    -> Result<(), anyhow::Error>
-where I: IncomeRepo, O: OutcomeRepo, S: SavingRepo, A: AdjustmentRepo,
-{
-    let key = &(year, month);
-    if let Some(adjustment) = adjustment_repo.get(key)? {
-        adjustment_repo.delete(key)?;
+    key: AdjustmentKey,
+    period: &AccountingPeriod,
+    income_repo: &dyn IncomeRepo,
+    outcome_repo: &dyn OutcomeRepo,
+    saving_repo: &dyn SavingRepo,
+    adjustment_repo: &dyn AdjustmentRepo,
+) -> Result<(), FinanceError> {
+    let (account_id, year, month) = key;
+    if let Some(adjustment) = adjustment_repo.get(&key)? {
+        adjustment_repo.delete(&key)?;
         match adjustment.kind {
             AdjustmentKind::Income(id) => income_repo.delete_by_id(id)?,
             AdjustmentKind::Outcome(id) => outcome_repo.delete_by_id(id)?,
         }
     }
-    let (_, closing_date) = get_opening_and_closing_date(year, month)?;
-    let saving = match saving_repo.get(key)? {
+    let (_, closing_date) = period.get_opening_and_closing_date(year, month)?;
+    let saving = match saving_repo.get(account_id, &(year, month))? {
         Some(saving) => saving,
-        None => Saving::new(*key, Decimal::ZERO),
+        None => Saving::new(account_id, (year, month), Decimal::ZERO),
     };
     let adjustment_amount = saving_input - saving.amount;
-    update_saving((year, month), adjustment_amount, saving_repo)?;
+    update_saving(account_id, (year, month), adjustment_amount, period, saving_repo)?;
     let adjustment = if adjustment_amount > Decimal::ZERO {
-        let income = Income::new("調整金".to_string(), adjustment_amount, closing_date);
+        let income = Income::new("調整金".to_string(), adjustment_amount, closing_date, account_id);
         let id = income_repo.store(income)?;
         Adjustment::new(AdjustmentKind::Income(id), adjustment_amount, closing_date)
     } else if adjustment_amount < Decimal::ZERO {
-        let outcome = Outcome::new("調整金".to_string(), -adjustment_amount, closing_date);
+        let outcome = Outcome::new("調整金".to_string(), -adjustment_amount, closing_date, account_id);
         let id = outcome_repo.store(outcome)?;
         Adjustment::new(AdjustmentKind::Outcome(id), -adjustment_amount, closing_date)
     } else {
         return Ok(());
     };
-    adjustment_repo.store(key, adjustment)
+    adjustment_repo.store(&key, adjustment)?;
+    Ok(())
 }
 
 pub trait AdjustmentRepo {
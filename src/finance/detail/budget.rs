@@ -0,0 +1,239 @@
+use chrono::{DateTime, Local};
+use rust_decimal::prelude::*;
+
+use super::category::{get_category_path, get_descendant_category_ids, Category};
+use super::outcome::{Outcome, OutcomeRepo};
+use crate::finance::error::FinanceError;
+use crate::finance::setting::AccountingPeriod;
+use crate::util::get_next_ym;
+
+// 予算の対象
+// 全体, 支出の名前, カテゴリ(子孫のカテゴリを含む)
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetTarget {
+    Overall,
+    OutcomeName(String),
+    Category(u64),
+}
+
+// 毎月の支出の上限
+// rollover が有効なら、使わなかった分を翌月の上限に繰り越す
+#[derive(Debug, Clone)]
+pub struct Budget {
+    pub id: Option<u64>,
+    pub target: BudgetTarget,
+    pub amount: Decimal,
+    pub rollover: bool,
+    pub start_year_and_month: (i32, u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetWarning {
+    // 月の経過日数に対して使いすぎている
+    OverPace,
+    // 上限を超えた
+    Exceeded,
+}
+
+#[derive(Debug, Clone)]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    // 繰越を含めたその月の上限
+    pub limit: Decimal,
+    pub actual: Decimal,
+    pub remaining: Decimal,
+    pub warning: Option<BudgetWarning>,
+}
+
+impl Budget {
+    pub fn try_new(
+        target: BudgetTarget,
+        amount: String,
+        rollover: bool,
+        start_year_and_month: (i32, u32),
+    ) -> Result<Self, FinanceError> {
+        if let BudgetTarget::OutcomeName(name) = &target {
+            if name.trim().is_empty() {
+                return Err(FinanceError::EmptyName);
+            }
+        }
+        let amount = Decimal::from_str(&amount)
+            .ok()
+            .filter(|amount| *amount >= Decimal::ZERO)
+            .ok_or(FinanceError::InvalidAmount(amount))?;
+        Ok(Self { id: None, target, amount, rollover, start_year_and_month })
+    }
+
+    pub fn is_active(&self, year: i32, month: u32) -> bool {
+        (year, month) >= self.start_year_and_month
+    }
+
+    pub fn matches(&self, outcome: &Outcome, categories: &[Category]) -> bool {
+        match &self.target {
+            BudgetTarget::Overall => true,
+            BudgetTarget::OutcomeName(name) => outcome.name == *name,
+            BudgetTarget::Category(id) => outcome
+                .category_id
+                .is_some_and(|category_id| get_descendant_category_ids(*id, categories).contains(&category_id)),
+        }
+    }
+
+    pub fn label(&self, categories: &[Category]) -> String {
+        match &self.target {
+            BudgetTarget::Overall => "全体".to_string(),
+            BudgetTarget::OutcomeName(name) => name.clone(),
+            BudgetTarget::Category(id) => get_category_path(*id, categories),
+        }
+    }
+
+    // その月に対象の支出として記録された合計
+    pub fn get_actual(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        outcome_repo: &dyn OutcomeRepo,
+        categories: &[Category],
+    ) -> Result<Decimal, anyhow::Error> {
+        let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+        Ok(outcome_repo
+            .list(&start_date, &end_date)?
+            .iter()
+            .filter(|outcome| self.matches(outcome, categories))
+            .map(|outcome| outcome.amount)
+            .sum())
+    }
+
+    // 開始月から前月までに使い残した分
+    pub fn get_carryover(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        outcome_repo: &dyn OutcomeRepo,
+        categories: &[Category],
+    ) -> Result<Decimal, anyhow::Error> {
+        let mut carryover = Decimal::ZERO;
+        if !self.rollover {
+            return Ok(carryover);
+        }
+        let mut current_ym = self.start_year_and_month;
+        while current_ym < (year, month) {
+            let actual = self.get_actual(current_ym.0, current_ym.1, period, outcome_repo, categories)?;
+            carryover = (self.amount + carryover - actual).max(Decimal::ZERO);
+            current_ym = get_next_ym(current_ym);
+        }
+        Ok(carryover)
+    }
+}
+
+pub trait BudgetRepo {
+    fn list(&self) -> Result<Vec<Budget>, anyhow::Error>;
+    fn store(&self, budget: Budget) -> Result<u64, anyhow::Error>;
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error>;
+}
+
+pub fn get_budget_status(
+    budget: &Budget,
+    year: i32,
+    month: u32,
+    today: &DateTime<Local>,
+    period: &AccountingPeriod,
+    outcome_repo: &dyn OutcomeRepo,
+    categories: &[Category],
+) -> Result<BudgetStatus, anyhow::Error> {
+    let limit = budget.amount + budget.get_carryover(year, month, period, outcome_repo, categories)?;
+    let actual = budget.get_actual(year, month, period, outcome_repo, categories)?;
+    let remaining = limit - actual;
+    let warning = if actual > limit {
+        Some(BudgetWarning::Exceeded)
+    } else if period.get_year_month(today) == (year, month) {
+        // 日割りした上限を超えていれば注意を出す
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(year, month)?;
+        let days = Decimal::from((closing_date.date_naive() - opening_date.date_naive()).num_days() + 1);
+        let elapsed = Decimal::from((today.date_naive() - opening_date.date_naive()).num_days() + 1);
+        if actual > limit * elapsed / days {
+            Some(BudgetWarning::OverPace)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    Ok(BudgetStatus { budget: budget.clone(), limit, actual, remaining, warning })
+}
+
+pub fn get_budget_statuses(
+    year: i32,
+    month: u32,
+    today: &DateTime<Local>,
+    period: &AccountingPeriod,
+    budget_repo: &dyn BudgetRepo,
+    outcome_repo: &dyn OutcomeRepo,
+    categories: &[Category],
+) -> Result<Vec<BudgetStatus>, anyhow::Error> {
+    budget_repo
+        .list()?
+        .iter()
+        .filter(|budget| budget.is_active(year, month))
+        .map(|budget| get_budget_status(budget, year, month, today, period, outcome_repo, categories))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::repository::DummyOutcomeRepo;
+    use chrono::TimeZone;
+
+    fn date(month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, month, day, 12, 0, 0).unwrap()
+    }
+
+    fn store_lunch(repo: &dyn OutcomeRepo, month: u32, day: u32, amount: i64) {
+        repo.store(Outcome::new("ランチ".to_string(), Decimal::from(amount), date(month, day), 1)).unwrap();
+    }
+
+    fn lunch_budget(rollover: bool) -> Budget {
+        Budget::try_new(BudgetTarget::OutcomeName("ランチ".to_string()), "30000".to_string(), rollover, (2025, 4)).unwrap()
+    }
+
+    #[test]
+    fn carries_over_underspending_but_not_overspending() {
+        let (repo, period) = (DummyOutcomeRepo::new(), AccountingPeriod::default());
+        let today = date(8, 1);
+        store_lunch(&repo, 4, 10, 20000);
+        store_lunch(&repo, 5, 10, 45000);
+
+        // 4月の残り 10000 が 5月に繰り越されるが、それでも超えている
+        let may = get_budget_status(&lunch_budget(true), 2025, 5, &today, &period, &repo, &[]).unwrap();
+        assert_eq!((may.limit, may.remaining), (Decimal::from(40000), Decimal::from(-5000)));
+        assert_eq!(may.warning, Some(BudgetWarning::Exceeded));
+        // 超えた分は 6月の上限から引かない
+        let june = get_budget_status(&lunch_budget(true), 2025, 6, &today, &period, &repo, &[]).unwrap();
+        assert_eq!((june.limit, june.actual, june.warning), (Decimal::from(30000), Decimal::ZERO, None));
+
+        let may = get_budget_status(&lunch_budget(false), 2025, 5, &today, &period, &repo, &[]).unwrap();
+        assert_eq!(may.limit, Decimal::from(30000));
+    }
+
+    #[test]
+    fn warns_only_when_over_the_daily_pace() {
+        let (repo, period) = (DummyOutcomeRepo::new(), AccountingPeriod::default());
+        let budget = lunch_budget(false);
+        // 4月は 30 日なので、10日目の目安は 10000
+        store_lunch(&repo, 4, 1, 10000);
+        let status = get_budget_status(&budget, 2025, 4, &date(4, 10), &period, &repo, &[]).unwrap();
+        assert_eq!(status.warning, None);
+
+        store_lunch(&repo, 4, 2, 1);
+        let status = get_budget_status(&budget, 2025, 4, &date(4, 10), &period, &repo, &[]).unwrap();
+        assert_eq!(status.warning, Some(BudgetWarning::OverPace));
+        // 翌日になれば目安の範囲に戻る
+        let status = get_budget_status(&budget, 2025, 4, &date(4, 11), &period, &repo, &[]).unwrap();
+        assert_eq!(status.warning, None);
+        // 過ぎた月は上限を超えたときだけ注意を出す
+        let status = get_budget_status(&budget, 2025, 4, &date(5, 1), &period, &repo, &[]).unwrap();
+        assert_eq!(status.warning, None);
+    }
+}
@@ -0,0 +1,377 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::collections::HashSet;
+use crate::finance::error::FinanceError;
+
+// 支払日が休日に当たったときにずらす向き
+// 給料は前営業日、カードの引き落としは翌営業日になることが多い
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusinessDayRoll {
+    None,
+    Previous,
+    Next,
+}
+
+impl BusinessDayRoll {
+    pub const ALL: [BusinessDayRoll; 3] = [BusinessDayRoll::None, BusinessDayRoll::Previous, BusinessDayRoll::Next];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BusinessDayRoll::None => "そのまま",
+            BusinessDayRoll::Previous => "前営業日",
+            BusinessDayRoll::Next => "翌営業日",
+        }
+    }
+}
+
+// 利用者が追加した休日 (銀行の休業日や年末年始など)
+#[derive(Debug, Clone)]
+pub struct Holiday {
+    pub id: Option<u64>,
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+impl Holiday {
+    pub fn try_new(date: NaiveDate, name: String) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
+        Ok(Self { id: None, date, name: name.trim().to_string() })
+    }
+}
+
+pub trait HolidayRepo {
+    fn list(&self) -> Result<Vec<Holiday>, anyhow::Error>;
+    fn store(&self, holiday: Holiday) -> Result<u64, anyhow::Error>;
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error>;
+}
+
+// 土日と休日を除いた日を営業日とする
+#[derive(Debug, Clone)]
+pub struct BusinessCalendar {
+    holidays: HashSet<NaiveDate>,
+}
+
+impl BusinessCalendar {
+    // 同梱の祝日だけのカレンダー
+    // 表より後の年は決まりから計算した祝日を使う
+    pub fn japanese() -> Self {
+        let last_listed_year = JAPANESE_HOLIDAYS.last().map_or(0, |(year, ..)| *year);
+        Self {
+            holidays: JAPANESE_HOLIDAYS
+                .iter()
+                .filter_map(|(year, month, day, _)| NaiveDate::from_ymd_opt(*year, *month, *day))
+                .chain((last_listed_year + 1..=LAST_CALCULATED_YEAR).flat_map(calculate_japanese_holidays))
+                .collect(),
+        }
+    }
+
+    // 同梱の祝日に利用者が追加した休日を加えたカレンダー
+    pub fn load(repo: &dyn HolidayRepo) -> Result<Self, anyhow::Error> {
+        let mut calendar = Self::japanese();
+        calendar.holidays.extend(repo.list()?.into_iter().map(|holiday| holiday.date));
+        Ok(calendar)
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    pub fn roll(&self, date: NaiveDate, roll: BusinessDayRoll) -> NaiveDate {
+        let step = |date: NaiveDate| match roll {
+            BusinessDayRoll::None => None,
+            BusinessDayRoll::Previous => date.checked_sub_days(Days::new(1)),
+            BusinessDayRoll::Next => date.checked_add_days(Days::new(1)),
+        };
+        let mut rolled = date;
+        while !self.is_business_day(rolled) {
+            match step(rolled) {
+                Some(next) => rolled = next,
+                None => return date,
+            }
+        }
+        rolled
+    }
+
+    // 月をまたいでしまう場合は逆向きにずらす
+    // アルバイトの給料は入金日の月で管理しているので、別の月に移らないようにする
+    pub fn roll_within_month(&self, date: NaiveDate, roll: BusinessDayRoll) -> NaiveDate {
+        let first = date.with_day(1).unwrap_or(date);
+        let last = first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(date);
+        self.roll_within(date, roll, first, last)
+    }
+
+    // first から last の外に出てしまう場合は逆向きにずらす
+    // 毎月の収入・支出は家計簿の月ごとに管理しているので、別の月に移らないようにする
+    pub fn roll_within(&self, date: NaiveDate, roll: BusinessDayRoll, first: NaiveDate, last: NaiveDate) -> NaiveDate {
+        let contains = |date: &NaiveDate| (first..=last).contains(date);
+        let rolled = self.roll(date, roll);
+        if contains(&rolled) {
+            return rolled;
+        }
+        let reversed = match roll {
+            BusinessDayRoll::Previous => self.roll(date, BusinessDayRoll::Next),
+            BusinessDayRoll::Next => self.roll(date, BusinessDayRoll::Previous),
+            BusinessDayRoll::None => date,
+        };
+        if contains(&reversed) { reversed } else { date }
+    }
+}
+
+// 春分の日・秋分の日の近似式が使える最後の年
+const LAST_CALCULATED_YEAR: i32 = 2099;
+
+// 祝日法の決まりから year 年の祝日 (振替休日・国民の休日を含む) を計算する
+// 春分の日・秋分の日は前年に官報で決まるので、近似式による見込み
+fn calculate_japanese_holidays(year: i32) -> Vec<NaiveDate> {
+    let fixed = [(1, 1), (2, 11), (2, 23), (4, 29), (5, 3), (5, 4), (5, 5), (8, 11), (11, 3), (11, 23)]
+        .into_iter()
+        .filter_map(|(month, day)| NaiveDate::from_ymd_opt(year, month, day));
+    // ハッピーマンデー
+    let mondays = [(1, 2), (7, 3), (9, 3), (10, 2)]
+        .into_iter()
+        .filter_map(|(month, n)| NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Mon, n));
+    let elapsed = (year - 1980) as f64;
+    let leap_days = ((year - 1980) / 4) as f64;
+    let equinox = |month: u32, base: f64| {
+        NaiveDate::from_ymd_opt(year, month, (base + 0.242194 * elapsed - leap_days).floor() as u32)
+    };
+    let equinoxes = [equinox(3, 20.8431), equinox(9, 23.2488)].into_iter().flatten();
+
+    let mut holidays: Vec<NaiveDate> = fixed.chain(mondays).chain(equinoxes).collect();
+    holidays.sort();
+    // 前後を祝日に挟まれた日は国民の休日
+    let sandwiched: Vec<NaiveDate> = holidays
+        .windows(2)
+        .filter(|pair| (pair[1] - pair[0]).num_days() == 2)
+        .filter_map(|pair| pair[0].succ_opt())
+        .collect();
+    holidays.extend(sandwiched);
+    // 日曜日の祝日の後の、祝日でない最初の日は振替休日
+    let substitutes: Vec<NaiveDate> = holidays
+        .iter()
+        .filter(|date| date.weekday() == Weekday::Sun)
+        .filter_map(|date| date.iter_days().skip(1).find(|next| !holidays.contains(next)))
+        .collect();
+    holidays.extend(substitutes);
+    holidays.sort();
+    holidays.dedup();
+    holidays
+}
+
+// 内閣府の「国民の祝日」(振替休日・国民の休日を含む)
+const JAPANESE_HOLIDAYS: &[(i32, u32, u32, &str)] = &[
+    (2024, 1, 1, "元日"),
+    (2024, 1, 8, "成人の日"),
+    (2024, 2, 11, "建国記念の日"),
+    (2024, 2, 12, "振替休日"),
+    (2024, 2, 23, "天皇誕生日"),
+    (2024, 3, 20, "春分の日"),
+    (2024, 4, 29, "昭和の日"),
+    (2024, 5, 3, "憲法記念日"),
+    (2024, 5, 4, "みどりの日"),
+    (2024, 5, 5, "こどもの日"),
+    (2024, 5, 6, "振替休日"),
+    (2024, 7, 15, "海の日"),
+    (2024, 8, 11, "山の日"),
+    (2024, 8, 12, "振替休日"),
+    (2024, 9, 16, "敬老の日"),
+    (2024, 9, 22, "秋分の日"),
+    (2024, 9, 23, "振替休日"),
+    (2024, 10, 14, "スポーツの日"),
+    (2024, 11, 3, "文化の日"),
+    (2024, 11, 4, "振替休日"),
+    (2024, 11, 23, "勤労感謝の日"),
+    (2025, 1, 1, "元日"),
+    (2025, 1, 13, "成人の日"),
+    (2025, 2, 11, "建国記念の日"),
+    (2025, 2, 23, "天皇誕生日"),
+    (2025, 2, 24, "振替休日"),
+    (2025, 3, 20, "春分の日"),
+    (2025, 4, 29, "昭和の日"),
+    (2025, 5, 3, "憲法記念日"),
+    (2025, 5, 4, "みどりの日"),
+    (2025, 5, 5, "こどもの日"),
+    (2025, 5, 6, "振替休日"),
+    (2025, 7, 21, "海の日"),
+    (2025, 8, 11, "山の日"),
+    (2025, 9, 15, "敬老の日"),
+    (2025, 9, 23, "秋分の日"),
+    (2025, 10, 13, "スポーツの日"),
+    (2025, 11, 3, "文化の日"),
+    (2025, 11, 23, "勤労感謝の日"),
+    (2025, 11, 24, "振替休日"),
+    (2026, 1, 1, "元日"),
+    (2026, 1, 12, "成人の日"),
+    (2026, 2, 11, "建国記念の日"),
+    (2026, 2, 23, "天皇誕生日"),
+    (2026, 3, 20, "春分の日"),
+    (2026, 4, 29, "昭和の日"),
+    (2026, 5, 3, "憲法記念日"),
+    (2026, 5, 4, "みどりの日"),
+    (2026, 5, 5, "こどもの日"),
+    (2026, 5, 6, "振替休日"),
+    (2026, 7, 20, "海の日"),
+    (2026, 8, 11, "山の日"),
+    (2026, 9, 21, "敬老の日"),
+    (2026, 9, 22, "国民の休日"),
+    (2026, 9, 23, "秋分の日"),
+    (2026, 10, 12, "スポーツの日"),
+    (2026, 11, 3, "文化の日"),
+    (2026, 11, 23, "勤労感謝の日"),
+    (2027, 1, 1, "元日"),
+    (2027, 1, 11, "成人の日"),
+    (2027, 2, 11, "建国記念の日"),
+    (2027, 2, 23, "天皇誕生日"),
+    (2027, 3, 21, "春分の日"),
+    (2027, 3, 22, "振替休日"),
+    (2027, 4, 29, "昭和の日"),
+    (2027, 5, 3, "憲法記念日"),
+    (2027, 5, 4, "みどりの日"),
+    (2027, 5, 5, "こどもの日"),
+    (2027, 7, 19, "海の日"),
+    (2027, 8, 11, "山の日"),
+    (2027, 9, 20, "敬老の日"),
+    (2027, 9, 23, "秋分の日"),
+    (2027, 10, 11, "スポーツの日"),
+    (2027, 11, 3, "文化の日"),
+    (2027, 11, 23, "勤労感謝の日"),
+    (2028, 1, 1, "元日"),
+    (2028, 1, 10, "成人の日"),
+    (2028, 2, 11, "建国記念の日"),
+    (2028, 2, 23, "天皇誕生日"),
+    (2028, 3, 20, "春分の日"),
+    (2028, 4, 29, "昭和の日"),
+    (2028, 5, 3, "憲法記念日"),
+    (2028, 5, 4, "みどりの日"),
+    (2028, 5, 5, "こどもの日"),
+    (2028, 7, 17, "海の日"),
+    (2028, 8, 11, "山の日"),
+    (2028, 9, 18, "敬老の日"),
+    (2028, 9, 22, "秋分の日"),
+    (2028, 10, 9, "スポーツの日"),
+    (2028, 11, 3, "文化の日"),
+    (2028, 11, 23, "勤労感謝の日"),
+    (2029, 1, 1, "元日"),
+    (2029, 1, 8, "成人の日"),
+    (2029, 2, 11, "建国記念の日"),
+    (2029, 2, 12, "振替休日"),
+    (2029, 2, 23, "天皇誕生日"),
+    (2029, 3, 20, "春分の日"),
+    (2029, 4, 29, "昭和の日"),
+    (2029, 4, 30, "振替休日"),
+    (2029, 5, 3, "憲法記念日"),
+    (2029, 5, 4, "みどりの日"),
+    (2029, 5, 5, "こどもの日"),
+    (2029, 7, 16, "海の日"),
+    (2029, 8, 11, "山の日"),
+    (2029, 9, 17, "敬老の日"),
+    (2029, 9, 23, "秋分の日"),
+    (2029, 9, 24, "振替休日"),
+    (2029, 10, 8, "スポーツの日"),
+    (2029, 11, 3, "文化の日"),
+    (2029, 11, 23, "勤労感謝の日"),
+    (2030, 1, 1, "元日"),
+    (2030, 1, 14, "成人の日"),
+    (2030, 2, 11, "建国記念の日"),
+    (2030, 2, 23, "天皇誕生日"),
+    (2030, 3, 20, "春分の日"),
+    (2030, 4, 29, "昭和の日"),
+    (2030, 5, 3, "憲法記念日"),
+    (2030, 5, 4, "みどりの日"),
+    (2030, 5, 5, "こどもの日"),
+    (2030, 5, 6, "振替休日"),
+    (2030, 7, 15, "海の日"),
+    (2030, 8, 11, "山の日"),
+    (2030, 8, 12, "振替休日"),
+    (2030, 9, 16, "敬老の日"),
+    (2030, 9, 23, "秋分の日"),
+    (2030, 10, 14, "スポーツの日"),
+    (2030, 11, 3, "文化の日"),
+    (2030, 11, 4, "振替休日"),
+    (2030, 11, 23, "勤労感謝の日"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn weekends_and_holidays_are_not_business_days() {
+        let calendar = BusinessCalendar::japanese();
+        assert!(calendar.is_business_day(date(2025, 5, 2)));
+        assert!(!calendar.is_business_day(date(2025, 5, 3)));
+        assert!(!calendar.is_business_day(date(2025, 5, 4)));
+        // 振替休日
+        assert!(!calendar.is_business_day(date(2025, 5, 6)));
+        // 国民の休日
+        assert!(!calendar.is_business_day(date(2026, 9, 22)));
+    }
+
+    #[test]
+    fn rolls_to_previous_or_next_business_day() {
+        let calendar = BusinessCalendar::japanese();
+        // 2025-05-03 〜 05-06 は連休
+        assert_eq!(calendar.roll(date(2025, 5, 4), BusinessDayRoll::Previous), date(2025, 5, 2));
+        assert_eq!(calendar.roll(date(2025, 5, 4), BusinessDayRoll::Next), date(2025, 5, 7));
+        assert_eq!(calendar.roll(date(2025, 5, 4), BusinessDayRoll::None), date(2025, 5, 4));
+        assert_eq!(calendar.roll(date(2025, 5, 7), BusinessDayRoll::Previous), date(2025, 5, 7));
+    }
+
+    #[test]
+    fn rolls_within_month() {
+        let calendar = BusinessCalendar::japanese();
+        // 2025-11-30 は日曜日
+        assert_eq!(calendar.roll(date(2025, 11, 30), BusinessDayRoll::Next), date(2025, 12, 1));
+        assert_eq!(calendar.roll_within_month(date(2025, 11, 30), BusinessDayRoll::Next), date(2025, 11, 28));
+        // 2025-06-01 は日曜日
+        assert_eq!(calendar.roll_within_month(date(2025, 6, 1), BusinessDayRoll::Previous), date(2025, 6, 2));
+        // 25日始まりの月で、2025-05-25 (日曜日) を前営業日にずらすと前の月に移ってしまう
+        let rolled = calendar.roll_within(date(2025, 5, 25), BusinessDayRoll::Previous, date(2025, 5, 25), date(2025, 6, 24));
+        assert_eq!(rolled, date(2025, 5, 26));
+    }
+
+    #[test]
+    fn user_holidays_are_added() {
+        use crate::finance::detail::repository::DummyHolidayRepo;
+
+        let repo = DummyHolidayRepo::new();
+        repo.store(Holiday::try_new(date(2025, 12, 31), "年末休業".to_string()).unwrap()).unwrap();
+        let calendar = BusinessCalendar::load(&repo).unwrap();
+        assert_eq!(calendar.roll(date(2025, 12, 31), BusinessDayRoll::Previous), date(2025, 12, 30));
+        assert!(BusinessCalendar::japanese().is_business_day(date(2025, 12, 31)));
+    }
+
+    #[test]
+    fn calculated_holidays_match_the_listed_ones() {
+        for year in 2024..=2030 {
+            let listed: Vec<NaiveDate> = JAPANESE_HOLIDAYS
+                .iter()
+                .filter(|(listed_year, ..)| *listed_year == year)
+                .map(|(year, month, day, _)| date(*year, *month, *day))
+                .collect();
+            assert_eq!(calculate_japanese_holidays(year), listed, "{}", year);
+        }
+    }
+
+    #[test]
+    fn holidays_after_the_list_are_calculated() {
+        let calendar = BusinessCalendar::japanese();
+        // 2031-01-13 成人の日, 2032-09-20 敬老の日, 2032-09-21 国民の休日, 2032-09-22 秋分の日
+        for holiday in [date(2031, 1, 13), date(2032, 9, 20), date(2032, 9, 21), date(2032, 9, 22)] {
+            assert!(!calendar.is_business_day(holiday), "{}", holiday);
+        }
+        // 2033-01-01 は土曜日なので振替休日はない
+        assert!(calendar.is_business_day(date(2033, 1, 3)));
+        // 2034-01-01 は日曜日なので 2日が振替休日
+        assert!(!calendar.is_business_day(date(2034, 1, 2)));
+        assert_eq!(calendar.roll(date(2036, 5, 3), BusinessDayRoll::Next), date(2036, 5, 7));
+    }
+}
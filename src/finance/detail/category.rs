@@ -0,0 +1,178 @@
+use crate::finance::error::FinanceError;
+
+// 収入・支出の分類
+// parent_id で 食費 > 外食 のような階層を作る
+#[derive(Debug, Clone)]
+pub struct Category {
+    pub id: Option<u64>,
+    pub name: String,
+    pub parent_id: Option<u64>,
+}
+
+impl Category {
+    pub fn try_new(name: String, parent_id: Option<u64>) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
+        Ok(Self { id: None, name: name.trim().to_string(), parent_id })
+    }
+}
+
+pub trait CategoryRepo {
+    fn list(&self) -> Result<Vec<Category>, anyhow::Error>;
+    fn get_by_id(&self, id: u64) -> Result<Option<Category>, anyhow::Error>;
+    fn store(&self, category: Category) -> Result<u64, anyhow::Error>;
+}
+
+pub fn store_category(category: Category, repo: &dyn CategoryRepo) -> Result<u64, FinanceError> {
+    if let Some(parent_id) = category.parent_id {
+        repo.get_by_id(parent_id)?
+            .ok_or(FinanceError::NotFound { entity: "カテゴリ", id: parent_id })?;
+    }
+    Ok(repo.store(category)?)
+}
+
+// ルートから id までの名前を " > " でつなげる
+pub fn get_category_path(id: u64, categories: &[Category]) -> String {
+    let mut names = vec![];
+    let mut current = Some(id);
+    while let Some(id) = current {
+        match categories.iter().find(|category| category.id == Some(id)) {
+            // 壊れたデータで循環していても止まるようにする
+            Some(category) if names.len() < categories.len() => {
+                names.push(category.name.clone());
+                current = category.parent_id;
+            }
+            _ => break,
+        }
+    }
+    names.reverse();
+    names.join(" > ")
+}
+
+// id 自身とその子孫のカテゴリ
+pub fn get_descendant_category_ids(id: u64, categories: &[Category]) -> Vec<u64> {
+    let mut ids = vec![id];
+    let mut i = 0;
+    while i < ids.len() {
+        for category in categories {
+            if let Some(child_id) = category.id {
+                if category.parent_id == Some(ids[i]) && !ids.contains(&child_id) {
+                    ids.push(child_id);
+                }
+            }
+        }
+        i += 1;
+    }
+    ids
+}
+
+// "旅行, 友人" のような入力をタグの一覧にする
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = vec![];
+    for tag in input.split([',', '、']).map(|tag| tag.trim()) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// 明細の絞り込み条件
+// カテゴリは子孫のカテゴリも含めて一致させる
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LedgerFilter {
+    pub account_id: Option<u64>,
+    pub category_id: Option<u64>,
+    pub tag: Option<String>,
+}
+
+impl LedgerFilter {
+    pub fn matches(&self, account_id: u64, category_id: Option<u64>, tags: &[String], categories: &[Category]) -> bool {
+        if self.account_id.is_some_and(|filter_account_id| filter_account_id != account_id) {
+            return false;
+        }
+        if let Some(filter_category_id) = self.category_id {
+            let ids = get_descendant_category_ids(filter_category_id, categories);
+            match category_id {
+                Some(category_id) if ids.contains(&category_id) => (),
+                _ => return false,
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !tags.iter().any(|t| t == tag) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: u64, name: &str, parent_id: Option<u64>) -> Category {
+        Category { id: Some(id), name: name.to_string(), parent_id }
+    }
+
+    // 食費 > 外食 > ランチ、日用品
+    fn categories() -> Vec<Category> {
+        vec![
+            category(1, "食費", None),
+            category(2, "外食", Some(1)),
+            category(3, "ランチ", Some(2)),
+            category(4, "日用品", None),
+        ]
+    }
+
+    #[test]
+    fn builds_category_paths() {
+        let categories = categories();
+        assert_eq!(get_category_path(3, &categories), "食費 > 外食 > ランチ");
+        assert_eq!(get_category_path(4, &categories), "日用品");
+        assert_eq!(get_category_path(9, &categories), "");
+        // 循環していても止まる
+        let cyclic = vec![category(1, "A", Some(2)), category(2, "B", Some(1))];
+        assert_eq!(get_category_path(1, &cyclic), "B > A");
+    }
+
+    #[test]
+    fn collects_descendant_categories() {
+        let categories = categories();
+        assert_eq!(get_descendant_category_ids(1, &categories), vec![1, 2, 3]);
+        assert_eq!(get_descendant_category_ids(2, &categories), vec![2, 3]);
+        assert_eq!(get_descendant_category_ids(4, &categories), vec![4]);
+    }
+
+    #[test]
+    fn filters_by_category_including_children() {
+        let categories = categories();
+        let tags = vec![];
+        let filter = LedgerFilter { category_id: Some(1), ..LedgerFilter::default() };
+        assert!(filter.matches(1, Some(1), &tags, &categories));
+        assert!(filter.matches(1, Some(3), &tags, &categories));
+        assert!(!filter.matches(1, Some(4), &tags, &categories));
+        assert!(!filter.matches(1, None, &tags, &categories));
+        // 子で絞り込んだときに親は含まない
+        let filter = LedgerFilter { category_id: Some(2), ..LedgerFilter::default() };
+        assert!(filter.matches(1, Some(3), &tags, &categories));
+        assert!(!filter.matches(1, Some(1), &tags, &categories));
+    }
+
+    #[test]
+    fn filters_by_tag_and_account() {
+        let categories = categories();
+        let tags = parse_tags("旅行、友人, 旅行");
+        assert_eq!(tags, vec!["旅行".to_string(), "友人".to_string()]);
+        let filter = LedgerFilter { tag: Some("友人".to_string()), ..LedgerFilter::default() };
+        assert!(filter.matches(1, None, &tags, &categories));
+        assert!(!filter.matches(1, None, &[], &categories));
+        // 条件はすべて満たす必要がある
+        let filter = LedgerFilter { account_id: Some(2), category_id: Some(1), tag: Some("旅行".to_string()) };
+        assert!(filter.matches(2, Some(2), &tags, &categories));
+        assert!(!filter.matches(1, Some(2), &tags, &categories));
+        assert!(!filter.matches(2, Some(4), &tags, &categories));
+        assert!(LedgerFilter::default().matches(1, None, &[], &categories));
+    }
+}
@@ -1,5 +1,8 @@
-use chrono::{DateTime, Local, TimeZone};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
 use rust_decimal::prelude::*;
+use super::saving::{update_saving, SavingRepo};
+use crate::finance::error::FinanceError;
+use crate::finance::setting::AccountingPeriod;
 
 #[derive(Debug, Clone)]
 pub struct Income {
@@ -7,33 +10,57 @@ pub struct Income {
     pub name: String,
     pub amount: Decimal,
     pub date: DateTime<Local>,
+    pub account_id: u64,
+    pub category_id: Option<u64>,
+    pub tags: Vec<String>,
 }
 
 impl Income {
-    pub fn new(name: String, amount: Decimal, date: DateTime<Local>) -> Self {
-        Self { id: None, name, amount, date }
+    pub fn new(name: String, amount: Decimal, date: DateTime<Local>, account_id: u64) -> Self {
+        Self { id: None, name, amount, date, account_id, category_id: None, tags: vec![] }
     }
     
-    pub fn try_new(name: String, amount: String, year: i32, month: u32, day: u32) -> Result<Self, anyhow::Error> {
+    pub fn try_new(
+        name: String,
+        amount: String,
+        date: NaiveDate,
+        account_id: u64,
+        category_id: Option<u64>,
+        tags: Vec<String>,
+    ) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
         let amount = Decimal::from_str(&amount)
-            .map_err(|e| anyhow::anyhow!("Invalid amount '{}': {}", amount, e))?;
+            .map_err(|_| FinanceError::InvalidAmount(amount))?;
 
-        let date = Local.with_ymd_and_hms(year, month, day, 0, 0, 0)
+        let date = Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
             .single()
-            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))?;
+            .ok_or_else(|| FinanceError::InvalidDate(date.to_string()))?;
 
-        Ok(Self { id: None, name, amount, date })
+        Ok(Self { id: None, name, amount, date, account_id, category_id, tags })
     }
 
-    pub fn try_update(id: u64, name: String, amount: String, year: i32, month: u32, day: u32) -> Result<Self, anyhow::Error> {
+    pub fn try_update(
+        id: u64,
+        name: String,
+        amount: String,
+        date: NaiveDate,
+        account_id: u64,
+        category_id: Option<u64>,
+        tags: Vec<String>,
+    ) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
         let amount = Decimal::from_str(&amount)
-            .map_err(|e| anyhow::anyhow!("Invalid amount '{}': {}", amount, e))?;
+            .map_err(|_| FinanceError::InvalidAmount(amount))?;
 
-        let date = Local.with_ymd_and_hms(year, month, day, 0, 0, 0)
+        let date = Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
             .single()
-            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))?;
+            .ok_or_else(|| FinanceError::InvalidDate(date.to_string()))?;
 
-        Ok(Self { id: Some(id), name, amount, date })
+        Ok(Self { id: Some(id), name, amount, date, account_id, category_id, tags })
     }
 }
 
@@ -43,4 +70,45 @@ pub trait IncomeRepo {
     fn store(&self, income: Income) -> Result<u64, anyhow::Error>;
     fn update(&self, income: Income) -> Result<(), anyhow::Error>;
     fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error>;
-}
\ No newline at end of file
+}
+
+pub fn store_income(
+    income: Income,
+    period: &AccountingPeriod,
+    income_repo: &dyn IncomeRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<u64, FinanceError> {
+    let id = income_repo.store(income.clone())?;
+    update_saving(income.account_id, period.get_year_month(&income.date), income.amount, period, saving_repo)?;
+    Ok(id)
+}
+
+pub fn update_income(
+    income: Income,
+    period: &AccountingPeriod,
+    income_repo: &dyn IncomeRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<(), FinanceError> {
+    let id = income.id.ok_or_else(|| anyhow::anyhow!("Income has no id"))?;
+    let prev = income_repo
+        .get_by_id(id)?
+        .ok_or(FinanceError::NotFound { entity: "収入", id })?;
+    income_repo.update(income.clone())?;
+    update_saving(prev.account_id, period.get_year_month(&prev.date), -prev.amount, period, saving_repo)?;
+    update_saving(income.account_id, period.get_year_month(&income.date), income.amount, period, saving_repo)?;
+    Ok(())
+}
+
+pub fn delete_income(
+    id: u64,
+    period: &AccountingPeriod,
+    income_repo: &dyn IncomeRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<(), FinanceError> {
+    let Some(prev) = income_repo.get_by_id(id)? else {
+        return Ok(());
+    };
+    income_repo.delete_by_id(id)?;
+    update_saving(prev.account_id, period.get_year_month(&prev.date), -prev.amount, period, saving_repo)?;
+    Ok(())
+}
@@ -2,10 +2,22 @@ pub mod income;
 pub mod outcome;
 pub mod adjustment;
 pub mod saving;
+pub mod category;
+pub mod budget;
+pub mod account;
+pub mod transfer;
+pub mod statement;
+pub mod calendar;
 pub mod repository;
 
 pub use income::*;
 pub use outcome::*;
 pub use adjustment::*;
 pub use saving::*;
+pub use category::*;
+pub use budget::*;
+pub use account::*;
+pub use transfer::*;
+pub use statement::*;
+pub use calendar::*;
 pub use repository::*;
\ No newline at end of file
@@ -1,5 +1,8 @@
-use chrono::{DateTime, Local, TimeZone};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
 use rust_decimal::prelude::*;
+use super::saving::{update_saving, SavingRepo};
+use crate::finance::error::FinanceError;
+use crate::finance::setting::AccountingPeriod;
 
 #[derive(Debug, Clone)]
 pub struct Outcome {
@@ -7,33 +10,57 @@ pub struct Outcome {
     pub name: String,
     pub amount: Decimal,
     pub date: DateTime<Local>,
+    pub account_id: u64,
+    pub category_id: Option<u64>,
+    pub tags: Vec<String>,
 }
 
 impl Outcome {
-    pub fn new(name: String, amount: Decimal, date: DateTime<Local>) -> Self {
-        Self { id: None, name, amount, date }
+    pub fn new(name: String, amount: Decimal, date: DateTime<Local>, account_id: u64) -> Self {
+        Self { id: None, name, amount, date, account_id, category_id: None, tags: vec![] }
     }
 
-    pub fn try_new(name: String, amount: String, year: i32, month: u32, day: u32) -> Result<Self, anyhow::Error> {
+    pub fn try_new(
+        name: String,
+        amount: String,
+        date: NaiveDate,
+        account_id: u64,
+        category_id: Option<u64>,
+        tags: Vec<String>,
+    ) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
         let amount = Decimal::from_str(&amount)
-            .map_err(|e| anyhow::anyhow!("Invalid amount '{}': {}", amount, e))?;
+            .map_err(|_| FinanceError::InvalidAmount(amount))?;
 
-        let date = Local.with_ymd_and_hms(year, month, day, 0, 0, 0)
+        let date = Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
             .single()
-            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))?;
+            .ok_or_else(|| FinanceError::InvalidDate(date.to_string()))?;
 
-        Ok(Self { id: None, name, amount, date })
+        Ok(Self { id: None, name, amount, date, account_id, category_id, tags })
     }
 
-    pub fn try_update(id: u64, name: String, amount: String, year: i32, month: u32, day: u32) -> Result<Self, anyhow::Error> {
+    pub fn try_update(
+        id: u64,
+        name: String,
+        amount: String,
+        date: NaiveDate,
+        account_id: u64,
+        category_id: Option<u64>,
+        tags: Vec<String>,
+    ) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
         let amount = Decimal::from_str(&amount)
-            .map_err(|e| anyhow::anyhow!("Invalid amount '{}': {}", amount, e))?;
+            .map_err(|_| FinanceError::InvalidAmount(amount))?;
 
-        let date = Local.with_ymd_and_hms(year, month, day, 0, 0, 0)
+        let date = Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
             .single()
-            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))?;
+            .ok_or_else(|| FinanceError::InvalidDate(date.to_string()))?;
 
-        Ok(Self { id: Some(id), name, amount, date })
+        Ok(Self { id: Some(id), name, amount, date, account_id, category_id, tags })
     }
 }
 
@@ -44,3 +71,44 @@ pub trait OutcomeRepo {
     fn update(&self, outcome: Outcome) -> Result<(), anyhow::Error>;
     fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error>;
 }
+
+pub fn store_outcome(
+    outcome: Outcome,
+    period: &AccountingPeriod,
+    outcome_repo: &dyn OutcomeRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<u64, FinanceError> {
+    let id = outcome_repo.store(outcome.clone())?;
+    update_saving(outcome.account_id, period.get_year_month(&outcome.date), -outcome.amount, period, saving_repo)?;
+    Ok(id)
+}
+
+pub fn update_outcome(
+    outcome: Outcome,
+    period: &AccountingPeriod,
+    outcome_repo: &dyn OutcomeRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<(), FinanceError> {
+    let id = outcome.id.ok_or_else(|| anyhow::anyhow!("Outcome has no id"))?;
+    let prev = outcome_repo
+        .get_by_id(id)?
+        .ok_or(FinanceError::NotFound { entity: "支出", id })?;
+    outcome_repo.update(outcome.clone())?;
+    update_saving(prev.account_id, period.get_year_month(&prev.date), prev.amount, period, saving_repo)?;
+    update_saving(outcome.account_id, period.get_year_month(&outcome.date), -outcome.amount, period, saving_repo)?;
+    Ok(())
+}
+
+pub fn delete_outcome(
+    id: u64,
+    period: &AccountingPeriod,
+    outcome_repo: &dyn OutcomeRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<(), FinanceError> {
+    let Some(prev) = outcome_repo.get_by_id(id)? else {
+        return Ok(());
+    };
+    outcome_repo.delete_by_id(id)?;
+    update_saving(prev.account_id, period.get_year_month(&prev.date), prev.amount, period, saving_repo)?;
+    Ok(())
+}
@@ -7,22 +7,47 @@ use crate::finance::detail::income::*;
 use crate::finance::detail::outcome::*;
 use crate::finance::detail::adjustment::*;
 use crate::finance::detail::saving::*;
+use crate::finance::detail::category::*;
+use crate::finance::detail::budget::*;
+use crate::finance::detail::account::*;
+use crate::finance::detail::transfer::*;
+use crate::finance::detail::calendar::*;
 
 pub struct DummyIncomeRepo;
 
 thread_local! {
     static INCOME_COLLECTION: RefCell<HashMap<u64, Income>> = RefCell::new(HashMap::from_iter(vec![
-        (1, Income { id: Some(1), name: "Income 1".to_string(), amount: dec!(100000), date: Local::now() }),
-        (2, Income { id: Some(2), name: "Income 2".to_string(), amount: dec!(50000), date: Local::now() }),
+        (1, Income { id: Some(1), name: "Income 1".to_string(), amount: dec!(100000), date: Local::now(), account_id: 1, category_id: None, tags: vec![] }),
+        (2, Income { id: Some(2), name: "Income 2".to_string(), amount: dec!(50000), date: Local::now(), account_id: 1, category_id: None, tags: vec![] }),
     ]));
     static OUTCOME_COLLECTION: RefCell<HashMap<u64, Outcome>> = RefCell::new(HashMap::from_iter(vec![
-        (1, Outcome { id: Some(1), name: "Outcome 1".to_string(), amount: dec!(10000), date: Local::now() }),
-        (2, Outcome { id: Some(2), name: "Outcome 2".to_string(), amount: dec!(5000), date: Local::now() }),
+        (1, Outcome { id: Some(1), name: "Outcome 1".to_string(), amount: dec!(10000), date: Local::now(), account_id: 1, category_id: None, tags: vec![] }),
+        (2, Outcome { id: Some(2), name: "Outcome 2".to_string(), amount: dec!(5000), date: Local::now(), account_id: 1, category_id: None, tags: vec![] }),
     ]));
+    static CATEGORY_COLLECTION: RefCell<HashMap<u64, Category>> = RefCell::new(HashMap::from_iter(vec![
+        (1, Category { id: Some(1), name: "食費".to_string(), parent_id: None }),
+        (2, Category { id: Some(2), name: "外食".to_string(), parent_id: Some(1) }),
+    ]));
+    static BUDGET_COLLECTION: RefCell<HashMap<u64, Budget>> = RefCell::new(HashMap::from_iter(vec![
+        (1, Budget { id: Some(1), target: BudgetTarget::Category(1), amount: dec!(30000), rollover: true, start_year_and_month: (2025, 1) }),
+    ]));
+    static ACCOUNT_COLLECTION: RefCell<HashMap<u64, Account>> = RefCell::new(HashMap::from_iter(vec![
+        (1, Account { id: Some(1), name: "メイン口座".to_string(), kind: AccountKind::Bank, card: None }),
+        (2, Account { id: Some(2), name: "財布".to_string(), kind: AccountKind::Cash, card: None }),
+        (3, Account { id: Some(3), name: "Suica".to_string(), kind: AccountKind::EMoney, card: None }),
+        (4, Account {
+            id: Some(4),
+            name: "カード".to_string(),
+            kind: AccountKind::CreditCard,
+            card: Some(CardCycle { closing_day: 15, payment_day: 10, payment_account_id: 1 }),
+        }),
+    ]));
+    static TRANSFER_COLLECTION: RefCell<HashMap<u64, Transfer>> = RefCell::new(HashMap::from_iter(vec![]));
+    static HOLIDAY_COLLECTION: RefCell<HashMap<u64, Holiday>> = RefCell::new(HashMap::from_iter(vec![]));
     static ADJUSTMENT_COLLECTION: RefCell<HashMap<AdjustmentKey, Adjustment>> = RefCell::new(HashMap::from_iter(vec![]));
-    static SAVING_COLLECTION: RefCell<HashMap<SavingKey, Saving>> = RefCell::new(HashMap::from_iter(vec![
-        ((2025, 1), Saving { key: (2025, 1), amount: dec!(100000) }),
-        ((2025, 2), Saving { key: (2025, 2), amount: dec!(235000) }),
+    static SAVING_COLLECTION: RefCell<HashMap<(u64, SavingKey), Saving>> = RefCell::new(HashMap::from_iter(vec![
+        ((1, (2025, 1)), Saving { account_id: 1, key: (2025, 1), amount: dec!(100000) }),
+        ((1, (2025, 2)), Saving { account_id: 1, key: (2025, 2), amount: dec!(235000) }),
     ]));
 }
 
@@ -150,20 +175,30 @@ impl DummyAdjustmentRepo {
 pub struct DummySavingRepo;
 
 impl SavingRepo for DummySavingRepo {
-    fn get(&self, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error> {
+    fn get(&self, account_id: u64, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error> {
         Ok(SAVING_COLLECTION.with(|collection| {
-            collection.borrow().get(key).cloned()
+            collection.borrow().get(&(account_id, *key)).cloned()
         }))
     }
-    fn store(&self, key: &SavingKey, saving: Saving) -> Result<(), anyhow::Error> {
+    fn get_latest(&self, account_id: u64, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error> {
+        Ok(SAVING_COLLECTION.with(|collection| {
+            collection
+                .borrow()
+                .values()
+                .filter(|saving| saving.account_id == account_id && saving.key <= *key)
+                .max_by_key(|saving| saving.key)
+                .cloned()
+        }))
+    }
+    fn store(&self, saving: Saving) -> Result<(), anyhow::Error> {
         SAVING_COLLECTION.with(|collection| {
-            collection.borrow_mut().insert(*key, saving);
+            collection.borrow_mut().insert((saving.account_id, saving.key), saving);
         });
         Ok(())
     }
-    fn update(&self, key: &SavingKey, saving: Saving) -> Result<(), anyhow::Error> {
+    fn update(&self, saving: Saving) -> Result<(), anyhow::Error> {
         SAVING_COLLECTION.with(|collection| {
-            collection.borrow_mut().insert(*key, saving);
+            collection.borrow_mut().insert((saving.account_id, saving.key), saving);
         });
         Ok(())
     }
@@ -173,4 +208,191 @@ impl DummySavingRepo {
     pub fn new() -> Self {
         Self
     }
-}
\ No newline at end of file
+}
+
+pub struct DummyCategoryRepo;
+
+impl CategoryRepo for DummyCategoryRepo {
+    fn list(&self) -> Result<Vec<Category>, anyhow::Error> {
+        let mut categories: Vec<Category> = CATEGORY_COLLECTION
+            .with(|collection| collection.borrow().values().cloned().collect());
+        categories.sort_by_key(|category| category.id);
+        Ok(categories)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Category>, anyhow::Error> {
+        Ok(CATEGORY_COLLECTION.with(|collection| collection.borrow().get(&id).cloned()))
+    }
+    fn store(&self, mut category: Category) -> Result<u64, anyhow::Error> {
+        let id = CATEGORY_COLLECTION.with(|collection| {
+            let id = collection.borrow().len() as u64 + 1;
+            category.id = Some(id);
+            collection.borrow_mut().insert(id, category);
+            id
+        });
+        Ok(id)
+    }
+}
+
+impl DummyCategoryRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+pub struct DummyBudgetRepo;
+
+impl BudgetRepo for DummyBudgetRepo {
+    fn list(&self) -> Result<Vec<Budget>, anyhow::Error> {
+        let mut budgets: Vec<Budget> = BUDGET_COLLECTION
+            .with(|collection| collection.borrow().values().cloned().collect());
+        budgets.sort_by_key(|budget| budget.id);
+        Ok(budgets)
+    }
+    fn store(&self, mut budget: Budget) -> Result<u64, anyhow::Error> {
+        let id = BUDGET_COLLECTION.with(|collection| {
+            let id = collection.borrow().keys().max().copied().unwrap_or(0) + 1;
+            budget.id = Some(id);
+            collection.borrow_mut().insert(id, budget);
+            id
+        });
+        Ok(id)
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        BUDGET_COLLECTION.with(|collection| {
+            collection.borrow_mut().remove(&id);
+        });
+        Ok(())
+    }
+}
+
+impl DummyBudgetRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+pub struct DummyAccountRepo;
+
+impl AccountRepo for DummyAccountRepo {
+    fn list(&self) -> Result<Vec<Account>, anyhow::Error> {
+        let mut accounts: Vec<Account> = ACCOUNT_COLLECTION
+            .with(|collection| collection.borrow().values().cloned().collect());
+        accounts.sort_by_key(|account| account.id);
+        Ok(accounts)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Account>, anyhow::Error> {
+        Ok(ACCOUNT_COLLECTION.with(|collection| collection.borrow().get(&id).cloned()))
+    }
+    fn store(&self, mut account: Account) -> Result<u64, anyhow::Error> {
+        let id = ACCOUNT_COLLECTION.with(|collection| {
+            let id = collection.borrow().len() as u64 + 1;
+            account.id = Some(id);
+            collection.borrow_mut().insert(id, account);
+            id
+        });
+        Ok(id)
+    }
+}
+
+impl DummyAccountRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+pub struct DummyTransferRepo;
+
+impl TransferRepo for DummyTransferRepo {
+    fn list(&self, start_date: &DateTime<Local>, end_date: &DateTime<Local>) -> Result<Vec<Transfer>, anyhow::Error> {
+        let mut transfers: Vec<Transfer> = TRANSFER_COLLECTION.with(|collection| {
+            collection
+                .borrow()
+                .values()
+                .filter(|transfer| transfer.date >= *start_date && transfer.date <= *end_date)
+                .cloned()
+                .collect()
+        });
+        transfers.sort_by_key(|transfer| (transfer.date, transfer.id));
+        Ok(transfers)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Transfer>, anyhow::Error> {
+        Ok(TRANSFER_COLLECTION.with(|collection| collection.borrow().get(&id).cloned()))
+    }
+    fn store(&self, mut transfer: Transfer) -> Result<u64, anyhow::Error> {
+        let id = TRANSFER_COLLECTION.with(|collection| {
+            let id = collection.borrow().keys().max().copied().unwrap_or(0) + 1;
+            transfer.id = Some(id);
+            collection.borrow_mut().insert(id, transfer);
+            id
+        });
+        Ok(id)
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        TRANSFER_COLLECTION.with(|collection| {
+            collection.borrow_mut().remove(&id);
+        });
+        Ok(())
+    }
+}
+
+impl DummyTransferRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+pub struct DummyHolidayRepo;
+
+impl HolidayRepo for DummyHolidayRepo {
+    fn list(&self) -> Result<Vec<Holiday>, anyhow::Error> {
+        let mut holidays: Vec<Holiday> = HOLIDAY_COLLECTION
+            .with(|collection| collection.borrow().values().cloned().collect());
+        holidays.sort_by_key(|holiday| (holiday.date, holiday.id));
+        Ok(holidays)
+    }
+    fn store(&self, mut holiday: Holiday) -> Result<u64, anyhow::Error> {
+        let id = HOLIDAY_COLLECTION.with(|collection| {
+            let id = collection.borrow().keys().max().copied().unwrap_or(0) + 1;
+            holiday.id = Some(id);
+            collection.borrow_mut().insert(id, holiday);
+            id
+        });
+        Ok(id)
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        HOLIDAY_COLLECTION.with(|collection| {
+            collection.borrow_mut().remove(&id);
+        });
+        Ok(())
+    }
+}
+
+impl DummyHolidayRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// 現在のコレクションを退避し、それを書き戻す関数を返す
+pub fn snapshot_collections() -> Box<dyn FnOnce()> {
+    let incomes = INCOME_COLLECTION.with(|collection| collection.borrow().clone());
+    let outcomes = OUTCOME_COLLECTION.with(|collection| collection.borrow().clone());
+    let adjustments = ADJUSTMENT_COLLECTION.with(|collection| collection.borrow().clone());
+    let savings = SAVING_COLLECTION.with(|collection| collection.borrow().clone());
+    let categories = CATEGORY_COLLECTION.with(|collection| collection.borrow().clone());
+    let budgets = BUDGET_COLLECTION.with(|collection| collection.borrow().clone());
+    let accounts = ACCOUNT_COLLECTION.with(|collection| collection.borrow().clone());
+    let transfers = TRANSFER_COLLECTION.with(|collection| collection.borrow().clone());
+    let holidays = HOLIDAY_COLLECTION.with(|collection| collection.borrow().clone());
+    Box::new(move || {
+        INCOME_COLLECTION.with(|collection| *collection.borrow_mut() = incomes);
+        OUTCOME_COLLECTION.with(|collection| *collection.borrow_mut() = outcomes);
+        ADJUSTMENT_COLLECTION.with(|collection| *collection.borrow_mut() = adjustments);
+        SAVING_COLLECTION.with(|collection| *collection.borrow_mut() = savings);
+        CATEGORY_COLLECTION.with(|collection| *collection.borrow_mut() = categories);
+        BUDGET_COLLECTION.with(|collection| *collection.borrow_mut() = budgets);
+        ACCOUNT_COLLECTION.with(|collection| *collection.borrow_mut() = accounts);
+        TRANSFER_COLLECTION.with(|collection| *collection.borrow_mut() = transfers);
+        HOLIDAY_COLLECTION.with(|collection| *collection.borrow_mut() = holidays);
+    })
+}
@@ -0,0 +1,692 @@
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::rc::Rc;
+
+use crate::finance::database::{get_datetime, get_decimal, to_timestamp};
+use crate::finance::detail::income::*;
+use crate::finance::detail::outcome::*;
+use crate::finance::detail::adjustment::*;
+use crate::finance::detail::saving::*;
+use crate::finance::detail::category::*;
+use crate::finance::detail::budget::*;
+use crate::finance::detail::account::*;
+use crate::finance::detail::transfer::*;
+use crate::finance::detail::calendar::*;
+
+// kind は "income" か "outcome"
+fn get_tags(conn: &Connection, kind: &str, id: u64) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT tag FROM {kind}_tags WHERE {kind}_id = ?1 ORDER BY rowid",
+    ))?;
+    let tags = stmt
+        .query_map(params![id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+fn set_tags(conn: &Connection, kind: &str, id: u64, tags: &[String]) -> rusqlite::Result<()> {
+    conn.execute(&format!("DELETE FROM {kind}_tags WHERE {kind}_id = ?1"), params![id])?;
+    for tag in tags {
+        conn.execute(
+            &format!("INSERT OR IGNORE INTO {kind}_tags ({kind}_id, tag) VALUES (?1, ?2)"),
+            params![id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+pub struct SqliteIncomeRepo {
+    conn: Rc<Connection>,
+}
+
+fn income_from_row(row: &Row) -> rusqlite::Result<Income> {
+    Ok(Income {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        amount: get_decimal(row, 2)?,
+        date: get_datetime(row, 3)?,
+        account_id: row.get(5)?,
+        category_id: row.get(4)?,
+        tags: vec![],
+    })
+}
+
+impl IncomeRepo for SqliteIncomeRepo {
+    fn list(&self, start_date: &DateTime<Local>, end_date: &DateTime<Local>) -> Result<Vec<Income>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, amount, date, category_id, account_id FROM incomes WHERE date >= ?1 AND date <= ?2 ORDER BY date, id",
+        )?;
+        let mut incomes = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], income_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        for income in incomes.iter_mut() {
+            income.tags = get_tags(&self.conn, "income", income.id.unwrap())?;
+        }
+        Ok(incomes)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Income>, anyhow::Error> {
+        let income = self.conn
+            .query_row("SELECT id, name, amount, date, category_id, account_id FROM incomes WHERE id = ?1", params![id], income_from_row)
+            .optional()?;
+        match income {
+            Some(mut income) => {
+                income.tags = get_tags(&self.conn, "income", id)?;
+                Ok(Some(income))
+            }
+            None => Ok(None),
+        }
+    }
+    fn store(&self, income: Income) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO incomes (name, amount, date, category_id, account_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![income.name, income.amount.to_string(), to_timestamp(&income.date), income.category_id, income.account_id],
+        )?;
+        let id = self.conn.last_insert_rowid() as u64;
+        set_tags(&self.conn, "income", id, &income.tags)?;
+        Ok(id)
+    }
+    fn update(&self, income: Income) -> Result<(), anyhow::Error> {
+        let id = income.id.ok_or_else(|| anyhow::anyhow!("Income has no id"))?;
+        self.conn.execute(
+            "UPDATE incomes SET name = ?1, amount = ?2, date = ?3, category_id = ?4, account_id = ?5 WHERE id = ?6",
+            params![income.name, income.amount.to_string(), to_timestamp(&income.date), income.category_id, income.account_id, id],
+        )?;
+        set_tags(&self.conn, "income", id, &income.tags)?;
+        Ok(())
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM income_tags WHERE income_id = ?1", params![id])?;
+        self.conn.execute("DELETE FROM incomes WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+impl SqliteIncomeRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteOutcomeRepo {
+    conn: Rc<Connection>,
+}
+
+fn outcome_from_row(row: &Row) -> rusqlite::Result<Outcome> {
+    Ok(Outcome {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        amount: get_decimal(row, 2)?,
+        date: get_datetime(row, 3)?,
+        account_id: row.get(5)?,
+        category_id: row.get(4)?,
+        tags: vec![],
+    })
+}
+
+impl OutcomeRepo for SqliteOutcomeRepo {
+    fn list(&self, start_date: &DateTime<Local>, end_date: &DateTime<Local>) -> Result<Vec<Outcome>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, amount, date, category_id, account_id FROM outcomes WHERE date >= ?1 AND date <= ?2 ORDER BY date, id",
+        )?;
+        let mut outcomes = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], outcome_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        for outcome in outcomes.iter_mut() {
+            outcome.tags = get_tags(&self.conn, "outcome", outcome.id.unwrap())?;
+        }
+        Ok(outcomes)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Outcome>, anyhow::Error> {
+        let outcome = self.conn
+            .query_row("SELECT id, name, amount, date, category_id, account_id FROM outcomes WHERE id = ?1", params![id], outcome_from_row)
+            .optional()?;
+        match outcome {
+            Some(mut outcome) => {
+                outcome.tags = get_tags(&self.conn, "outcome", id)?;
+                Ok(Some(outcome))
+            }
+            None => Ok(None),
+        }
+    }
+    fn store(&self, outcome: Outcome) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO outcomes (name, amount, date, category_id, account_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![outcome.name, outcome.amount.to_string(), to_timestamp(&outcome.date), outcome.category_id, outcome.account_id],
+        )?;
+        let id = self.conn.last_insert_rowid() as u64;
+        set_tags(&self.conn, "outcome", id, &outcome.tags)?;
+        Ok(id)
+    }
+    fn update(&self, outcome: Outcome) -> Result<(), anyhow::Error> {
+        let id = outcome.id.ok_or_else(|| anyhow::anyhow!("Outcome has no id"))?;
+        self.conn.execute(
+            "UPDATE outcomes SET name = ?1, amount = ?2, date = ?3, category_id = ?4, account_id = ?5 WHERE id = ?6",
+            params![outcome.name, outcome.amount.to_string(), to_timestamp(&outcome.date), outcome.category_id, outcome.account_id, id],
+        )?;
+        set_tags(&self.conn, "outcome", id, &outcome.tags)?;
+        Ok(())
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM outcome_tags WHERE outcome_id = ?1", params![id])?;
+        self.conn.execute("DELETE FROM outcomes WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+impl SqliteOutcomeRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteAdjustmentRepo {
+    conn: Rc<Connection>,
+}
+
+fn adjustment_from_row(row: &Row) -> rusqlite::Result<Adjustment> {
+    let kind: String = row.get(0)?;
+    let entry_id: u64 = row.get(1)?;
+    let kind = match kind.as_str() {
+        "income" => AdjustmentKind::Income(entry_id),
+        "outcome" => AdjustmentKind::Outcome(entry_id),
+        _ => return Err(rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Text,
+            format!("Invalid adjustment kind: {}", kind).into(),
+        )),
+    };
+    Ok(Adjustment {
+        kind,
+        amount: get_decimal(row, 2)?,
+        date: get_datetime(row, 3)?,
+    })
+}
+
+impl AdjustmentRepo for SqliteAdjustmentRepo {
+    fn get(&self, key: &AdjustmentKey) -> Result<Option<Adjustment>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT kind, entry_id, amount, date FROM adjustments WHERE account_id = ?1 AND year = ?2 AND month = ?3",
+                params![key.0, key.1, key.2],
+                adjustment_from_row,
+            )
+            .optional()?)
+    }
+    fn store(&self, key: &AdjustmentKey, adjustment: Adjustment) -> Result<(), anyhow::Error> {
+        let (kind, entry_id) = match adjustment.kind {
+            AdjustmentKind::Income(id) => ("income", id),
+            AdjustmentKind::Outcome(id) => ("outcome", id),
+        };
+        self.conn.execute(
+            "INSERT OR REPLACE INTO adjustments (account_id, year, month, kind, entry_id, amount, date) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![key.0, key.1, key.2, kind, entry_id, adjustment.amount.to_string(), to_timestamp(&adjustment.date)],
+        )?;
+        Ok(())
+    }
+    fn delete(&self, key: &AdjustmentKey) -> Result<(), anyhow::Error> {
+        self.conn.execute(
+            "DELETE FROM adjustments WHERE account_id = ?1 AND year = ?2 AND month = ?3",
+            params![key.0, key.1, key.2],
+        )?;
+        Ok(())
+    }
+}
+
+impl SqliteAdjustmentRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteSavingRepo {
+    conn: Rc<Connection>,
+}
+
+impl SavingRepo for SqliteSavingRepo {
+    fn get(&self, account_id: u64, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT amount FROM savings WHERE account_id = ?1 AND year = ?2 AND month = ?3",
+                params![account_id, key.0, key.1],
+                |row| Ok(Saving { account_id, key: *key, amount: get_decimal(row, 0)? }),
+            )
+            .optional()?)
+    }
+    fn get_latest(&self, account_id: u64, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT year, month, amount FROM savings
+                 WHERE account_id = ?1 AND (year < ?2 OR (year = ?2 AND month <= ?3))
+                 ORDER BY year DESC, month DESC LIMIT 1",
+                params![account_id, key.0, key.1],
+                |row| Ok(Saving { account_id, key: (row.get(0)?, row.get(1)?), amount: get_decimal(row, 2)? }),
+            )
+            .optional()?)
+    }
+    fn store(&self, saving: Saving) -> Result<(), anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO savings (account_id, year, month, amount) VALUES (?1, ?2, ?3, ?4)",
+            params![saving.account_id, saving.key.0, saving.key.1, saving.amount.to_string()],
+        )?;
+        Ok(())
+    }
+    fn update(&self, saving: Saving) -> Result<(), anyhow::Error> {
+        self.conn.execute(
+            "UPDATE savings SET amount = ?1 WHERE account_id = ?2 AND year = ?3 AND month = ?4",
+            params![saving.amount.to_string(), saving.account_id, saving.key.0, saving.key.1],
+        )?;
+        Ok(())
+    }
+}
+
+impl SqliteSavingRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteCategoryRepo {
+    conn: Rc<Connection>,
+}
+
+fn category_from_row(row: &Row) -> rusqlite::Result<Category> {
+    Ok(Category {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        parent_id: row.get(2)?,
+    })
+}
+
+impl CategoryRepo for SqliteCategoryRepo {
+    fn list(&self) -> Result<Vec<Category>, anyhow::Error> {
+        let mut stmt = self.conn.prepare("SELECT id, name, parent_id FROM categories ORDER BY id")?;
+        let categories = stmt
+            .query_map([], category_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(categories)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Category>, anyhow::Error> {
+        Ok(self.conn
+            .query_row("SELECT id, name, parent_id FROM categories WHERE id = ?1", params![id], category_from_row)
+            .optional()?)
+    }
+    fn store(&self, category: Category) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO categories (name, parent_id) VALUES (?1, ?2)",
+            params![category.name, category.parent_id],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+}
+
+impl SqliteCategoryRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteBudgetRepo {
+    conn: Rc<Connection>,
+}
+
+// "overall", "name:<支出名>", "category:<カテゴリID>"
+fn encode_budget_target(target: &BudgetTarget) -> String {
+    match target {
+        BudgetTarget::Overall => "overall".to_string(),
+        BudgetTarget::OutcomeName(name) => format!("name:{}", name),
+        BudgetTarget::Category(id) => format!("category:{}", id),
+    }
+}
+
+fn decode_budget_target(value: &str) -> Option<BudgetTarget> {
+    match value.split_once(':') {
+        None if value == "overall" => Some(BudgetTarget::Overall),
+        Some(("name", name)) => Some(BudgetTarget::OutcomeName(name.to_string())),
+        Some(("category", id)) => id.parse().ok().map(BudgetTarget::Category),
+        _ => None,
+    }
+}
+
+fn budget_from_row(row: &Row) -> rusqlite::Result<Budget> {
+    let target: String = row.get(1)?;
+    let target = decode_budget_target(&target).ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        1,
+        rusqlite::types::Type::Text,
+        format!("Invalid budget target: {}", target).into(),
+    ))?;
+    Ok(Budget {
+        id: Some(row.get(0)?),
+        target,
+        amount: get_decimal(row, 2)?,
+        rollover: row.get(3)?,
+        start_year_and_month: (row.get(4)?, row.get(5)?),
+    })
+}
+
+impl BudgetRepo for SqliteBudgetRepo {
+    fn list(&self) -> Result<Vec<Budget>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, target, amount, rollover, start_year, start_month FROM budgets ORDER BY id",
+        )?;
+        let budgets = stmt
+            .query_map([], budget_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(budgets)
+    }
+    fn store(&self, budget: Budget) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO budgets (target, amount, rollover, start_year, start_month) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                encode_budget_target(&budget.target),
+                budget.amount.to_string(),
+                budget.rollover,
+                budget.start_year_and_month.0,
+                budget.start_year_and_month.1,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM budgets WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+impl SqliteBudgetRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteAccountRepo {
+    conn: Rc<Connection>,
+}
+
+fn encode_account_kind(kind: AccountKind) -> &'static str {
+    match kind {
+        AccountKind::Bank => "bank",
+        AccountKind::Cash => "cash",
+        AccountKind::CreditCard => "credit_card",
+        AccountKind::EMoney => "e_money",
+    }
+}
+
+fn decode_account_kind(value: &str) -> Option<AccountKind> {
+    match value {
+        "bank" => Some(AccountKind::Bank),
+        "cash" => Some(AccountKind::Cash),
+        "credit_card" => Some(AccountKind::CreditCard),
+        "e_money" => Some(AccountKind::EMoney),
+        _ => None,
+    }
+}
+
+fn account_from_row(row: &Row) -> rusqlite::Result<Account> {
+    let kind: String = row.get(2)?;
+    let kind = decode_account_kind(&kind).ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        2,
+        rusqlite::types::Type::Text,
+        format!("Invalid account kind: {}", kind).into(),
+    ))?;
+    let closing_day: Option<u32> = row.get(3)?;
+    let payment_day: Option<u32> = row.get(4)?;
+    let payment_account_id: Option<u64> = row.get(5)?;
+    let card = match (closing_day, payment_day, payment_account_id) {
+        (Some(closing_day), Some(payment_day), Some(payment_account_id)) => {
+            Some(CardCycle { closing_day, payment_day, payment_account_id })
+        }
+        _ => None,
+    };
+    Ok(Account {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        kind,
+        card,
+    })
+}
+
+impl AccountRepo for SqliteAccountRepo {
+    fn list(&self) -> Result<Vec<Account>, anyhow::Error> {
+        let mut stmt = self.conn.prepare("SELECT id, name, kind, closing_day, payment_day, payment_account_id FROM accounts ORDER BY id")?;
+        let accounts = stmt
+            .query_map([], account_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(accounts)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Account>, anyhow::Error> {
+        Ok(self.conn
+            .query_row("SELECT id, name, kind, closing_day, payment_day, payment_account_id FROM accounts WHERE id = ?1", params![id], account_from_row)
+            .optional()?)
+    }
+    fn store(&self, account: Account) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO accounts (name, kind, closing_day, payment_day, payment_account_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                account.name,
+                encode_account_kind(account.kind),
+                account.card.map(|card| card.closing_day),
+                account.card.map(|card| card.payment_day),
+                account.card.map(|card| card.payment_account_id),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+}
+
+impl SqliteAccountRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteTransferRepo {
+    conn: Rc<Connection>,
+}
+
+fn transfer_from_row(row: &Row) -> rusqlite::Result<Transfer> {
+    Ok(Transfer {
+        id: Some(row.get(0)?),
+        from_account_id: row.get(1)?,
+        to_account_id: row.get(2)?,
+        amount: get_decimal(row, 3)?,
+        date: get_datetime(row, 4)?,
+        memo: row.get(5)?,
+    })
+}
+
+impl TransferRepo for SqliteTransferRepo {
+    fn list(&self, start_date: &DateTime<Local>, end_date: &DateTime<Local>) -> Result<Vec<Transfer>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, from_account_id, to_account_id, amount, date, memo FROM transfers WHERE date >= ?1 AND date <= ?2 ORDER BY date, id",
+        )?;
+        let transfers = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], transfer_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(transfers)
+    }
+    fn get_by_id(&self, id: u64) -> Result<Option<Transfer>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT id, from_account_id, to_account_id, amount, date, memo FROM transfers WHERE id = ?1",
+                params![id],
+                transfer_from_row,
+            )
+            .optional()?)
+    }
+    fn store(&self, transfer: Transfer) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO transfers (from_account_id, to_account_id, amount, date, memo) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                transfer.from_account_id,
+                transfer.to_account_id,
+                transfer.amount.to_string(),
+                to_timestamp(&transfer.date),
+                transfer.memo,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM transfers WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+impl SqliteTransferRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteHolidayRepo {
+    conn: Rc<Connection>,
+}
+
+fn holiday_from_row(row: &Row) -> rusqlite::Result<Holiday> {
+    Ok(Holiday {
+        id: Some(row.get(0)?),
+        date: get_datetime(row, 1)?.date_naive(),
+        name: row.get(2)?,
+    })
+}
+
+impl HolidayRepo for SqliteHolidayRepo {
+    fn list(&self) -> Result<Vec<Holiday>, anyhow::Error> {
+        let mut stmt = self.conn.prepare("SELECT id, date, name FROM holidays ORDER BY date, id")?;
+        let holidays = stmt
+            .query_map([], holiday_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(holidays)
+    }
+    fn store(&self, holiday: Holiday) -> Result<u64, anyhow::Error> {
+        let date = Local
+            .from_local_datetime(&holiday.date.and_time(NaiveTime::MIN))
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}", holiday.date))?;
+        self.conn.execute(
+            "INSERT INTO holidays (date, name) VALUES (?1, ?2)",
+            params![to_timestamp(&date), holiday.name],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM holidays WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+impl SqliteHolidayRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::database::open_in_memory;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn round_trips_incomes() {
+        let conn = Rc::new(open_in_memory().unwrap());
+        let repo = SqliteIncomeRepo::new(conn.clone());
+        let mut income = Income::new("給料".to_string(), Decimal::from_str("250000.50").unwrap(), date(2025, 4, 25), 1);
+        income.tags = vec!["本業".to_string(), "手取り".to_string()];
+        let id = repo.store(income).unwrap();
+
+        // 金額は文字列、日時は秒のまま保存される
+        let (amount, timestamp): (String, i64) = conn
+            .query_row("SELECT amount, date FROM incomes WHERE id = ?1", params![id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(amount, "250000.50");
+        assert_eq!(timestamp, date(2025, 4, 25).timestamp());
+
+        let stored = repo.get_by_id(id).unwrap().unwrap();
+        assert_eq!(stored.amount, Decimal::from_str("250000.5").unwrap());
+        assert_eq!(stored.date, date(2025, 4, 25));
+        assert_eq!(stored.tags, vec!["本業".to_string(), "手取り".to_string()]);
+
+        let mut updated = stored.clone();
+        updated.amount = Decimal::from_str("0.01").unwrap();
+        updated.date = date(2025, 5, 1);
+        updated.tags = vec![];
+        repo.update(updated).unwrap();
+        assert!(repo.list(&date(2025, 4, 1), &date(2025, 4, 30)).unwrap().is_empty());
+        let listed = repo.list(&date(2025, 5, 1), &date(2025, 5, 31)).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].amount, Decimal::from_str("0.01").unwrap());
+        assert!(listed[0].tags.is_empty());
+
+        repo.delete_by_id(id).unwrap();
+        assert!(repo.get_by_id(id).unwrap().is_none());
+    }
+
+    #[test]
+    fn round_trips_outcomes() {
+        let conn = Rc::new(open_in_memory().unwrap());
+        let repo = SqliteOutcomeRepo::new(conn);
+        let first = repo
+            .store(Outcome::new("家賃".to_string(), Decimal::from(80000), date(2025, 4, 27), 1))
+            .unwrap();
+        let second = repo
+            .store(Outcome::new("コーヒー".to_string(), Decimal::from_str("4.75").unwrap(), date(2025, 4, 3), 1))
+            .unwrap();
+
+        let listed = repo.list(&date(2025, 4, 1), &date(2025, 4, 30)).unwrap();
+        assert_eq!(listed.iter().map(|outcome| outcome.id.unwrap()).collect::<Vec<_>>(), vec![second, first]);
+        assert_eq!(listed[0].amount, Decimal::from_str("4.75").unwrap());
+
+        let mut updated = listed[1].clone();
+        updated.name = "家賃 (更新)".to_string();
+        updated.amount = Decimal::from(82000);
+        repo.update(updated).unwrap();
+        let stored = repo.get_by_id(first).unwrap().unwrap();
+        assert_eq!((stored.name.as_str(), stored.amount), ("家賃 (更新)", Decimal::from(82000)));
+
+        repo.delete_by_id(second).unwrap();
+        assert_eq!(repo.list(&date(2025, 4, 1), &date(2025, 4, 30)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn round_trips_savings_and_transfers() {
+        let conn = Rc::new(open_in_memory().unwrap());
+        let accounts = SqliteAccountRepo::new(conn.clone());
+        let cash = accounts.store(Account::try_new("財布".to_string(), AccountKind::Cash, None).unwrap()).unwrap();
+
+        let savings = SqliteSavingRepo::new(conn.clone());
+        let key = (2025, 4);
+        savings.store(Saving { account_id: cash, key, amount: Decimal::from_str("1000.10").unwrap() }).unwrap();
+        savings.update(Saving { account_id: cash, key, amount: Decimal::from_str("-5.5").unwrap() }).unwrap();
+        assert_eq!(savings.get(cash, &key).unwrap().unwrap().amount, Decimal::from_str("-5.5").unwrap());
+        assert!(savings.get(1, &key).unwrap().is_none());
+        // 後の月の分がなければ最も新しい月の残高を使う
+        let latest = savings.get_latest(cash, &(2026, 1)).unwrap().unwrap();
+        assert_eq!((latest.key, latest.amount), (key, Decimal::from_str("-5.5").unwrap()));
+        assert!(savings.get_latest(cash, &(2025, 3)).unwrap().is_none());
+
+        let transfers = SqliteTransferRepo::new(conn);
+        let id = transfers
+            .store(Transfer {
+                id: None,
+                from_account_id: 1,
+                to_account_id: cash,
+                amount: Decimal::from_str("3000.25").unwrap(),
+                date: date(2025, 4, 10),
+                memo: "引き出し".to_string(),
+            })
+            .unwrap();
+        let stored = transfers.get_by_id(id).unwrap().unwrap();
+        assert_eq!((stored.from_account_id, stored.to_account_id), (1, cash));
+        assert_eq!(stored.amount, Decimal::from_str("3000.25").unwrap());
+        assert_eq!(stored.date, date(2025, 4, 10));
+        assert_eq!(transfers.list(&date(2025, 4, 1), &date(2025, 4, 30)).unwrap().len(), 1);
+        transfers.delete_by_id(id).unwrap();
+        assert!(transfers.get_by_id(id).unwrap().is_none());
+    }
+}
@@ -1,22 +1,8 @@
 mod db_dummy;
+#[cfg(feature = "sqlite")]
+mod db_sqlite;
 
-use crate::finance::detail::income::IncomeRepo;
-use crate::finance::detail::outcome::OutcomeRepo;
-use crate::finance::detail::adjustment::AdjustmentRepo;
-use crate::finance::detail::saving::SavingRepo;
-
-pub fn get_income_repo() -> impl IncomeRepo {
-    db_dummy::DummyIncomeRepo::new()
-}
-
-pub fn get_outcome_repo() -> impl OutcomeRepo {
-    db_dummy::DummyOutcomeRepo::new()
-}
-
-pub fn get_adjustment_repo() -> impl AdjustmentRepo {
-    db_dummy::DummyAdjustmentRepo::new()
-}
-
-pub fn get_saving_repo() -> impl SavingRepo {
-    db_dummy::DummySavingRepo::new()
-}
\ No newline at end of file
+pub use db_dummy::{DummyAccountRepo, DummyAdjustmentRepo, DummyBudgetRepo, DummyCategoryRepo, DummyHolidayRepo, DummyIncomeRepo, DummyOutcomeRepo, DummySavingRepo, DummyTransferRepo};
+pub use db_dummy::snapshot_collections as snapshot_dummy_collections;
+#[cfg(feature = "sqlite")]
+pub use db_sqlite::{SqliteAccountRepo, SqliteAdjustmentRepo, SqliteBudgetRepo, SqliteCategoryRepo, SqliteHolidayRepo, SqliteIncomeRepo, SqliteOutcomeRepo, SqliteSavingRepo, SqliteTransferRepo};
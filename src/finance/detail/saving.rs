@@ -1,50 +1,69 @@
 use rust_decimal::Decimal;
-use chrono::{Local, Datelike};
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
 
-use crate::util::get_next_ym;
+use super::account::{AccountKind, AccountRepo};
+use super::income::IncomeRepo;
+use super::outcome::OutcomeRepo;
+use super::transfer::TransferRepo;
+use crate::finance::setting::AccountingPeriod;
+use crate::util::{get_next_ym, get_prev_ym};
 
 pub type SavingKey = (i32, u32);
 
+// 口座ごとの月の終わり時点の残高
+// key はその月分 (月の区切りは AccountingPeriod による)
 #[derive(Debug, Clone)]
 pub struct Saving {
+    pub account_id: u64,
     pub key: SavingKey,
     pub amount: Decimal,
 }
 
 impl Saving {
-    pub fn new(key: SavingKey, amount: Decimal) -> Self {
-        Self { key, amount }
+    pub fn new(account_id: u64, key: SavingKey, amount: Decimal) -> Self {
+        Self { account_id, key, amount }
     }
 }
 
 pub trait SavingRepo {
-    fn get(&self, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error>;
-    fn store(&self, key: &SavingKey, saving: Saving) -> Result<(), anyhow::Error>;
-    fn update(&self, key: &SavingKey, saving: Saving) -> Result<(), anyhow::Error>;
+    fn get(&self, account_id: u64, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error>;
+    // key 以前で最も新しい月の残高
+    fn get_latest(&self, account_id: u64, key: &SavingKey) -> Result<Option<Saving>, anyhow::Error>;
+    // 口座と月は saving のものを使う
+    fn store(&self, saving: Saving) -> Result<(), anyhow::Error>;
+    fn update(&self, saving: Saving) -> Result<(), anyhow::Error>;
 }
 
 pub fn update_saving(
+    account_id: u64,
     key: SavingKey,
     amount: Decimal,
-    saving_repo: &impl SavingRepo,
+    period: &AccountingPeriod,
+    saving_repo: &dyn SavingRepo,
 ) -> Result<(), anyhow::Error> {
     let mut current_ym = key;
-    let today = Local::now();
-    while current_ym.0 < today.year() || (current_ym.0 == today.year() && current_ym.1 <= today.month()) {
-        match saving_repo.get(&current_ym)? {
+    let today_ym = period.get_year_month(&Local::now());
+    while current_ym <= today_ym {
+        match saving_repo.get(account_id, &current_ym)? {
             Some(saving) => {
-                let updated_saving: Saving = Saving::new(current_ym, saving.amount + amount);
-                saving_repo.update(&current_ym, updated_saving)?
+                let updated_saving: Saving = Saving::new(account_id, current_ym, saving.amount + amount);
+                saving_repo.update(updated_saving)?
             },
             None => {
-                match saving_repo.get(&(current_ym.0, if current_ym.1 == 1 { 12 } else { current_ym.1 - 1 }))? {
+                match saving_repo.get(account_id, &get_prev_ym(current_ym))? {
+                    // 前月の残高は key 以降なら変更済みなので、そのまま引き継ぐ
+                    Some(last_month_saving) if current_ym != key => {
+                        let updated_saving = Saving::new(account_id, current_ym, last_month_saving.amount);
+                        saving_repo.store(updated_saving)?
+                    },
                     Some(last_month_saving) => {
-                        let updated_saving = Saving::new(current_ym, last_month_saving.amount + amount);
-                        saving_repo.store(&current_ym, updated_saving)?
+                        let updated_saving = Saving::new(account_id, current_ym, last_month_saving.amount + amount);
+                        saving_repo.store(updated_saving)?
                     },
                     None => {
-                        let updated_saving = Saving::new(current_ym, amount);
-                        saving_repo.store(&current_ym, updated_saving)?
+                        let updated_saving = Saving::new(account_id, current_ym, amount);
+                        saving_repo.store(updated_saving)?
                     },
                 }
             },
@@ -52,4 +71,155 @@ pub fn update_saving(
         current_ym = get_next_ym(current_ym);
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+// 記録した収入・支出・振替から、すべての口座の残高を作り直す
+// 月の区切りを変えると、同じ年月でも含まれる記録が変わるため
+pub fn rebuild_savings(
+    period: &AccountingPeriod,
+    account_repo: &dyn AccountRepo,
+    income_repo: &dyn IncomeRepo,
+    outcome_repo: &dyn OutcomeRepo,
+    transfer_repo: &dyn TransferRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<(), anyhow::Error> {
+    let today_ym = period.get_year_month(&Local::now());
+    let start_date = DateTime::UNIX_EPOCH.with_timezone(&Local);
+    let (_, end_date) = period.get_opening_and_closing_date(today_ym.0, today_ym.1)?;
+
+    let mut changes: HashMap<(u64, SavingKey), Decimal> = HashMap::new();
+    for income in income_repo.list(&start_date, &end_date)? {
+        *changes.entry((income.account_id, period.get_year_month(&income.date))).or_default() += income.amount;
+    }
+    for outcome in outcome_repo.list(&start_date, &end_date)? {
+        *changes.entry((outcome.account_id, period.get_year_month(&outcome.date))).or_default() -= outcome.amount;
+    }
+    for transfer in transfer_repo.list(&start_date, &end_date)? {
+        let key = period.get_year_month(&transfer.date);
+        *changes.entry((transfer.from_account_id, key)).or_default() -= transfer.amount;
+        *changes.entry((transfer.to_account_id, key)).or_default() += transfer.amount;
+    }
+    let Some(first_ym) = changes.keys().map(|(_, key)| *key).min() else {
+        return Ok(());
+    };
+
+    for account in account_repo.list()? {
+        let account_id = account.id.unwrap();
+        let mut amount = Decimal::ZERO;
+        // 記録より前の月は残高 0 にしておく
+        let mut current_ym = get_prev_ym(first_ym);
+        while current_ym <= today_ym {
+            amount += changes.get(&(account_id, current_ym)).copied().unwrap_or_default();
+            let saving = Saving::new(account_id, current_ym, amount);
+            match saving_repo.get(account_id, &current_ym)? {
+                Some(_) => saving_repo.update(saving)?,
+                None => saving_repo.store(saving)?,
+            }
+            current_ym = get_next_ym(current_ym);
+        }
+    }
+    Ok(())
+}
+
+// すべての口座の残高の合計
+pub fn get_total_saving(
+    key: SavingKey,
+    account_repo: &dyn AccountRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<Decimal, anyhow::Error> {
+    let mut total = Decimal::ZERO;
+    for account in account_repo.list()? {
+        if let Some(saving) = saving_repo.get(account.id.unwrap(), &key)? {
+            total += saving.amount;
+        }
+    }
+    Ok(total)
+}
+
+// クレジットカードを除いた口座の残高の合計
+// カードの利用分は引き落とし日に支払い口座から出ていくものとして別に数える
+// 残高は今月の分までしかないので、その月の分がなければそれ以前で最も新しい残高を使う
+pub fn get_available_saving(
+    key: SavingKey,
+    account_repo: &dyn AccountRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<Decimal, anyhow::Error> {
+    let mut total = Decimal::ZERO;
+    for account in account_repo.list()? {
+        if account.kind == AccountKind::CreditCard {
+            continue;
+        }
+        if let Some(saving) = saving_repo.get_latest(account.id.unwrap(), &key)? {
+            total += saving.amount;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::repository::{
+        DummyAccountRepo, DummyIncomeRepo, DummyOutcomeRepo, DummySavingRepo, DummyTransferRepo,
+    };
+    use crate::finance::detail::{store_transfer, Income, Outcome, Transfer};
+    use chrono::{NaiveDate, TimeZone};
+
+    // 仮のデータの口座: 1 メイン口座, 2 財布, 3 Suica, 4 カード
+    const CASH: u64 = 2;
+    const SUICA: u64 = 3;
+    const CARD: u64 = 4;
+
+    fn transfer(from_account_id: u64, to_account_id: u64, amount: &str, month: u32) -> Transfer {
+        let date = NaiveDate::from_ymd_opt(2024, month, 10).unwrap();
+        Transfer::try_new(from_account_id, to_account_id, amount.to_string(), date, "".to_string()).unwrap()
+    }
+
+    #[test]
+    fn moves_savings_between_accounts_and_excludes_cards() {
+        let (period, accounts, savings) = (AccountingPeriod::default(), DummyAccountRepo::new(), DummySavingRepo::new());
+        update_saving(CASH, (2024, 6), Decimal::from(10000), &period, &savings).unwrap();
+        update_saving(CARD, (2024, 6), Decimal::from(-8000), &period, &savings).unwrap();
+        store_transfer(transfer(CASH, SUICA, "3000", 7), &period, &DummyTransferRepo::new(), &savings).unwrap();
+
+        let amount = |account_id, key| savings.get(account_id, &key).unwrap().map(|saving| saving.amount);
+        assert_eq!(amount(CASH, (2024, 6)), Some(Decimal::from(10000)));
+        assert_eq!(amount(CASH, (2024, 7)), Some(Decimal::from(7000)));
+        assert_eq!(amount(SUICA, (2024, 7)), Some(Decimal::from(3000)));
+        assert_eq!(amount(SUICA, (2024, 6)), None);
+        // 振替では合計は変わらず、カードの利用分は使えるお金から除く
+        for key in [(2024, 6), (2024, 7), (2024, 9)] {
+            assert_eq!(get_available_saving(key, &accounts, &savings).unwrap(), Decimal::from(10000));
+            assert_eq!(get_total_saving(key, &accounts, &savings).unwrap(), Decimal::from(2000));
+        }
+    }
+
+    #[test]
+    fn rebuilds_savings_from_records() {
+        let period = AccountingPeriod::default();
+        let (accounts, incomes, outcomes, transfers, savings) = (
+            DummyAccountRepo::new(),
+            DummyIncomeRepo::new(),
+            DummyOutcomeRepo::new(),
+            DummyTransferRepo::new(),
+            DummySavingRepo::new(),
+        );
+        let date = |month| Local.with_ymd_and_hms(2024, month, 5, 0, 0, 0).unwrap();
+        incomes.store(Income::new("給料".to_string(), Decimal::from(20000), date(6), CASH)).unwrap();
+        outcomes.store(Outcome::new("本".to_string(), Decimal::from(5000), date(7), CARD)).unwrap();
+        transfers.store(transfer(CASH, SUICA, "3000", 7)).unwrap();
+        // 記録と合わない残高は作り直される
+        savings.store(Saving::new(CASH, (2024, 7), Decimal::from(999))).unwrap();
+
+        rebuild_savings(&period, &accounts, &incomes, &outcomes, &transfers, &savings).unwrap();
+        let amount = |account_id, key| savings.get(account_id, &key).unwrap().map(|saving| saving.amount);
+        assert_eq!(amount(CASH, (2024, 5)), Some(Decimal::ZERO));
+        assert_eq!(amount(CASH, (2024, 6)), Some(Decimal::from(20000)));
+        assert_eq!(amount(CASH, (2024, 7)), Some(Decimal::from(17000)));
+        assert_eq!(amount(SUICA, (2024, 7)), Some(Decimal::from(3000)));
+        assert_eq!(amount(CARD, (2024, 7)), Some(Decimal::from(-5000)));
+        // 仮のデータの 2025年1月の残高も、記録がないので 0 になる
+        assert_eq!(amount(1, (2025, 1)), Some(Decimal::ZERO));
+        assert_eq!(get_available_saving((2024, 8), &accounts, &savings).unwrap(), Decimal::from(20000));
+    }
+}
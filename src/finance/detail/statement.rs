@@ -0,0 +1,169 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use rust_decimal::Decimal;
+
+use super::account::{Account, CardCycle};
+use super::calendar::{BusinessCalendar, BusinessDayRoll};
+use super::income::IncomeRepo;
+use super::outcome::OutcomeRepo;
+use crate::util::{get_clamped_day, get_next_ym, get_prev_ym};
+
+// クレジットカードの 1 回分の請求
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub payment_account_id: u64,
+    // 前回の締め日の翌日から
+    pub opening_date: DateTime<Local>,
+    pub closing_date: DateTime<Local>,
+    pub payment_date: DateTime<Local>,
+    // 利用額から返金を引いたもの
+    pub amount: Decimal,
+}
+
+impl CardCycle {
+    // year 年 month 月の締め日の終わり
+    pub fn get_closing_date(&self, year: i32, month: u32) -> Result<DateTime<Local>, anyhow::Error> {
+        let day = get_clamped_day(year, month, self.closing_day)?;
+        Local
+            .with_ymd_and_hms(year, month, day, 23, 59, 59)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))
+    }
+
+    // year 年 month 月に締めた分の引き落とし日
+    // 引き落とし日が休日なら翌営業日になる
+    pub fn get_payment_date(
+        &self,
+        year: i32,
+        month: u32,
+        calendar: &BusinessCalendar,
+    ) -> Result<DateTime<Local>, anyhow::Error> {
+        let (year, month) = get_next_ym((year, month));
+        let day = get_clamped_day(year, month, self.payment_day)?;
+        let date = NaiveDate::from_ymd_opt(year, month, day)
+            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))?;
+        let date = calendar.roll(date, BusinessDayRoll::Next);
+        Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}", date))
+    }
+}
+
+// year 年 month 月に締める請求
+// クレジットカードでない口座には請求がない
+pub fn get_statement(
+    account: &Account,
+    year: i32,
+    month: u32,
+    calendar: &BusinessCalendar,
+    income_repo: &dyn IncomeRepo,
+    outcome_repo: &dyn OutcomeRepo,
+) -> Result<Option<Statement>, anyhow::Error> {
+    let (Some(account_id), Some(card)) = (account.id, account.card) else {
+        return Ok(None);
+    };
+    let (prev_year, prev_month) = get_prev_ym((year, month));
+    let opening_date = card.get_closing_date(prev_year, prev_month)? + chrono::Duration::seconds(1);
+    let closing_date = card.get_closing_date(year, month)?;
+    let used: Decimal = outcome_repo
+        .list(&opening_date, &closing_date)?
+        .iter()
+        .filter(|outcome| outcome.account_id == account_id)
+        .map(|outcome| outcome.amount)
+        .sum();
+    let refunded: Decimal = income_repo
+        .list(&opening_date, &closing_date)?
+        .iter()
+        .filter(|income| income.account_id == account_id)
+        .map(|income| income.amount)
+        .sum();
+    Ok(Some(Statement {
+        payment_account_id: card.payment_account_id,
+        opening_date,
+        closing_date,
+        payment_date: card.get_payment_date(year, month, calendar)?,
+        amount: used - refunded,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::account::AccountKind;
+    use crate::finance::detail::repository::{DummyIncomeRepo, DummyOutcomeRepo};
+    use crate::finance::detail::{Income, IncomeRepo, Outcome, OutcomeRepo};
+
+    fn card(id: u64, closing_day: u32, payment_day: u32) -> Account {
+        Account {
+            id: Some(id),
+            name: "カード".to_string(),
+            kind: AccountKind::CreditCard,
+            card: Some(CardCycle { closing_day, payment_day, payment_account_id: 1 }),
+        }
+    }
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, hour, min, sec).unwrap()
+    }
+
+    #[test]
+    fn resolves_closing_and_payment_dates() {
+        let calendar = BusinessCalendar::japanese();
+        // (締め日, 引き落とし日, 締めた年月, 締め日, 引き落とし日)
+        let cases = [
+            // 31日締めは 2月なら 28日 (うるう年は 29日)
+            (31, 27, (2025, 2), (2025, 2, 28), (2025, 3, 27)),
+            (31, 27, (2024, 2), (2024, 2, 29), (2024, 3, 27)),
+            // 引き落とし日がない月は末日
+            (15, 31, (2025, 1), (2025, 1, 15), (2025, 2, 28)),
+            // 土曜日なら翌営業日、祝日 (2月11日) も飛ばす
+            (15, 10, (2025, 4), (2025, 4, 15), (2025, 5, 12)),
+            (15, 11, (2025, 1), (2025, 1, 15), (2025, 2, 12)),
+            // 年をまたぐ
+            (15, 10, (2024, 12), (2024, 12, 15), (2025, 1, 10)),
+        ];
+        for (closing_day, payment_day, (year, month), closing, payment) in cases {
+            let cycle = CardCycle { closing_day, payment_day, payment_account_id: 1 };
+            assert_eq!(cycle.get_closing_date(year, month).unwrap(), datetime(closing.0, closing.1, closing.2, 23, 59, 59));
+            assert_eq!(cycle.get_payment_date(year, month, &calendar).unwrap(), datetime(payment.0, payment.1, payment.2, 0, 0, 0));
+        }
+    }
+
+    #[test]
+    fn nets_refunds_against_charges_per_statement() {
+        let (calendar, incomes, outcomes) = (BusinessCalendar::japanese(), DummyIncomeRepo::new(), DummyOutcomeRepo::new());
+        let (day15, day31) = (card(5, 15, 10), card(6, 31, 27));
+        let charge = |account_id, (month, day, hour), amount| {
+            let date = datetime(2025, month, day, hour, 0, 0);
+            outcomes.store(Outcome::new("買い物".to_string(), Decimal::from(amount), date, account_id)).unwrap();
+        };
+        charge(5, (1, 15, 23), 1000);
+        charge(5, (1, 16, 0), 2000);
+        charge(6, (2, 28, 12), 3000);
+        charge(6, (3, 1, 0), 4000);
+        // 返金は利用額から引く
+        let refund = Income::new("返品".to_string(), Decimal::from(500), datetime(2025, 1, 20, 0, 0, 0), 5);
+        incomes.store(refund).unwrap();
+
+        // (カード, 締めた年月, 請求額, 引き落とし日)
+        let cases = [
+            // 締め日までの利用は翌月、締め日の翌日からの利用は翌々月に引き落とされる
+            (&day15, (2025, 1), 1000, (2025, 2, 10)),
+            (&day15, (2025, 2), 1500, (2025, 3, 10)),
+            (&day31, (2025, 2), 3000, (2025, 3, 27)),
+            (&day31, (2025, 3), 4000, (2025, 4, 28)),
+        ];
+        for (account, (year, month), amount, payment) in cases {
+            let statement = get_statement(account, year, month, &calendar, &incomes, &outcomes).unwrap().unwrap();
+            assert_eq!(statement.amount, Decimal::from(amount), "{:?} {}-{}", account.card, year, month);
+            assert_eq!(statement.payment_date, datetime(payment.0, payment.1, payment.2, 0, 0, 0));
+            assert_eq!(statement.payment_account_id, 1);
+        }
+        // 31日締めの 3月分は 3月1日から
+        let statement = get_statement(&day31, 2025, 3, &calendar, &incomes, &outcomes).unwrap().unwrap();
+        assert_eq!(statement.opening_date, datetime(2025, 3, 1, 0, 0, 0));
+
+        let bank = Account { id: Some(1), name: "メイン口座".to_string(), kind: AccountKind::Bank, card: None };
+        assert!(get_statement(&bank, 2025, 1, &calendar, &incomes, &outcomes).unwrap().is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use rust_decimal::prelude::*;
+use super::saving::{update_saving, SavingRepo};
+use crate::finance::error::FinanceError;
+use crate::finance::setting::AccountingPeriod;
+
+// 口座間の資金移動
+// 収入・支出としては数えず、それぞれの口座の残高だけを動かす
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub id: Option<u64>,
+    pub from_account_id: u64,
+    pub to_account_id: u64,
+    pub amount: Decimal,
+    pub date: DateTime<Local>,
+    pub memo: String,
+}
+
+impl Transfer {
+    pub fn try_new(
+        from_account_id: u64,
+        to_account_id: u64,
+        amount: String,
+        date: NaiveDate,
+        memo: String,
+    ) -> Result<Self, FinanceError> {
+        if from_account_id == to_account_id {
+            return Err(FinanceError::SameAccount);
+        }
+        let amount = Decimal::from_str(&amount)
+            .ok()
+            .filter(|amount| *amount > Decimal::ZERO)
+            .ok_or(FinanceError::InvalidAmount(amount))?;
+
+        let date = Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .single()
+            .ok_or_else(|| FinanceError::InvalidDate(date.to_string()))?;
+
+        Ok(Self { id: None, from_account_id, to_account_id, amount, date, memo: memo.trim().to_string() })
+    }
+
+    // 振替元の残高を減らし、振替先の残高を増やす
+    // sign に -1 を渡すと取り消しになる
+    fn apply(&self, sign: Decimal, period: &AccountingPeriod, saving_repo: &dyn SavingRepo) -> Result<(), anyhow::Error> {
+        let key = period.get_year_month(&self.date);
+        update_saving(self.from_account_id, key, -self.amount * sign, period, saving_repo)?;
+        update_saving(self.to_account_id, key, self.amount * sign, period, saving_repo)?;
+        Ok(())
+    }
+}
+
+pub trait TransferRepo {
+    fn list(&self, start_date: &DateTime<Local>, end_date: &DateTime<Local>) -> Result<Vec<Transfer>, anyhow::Error>;
+    fn get_by_id(&self, id: u64) -> Result<Option<Transfer>, anyhow::Error>;
+    fn store(&self, transfer: Transfer) -> Result<u64, anyhow::Error>;
+    fn delete_by_id(&self, id: u64) -> Result<(), anyhow::Error>;
+}
+
+pub fn store_transfer(
+    transfer: Transfer,
+    period: &AccountingPeriod,
+    transfer_repo: &dyn TransferRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<u64, FinanceError> {
+    let id = transfer_repo.store(transfer.clone())?;
+    transfer.apply(Decimal::ONE, period, saving_repo)?;
+    Ok(id)
+}
+
+pub fn delete_transfer(
+    id: u64,
+    period: &AccountingPeriod,
+    transfer_repo: &dyn TransferRepo,
+    saving_repo: &dyn SavingRepo,
+) -> Result<(), FinanceError> {
+    let Some(prev) = transfer_repo.get_by_id(id)? else {
+        return Ok(());
+    };
+    transfer_repo.delete_by_id(id)?;
+    prev.apply(-Decimal::ONE, period, saving_repo)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::repository::{DummySavingRepo, DummyTransferRepo};
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 7, 10).unwrap()
+    }
+
+    #[test]
+    fn rejects_transfers_within_one_account() {
+        assert!(matches!(
+            Transfer::try_new(2, 2, "1000".to_string(), date(), "".to_string()),
+            Err(FinanceError::SameAccount)
+        ));
+        assert!(matches!(
+            Transfer::try_new(2, 3, "0".to_string(), date(), "".to_string()),
+            Err(FinanceError::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn deleting_a_transfer_restores_both_accounts() {
+        let (period, transfers, savings) = (AccountingPeriod::default(), DummyTransferRepo::new(), DummySavingRepo::new());
+        update_saving(2, (2024, 7), Decimal::from(10000), &period, &savings).unwrap();
+        update_saving(3, (2024, 7), Decimal::from(500), &period, &savings).unwrap();
+        let amounts = || {
+            [(2, (2024, 7)), (3, (2024, 7)), (2, (2024, 9)), (3, (2024, 9))]
+                .map(|(account_id, key)| savings.get(account_id, &key).unwrap().unwrap().amount)
+        };
+        let before = amounts();
+
+        let transfer = Transfer::try_new(2, 3, "3000".to_string(), date(), "チャージ".to_string()).unwrap();
+        let id = store_transfer(transfer, &period, &transfers, &savings).unwrap();
+        assert_eq!(amounts(), [7000, 3500, 7000, 3500].map(Decimal::from));
+
+        delete_transfer(id, &period, &transfers, &savings).unwrap();
+        assert_eq!(amounts(), before);
+        assert!(transfers.get_by_id(id).unwrap().is_none());
+        // 消したものをもう一度消しても残高は変わらない
+        delete_transfer(id, &period, &transfers, &savings).unwrap();
+        assert_eq!(amounts(), before);
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+
+// 画面に表示するエラー
+// Display はそのまま入力欄の下に表示できる文言にする
+#[derive(Debug)]
+pub enum FinanceError {
+    // 数値として読めない金額
+    InvalidAmount(String),
+    // 数値として読めない勤務時間
+    InvalidHour(String),
+    // 日付として読めない、または存在しない日付
+    InvalidDate(String),
+    // 指定できない繰り返しの規則 (間隔が 0 など)
+    InvalidRecurrence(String),
+    // 1 から 31 でない月の開始日
+    InvalidPeriodStartDay(String),
+    // 1 から 120 でない予測する月数
+    InvalidForecastHorizon(String),
+    // 空の名前
+    EmptyName,
+    // 変更する対象 (テンプレートやアルバイト) が選ばれていない
+    NoTarget,
+    // 振替元と振替先が同じ口座
+    SameAccount,
+    // 対象のデータが存在しない
+    NotFound { entity: &'static str, id: u64 },
+    // リポジトリの読み書きに失敗した
+    Storage(anyhow::Error),
+}
+
+impl fmt::Display for FinanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FinanceError::InvalidAmount(input) => write!(f, "金額「{}」は数値で入力してください", input),
+            FinanceError::InvalidHour(input) => write!(f, "時間「{}」は数値で入力してください", input),
+            FinanceError::InvalidDate(input) => write!(f, "日付「{}」は正しい日付ではありません", input),
+            FinanceError::InvalidRecurrence(label) => write!(f, "繰り返し「{}」は指定できません", label),
+            FinanceError::InvalidPeriodStartDay(input) => write!(f, "月の開始日「{}」は 1 から 31 で入力してください", input),
+            FinanceError::InvalidForecastHorizon(input) => write!(f, "予測する期間「{}」は 1 から 120 か月で入力してください", input),
+            FinanceError::EmptyName => write!(f, "名前を入力してください"),
+            FinanceError::NoTarget => write!(f, "変更する対象を選んでください"),
+            FinanceError::SameAccount => write!(f, "振替元と振替先には別の口座を選んでください"),
+            FinanceError::NotFound { entity, id } => write!(f, "{} (ID: {}) が見つかりません", entity, id),
+            FinanceError::Storage(e) => write!(f, "保存に失敗しました: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FinanceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FinanceError::Storage(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for FinanceError {
+    fn from(e: anyhow::Error) -> Self {
+        FinanceError::Storage(e)
+    }
+}
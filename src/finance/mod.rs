@@ -1,4 +1,9 @@
 pub mod detail;
 pub mod plan;
 pub mod api;
-pub mod setting;
\ No newline at end of file
+pub mod setting;
+#[cfg(feature = "sqlite")]
+pub mod database;
+pub mod context;
+pub mod error;
+pub mod transaction;
\ No newline at end of file
@@ -4,9 +4,20 @@ use std::collections::BTreeMap;
 
 use super::income::Income;
 use super::outcome::Outcome;
-use crate::finance::detail::saving::SavingRepo;
+use crate::finance::detail::account::AccountRepo;
+use crate::finance::detail::saving::{get_available_saving, SavingRepo};
+use crate::finance::setting::AccountingPeriod;
 use crate::util::get_next_ym;
 
+pub type IncomeFactory = Box<dyn Fn(i32, u32) -> Result<Vec<Income>, anyhow::Error>>;
+pub type OutcomeFactory = Box<dyn Fn(i32, u32) -> Result<Vec<Outcome>, anyhow::Error>>;
+// 年月とその月末の残高
+pub type MonthEndBalance = ((i32, u32), Decimal);
+// 年月とその月の集計
+pub type MonthlyRollup = ((i32, u32), Rollup);
+// 年とその年の集計
+pub type YearlyRollup = (i32, Rollup);
+
 #[derive(Debug)]
 pub enum BalanceStatus {
     Deficit(Decimal),
@@ -21,71 +32,499 @@ pub struct InspectResult {
     pub outcomes: Vec<Outcome>,
 }
 
+impl InspectResult {
+    // マイナスなら不足
+    pub fn balance(&self) -> Decimal {
+        match self.balance_status {
+            BalanceStatus::Surplus(amount) => amount,
+            BalanceStatus::Deficit(amount) => -amount,
+        }
+    }
+
+    // その日の収入の合計
+    pub fn total_income(&self) -> Decimal {
+        self.incomes.iter().map(|income| income.amount).sum()
+    }
+
+    // その日の支出の合計
+    pub fn total_outcome(&self) -> Decimal {
+        self.outcomes.iter().map(|outcome| outcome.amount).sum()
+    }
+}
+
+// 予測期間の残高の要約
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BalanceSummary {
+    // 初めて残高がマイナスになる日とその残高
+    pub first_deficit: Option<(DateTime<Local>, Decimal)>,
+    // 初めて残高が下限を下回る日とその残高
+    pub first_below_threshold: Option<(DateTime<Local>, Decimal)>,
+    // 最も残高が少ない日とその残高 (同じ額なら早い日)
+    pub minimum: Option<(DateTime<Local>, Decimal)>,
+}
+
+impl BalanceSummary {
+    pub fn summarize(results: &[InspectResult], threshold: Decimal) -> Self {
+        let mut summary = Self::default();
+        for result in results {
+            let balance = result.balance();
+            if balance < Decimal::ZERO && summary.first_deficit.is_none() {
+                summary.first_deficit = Some((result.date, balance));
+            }
+            if balance < threshold && summary.first_below_threshold.is_none() {
+                summary.first_below_threshold = Some((result.date, balance));
+            }
+            if summary.minimum.is_none_or(|(_, minimum)| balance < minimum) {
+                summary.minimum = Some((result.date, balance));
+            }
+        }
+        summary
+    }
+}
+
+// 月や年の単位でまとめた収入・支出と残高
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rollup {
+    pub opening: Decimal,
+    pub total_income: Decimal,
+    pub total_outcome: Decimal,
+    pub closing: Decimal,
+    // 期間中に最も少なくなった残高 (期首の残高を含む)
+    pub minimum: Decimal,
+}
+
+impl Rollup {
+    fn new(opening: Decimal) -> Self {
+        Self {
+            opening,
+            total_income: Decimal::ZERO,
+            total_outcome: Decimal::ZERO,
+            closing: opening,
+            minimum: opening,
+        }
+    }
+
+    fn add(&mut self, result: &InspectResult) {
+        self.total_income += result.total_income();
+        self.total_outcome += result.total_outcome();
+        self.closing = result.balance();
+        self.minimum = self.minimum.min(self.closing);
+    }
+
+    // 続く期間の集計をまとめる
+    fn merge(&mut self, next: &Rollup) {
+        self.total_income += next.total_income;
+        self.total_outcome += next.total_outcome;
+        self.closing = next.closing;
+        self.minimum = self.minimum.min(next.minimum);
+    }
+
+    pub fn net(&self) -> Decimal {
+        self.total_income - self.total_outcome
+    }
+}
+
+// 月ごとの集計を年ごとにまとめる
+pub fn yearly_rollups(monthly: &[MonthlyRollup]) -> Vec<YearlyRollup> {
+    let mut yearly: Vec<YearlyRollup> = Vec::new();
+    for ((year, _), rollup) in monthly {
+        match yearly.last_mut() {
+            Some((last_year, last)) if last_year == year => last.merge(rollup),
+            _ => yearly.push((*year, rollup.clone())),
+        }
+    }
+    yearly
+}
+
+// 予測期間全体の日ごとの残高
+// 表やグラフ、集計はどれもこれから作る
+pub struct Forecast {
+    pub start_ym: (i32, u32),
+    pub end_ym: (i32, u32),
+    // 開始月の初めの残高
+    pub opening: Decimal,
+    pub results: Vec<InspectResult>,
+}
+
+impl Forecast {
+    // 月ごとの月末の残高
+    // 収入も支出もない月は前の月の残高のまま
+    pub fn month_end_balances(&self, period: &AccountingPeriod) -> Vec<MonthEndBalance> {
+        self.monthly_rollups(period)
+            .into_iter()
+            .map(|(ym, rollup)| (ym, rollup.closing))
+            .collect()
+    }
+
+    // 月ごとの集計
+    // 前の月に前倒しされた分は最初の月に、期間の後にずれた分は最後の月に含める
+    pub fn monthly_rollups(&self, period: &AccountingPeriod) -> Vec<MonthlyRollup> {
+        let (start_ym, end_ym) = (self.start_ym, self.end_ym);
+        if start_ym > end_ym {
+            return vec![];
+        }
+        let mut rollups = Vec::new();
+        let mut ym = start_ym;
+        let mut rollup = Rollup::new(self.opening);
+        for result in &self.results {
+            let result_ym = period.get_year_month(&result.date).clamp(start_ym, end_ym);
+            while ym < result_ym {
+                let opening = rollup.closing;
+                rollups.push((ym, rollup));
+                rollup = Rollup::new(opening);
+                ym = get_next_ym(ym);
+            }
+            rollup.add(result);
+        }
+        loop {
+            let opening = rollup.closing;
+            rollups.push((ym, rollup));
+            if ym >= end_ym {
+                break;
+            }
+            rollup = Rollup::new(opening);
+            ym = get_next_ym(ym);
+        }
+        rollups
+    }
+}
+
+// 1 日分の収入と支出
+#[derive(Default)]
+struct DailyEntries {
+    incomes: Vec<Income>,
+    outcomes: Vec<Outcome>,
+}
+
+// その月の収入と支出の合計
+fn get_month_net(
+    (year, month): (i32, u32),
+    income_factories: &[IncomeFactory],
+    outcome_factories: &[OutcomeFactory],
+) -> Result<Decimal, anyhow::Error> {
+    let mut net = Decimal::ZERO;
+    for factory in income_factories {
+        net += factory(year, month)?.iter().map(|income| income.amount).sum::<Decimal>();
+    }
+    for factory in outcome_factories {
+        net -= factory(year, month)?.iter().map(|outcome| outcome.amount).sum::<Decimal>();
+    }
+    Ok(net)
+}
+
+// 予測期間の日ごとの残高を作る
+// 警告やグラフ、集計は期間全体を見るので、途中までで止めずに最後まで作る
+// 残高は今月の分までしかないので、開始月が先なら今月から予測して開始月の前までは残高にだけ反映する
 pub fn inspect(
     start_ym: (i32, u32),
     end_ym: (i32, u32),
-    saving_repo: &impl SavingRepo,
-    income_factories: Vec<Box<dyn Fn(i32, u32) -> Result<Vec<Income>, anyhow::Error>>>,
-    outcome_factories: Vec<Box<dyn Fn(i32, u32) -> Result<Vec<Outcome>, anyhow::Error>>>,
-) -> Result<Vec<InspectResult>, anyhow::Error>
+    current_ym: (i32, u32),
+    account_repo: &dyn AccountRepo,
+    saving_repo: &dyn SavingRepo,
+    income_factories: Vec<IncomeFactory>,
+    outcome_factories: Vec<OutcomeFactory>,
+) -> Result<Forecast, anyhow::Error>
 {
-    let mut incomes = Vec::new();
-    let mut outcomes = Vec::new();
+    let mut ym = start_ym.min(current_ym);
+    let mut balance = get_available_saving(ym, account_repo, saving_repo)?;
+    while ym < start_ym {
+        balance += get_month_net(ym, &income_factories, &outcome_factories)?;
+        ym = get_next_ym(ym);
+    }
+    let opening = balance;
 
-    let mut current_ym = start_ym;
-    while current_ym.0 < end_ym.0 || (current_ym.0 == end_ym.0 && current_ym.1 <= end_ym.1) {
+    // 月の開始日や休日の前倒しで、次の月の分が前の暦月に入ることがあるので日付順に並べ直す
+    let mut entries: BTreeMap<DateTime<Local>, DailyEntries> = BTreeMap::new();
+    while ym <= end_ym {
+        let (year, month) = ym;
         for factory in &income_factories {
-            let factory_incomes = factory(current_ym.0, current_ym.1)?;
-            incomes.extend(factory_incomes);
+            for income in factory(year, month)? {
+                entries.entry(income.date).or_default().incomes.push(income);
+            }
         }
-
         for factory in &outcome_factories {
-            let factory_outcomes = factory(current_ym.0, current_ym.1)?;
-            outcomes.extend(factory_outcomes);
+            for outcome in factory(year, month)? {
+                entries.entry(outcome.date).or_default().outcomes.push(outcome);
+            }
         }
-
-        current_ym = get_next_ym(current_ym);
+        ym = get_next_ym(ym);
     }
 
-    let mut daily_balance: BTreeMap<DateTime<Local>, (Decimal, Decimal, Vec<Income>, Vec<Outcome>)> = BTreeMap::new();
+    let results = entries
+        .into_iter()
+        .map(|(date, entries)| {
+            let total_income: Decimal = entries.incomes.iter().map(|income| income.amount).sum();
+            let total_outcome: Decimal = entries.outcomes.iter().map(|outcome| outcome.amount).sum();
+            balance += total_income - total_outcome;
+            let balance_status = if balance >= Decimal::ZERO {
+                BalanceStatus::Surplus(balance)
+            } else {
+                BalanceStatus::Deficit(-balance)
+            };
+            InspectResult {
+                date,
+                balance_status,
+                incomes: entries.incomes,
+                outcomes: entries.outcomes,
+            }
+        })
+        .collect();
+    Ok(Forecast { start_ym, end_ym, opening, results })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::repository::{DummyAccountRepo, DummySavingRepo};
+    use crate::finance::plan::source::{EntrySource, SourceKind};
+    use crate::util::get_prev_ym;
+    use chrono::TimeZone;
 
-    for income in incomes {
-        let entry = daily_balance
-            .entry(income.date)
-            .or_insert((Decimal::ZERO, Decimal::ZERO, Vec::new(), Vec::new()));
-        entry.0 += income.amount;
-        entry.2.push(income);
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
     }
 
-    for outcome in outcomes {
-        let entry = daily_balance
-            .entry(outcome.date)
-            .or_insert((Decimal::ZERO, Decimal::ZERO, Vec::new(), Vec::new()));
-        entry.1 += outcome.amount;
-        entry.3.push(outcome);
+    #[test]
+    fn orders_entries_moved_into_the_previous_month() {
+        let income_factories: Vec<IncomeFactory> = vec![Box::new(|year, month| {
+            Ok(vec![Income {
+                name: "給料".to_string(),
+                date: date(year, month, 25),
+                amount: Decimal::from(100),
+                source: EntrySource::new(SourceKind::MonthlyIncome, Some(1)),
+            }])
+        })];
+        // 翌月の分が前の月の末日に前倒しされても日付順に並ぶ
+        let outcome_factories: Vec<OutcomeFactory> = vec![Box::new(|year, month| {
+            let (prev_year, prev_month) = get_prev_ym((year, month));
+            Ok(vec![Outcome {
+                name: "家賃".to_string(),
+                date: date(prev_year, prev_month, 28),
+                amount: Decimal::from(30),
+                source: EntrySource::new(SourceKind::MonthlyOutcome, Some(1)),
+            }])
+        })];
+        let forecast = inspect(
+            (2025, 1),
+            (2025, 3),
+            (2025, 1),
+            &DummyAccountRepo::new(),
+            &DummySavingRepo::new(),
+            income_factories,
+            outcome_factories,
+        )
+        .unwrap();
+        assert_eq!(
+            forecast.results.iter().map(|result| result.date).collect::<Vec<_>>(),
+            vec![date(2024, 12, 28), date(2025, 1, 25), date(2025, 1, 28), date(2025, 2, 25), date(2025, 2, 28), date(2025, 3, 25)],
+        );
+        assert_eq!(forecast.results.last().map(InspectResult::balance), Some(forecast.opening + Decimal::from(210)));
     }
 
-    let latest_saving = match saving_repo.get(&(start_ym.0, start_ym.1))? {
-        Some(saving) => saving.amount,
-        None => Decimal::ZERO,
-    };
-    let mut balance = latest_saving;
-    let mut results = Vec::new();
-    for (date, (total_income, total_outcome, incomes, outcomes)) in daily_balance {
-        balance += total_income - total_outcome;
-        let balance_status = if balance >= Decimal::ZERO {
-            BalanceStatus::Surplus(balance)
-        } else {
-            BalanceStatus::Deficit(-balance)
+    #[test]
+    fn summarizes_deficits_and_minimum() {
+        let balances = [(1, 500), (2, 300), (3, 400), (4, -300), (5, -300), (6, -100)];
+        let results: Vec<InspectResult> = balances
+            .into_iter()
+            .map(|(day, balance)| {
+                let balance = Decimal::from(balance);
+                InspectResult {
+                    date: date(2025, 1, day),
+                    balance_status: if balance >= Decimal::ZERO {
+                        BalanceStatus::Surplus(balance)
+                    } else {
+                        BalanceStatus::Deficit(-balance)
+                    },
+                    incomes: vec![],
+                    outcomes: vec![],
+                }
+            })
+            .collect();
+        let summary = BalanceSummary::summarize(&results, Decimal::from(350));
+        assert_eq!(summary.first_below_threshold, Some((date(2025, 1, 2), Decimal::from(300))));
+        assert_eq!(summary.first_deficit, Some((date(2025, 1, 4), Decimal::from(-300))));
+        assert_eq!(summary.minimum, Some((date(2025, 1, 4), Decimal::from(-300))));
+
+        let summary = BalanceSummary::summarize(&[], Decimal::ZERO);
+        assert_eq!(summary, BalanceSummary::default());
+    }
+
+    #[test]
+    fn totals_entries_of_the_day() {
+        let income = |name: &str, amount: i64, kind: SourceKind| Income {
+            name: name.to_string(),
+            amount: Decimal::from(amount),
+            date: date(2025, 1, 25),
+            source: EntrySource::new(kind, Some(1)),
+        };
+        let result = InspectResult {
+            date: date(2025, 1, 25),
+            balance_status: BalanceStatus::Surplus(Decimal::from(100)),
+            incomes: vec![income("給料", 200, SourceKind::MonthlyIncome), income("アルバイト", 50, SourceKind::PartTimeJob)],
+            outcomes: vec![Outcome {
+                name: "家賃".to_string(),
+                date: date(2025, 1, 25),
+                amount: Decimal::from(80),
+                source: EntrySource::new(SourceKind::MonthlyOutcome, Some(2)),
+            }],
         };
+        assert_eq!(result.total_income(), Decimal::from(250));
+        assert_eq!(result.total_outcome(), Decimal::from(80));
+    }
+
+    #[test]
+    fn carries_month_end_balances_forward() {
+        let income_factories: Vec<IncomeFactory> = vec![Box::new(|year, month| {
+            // 3 月は収入がない
+            if month == 3 {
+                return Ok(vec![]);
+            }
+            Ok(vec![Income {
+                name: "給料".to_string(),
+                date: date(year, month, 25),
+                amount: Decimal::from(100),
+                source: EntrySource::new(SourceKind::MonthlyIncome, Some(1)),
+            }])
+        })];
+        let outcome_factories: Vec<OutcomeFactory> = vec![Box::new(|year, month| {
+            Ok(vec![Outcome {
+                name: "家賃".to_string(),
+                date: date(year, month, 27),
+                amount: Decimal::from(30),
+                source: EntrySource::new(SourceKind::MonthlyOutcome, Some(1)),
+            }])
+        })];
+        let forecast = inspect(
+            (2025, 1),
+            (2025, 4),
+            (2025, 1),
+            &DummyAccountRepo::new(),
+            &DummySavingRepo::new(),
+            income_factories,
+            outcome_factories,
+        )
+        .unwrap();
+        let opening = forecast.opening;
+        let balances = forecast.month_end_balances(&AccountingPeriod::default());
+        assert_eq!(
+            balances,
+            vec![
+                ((2025, 1), opening + Decimal::from(70)),
+                ((2025, 2), opening + Decimal::from(140)),
+                ((2025, 3), opening + Decimal::from(110)),
+                ((2025, 4), opening + Decimal::from(180)),
+            ],
+        );
+    }
+
+    #[test]
+    fn rolls_up_months_and_years() {
+        let income_factories: Vec<IncomeFactory> = vec![Box::new(|year, month| {
+            Ok(vec![Income {
+                name: "給料".to_string(),
+                date: date(year, month, 25),
+                amount: Decimal::from(100),
+                source: EntrySource::new(SourceKind::MonthlyIncome, Some(1)),
+            }])
+        })];
+        // 収入の前に支払う
+        let outcome_factories: Vec<OutcomeFactory> = vec![Box::new(|year, month| {
+            Ok(vec![Outcome {
+                name: "家賃".to_string(),
+                date: date(year, month, 10),
+                amount: Decimal::from(130),
+                source: EntrySource::new(SourceKind::MonthlyOutcome, Some(1)),
+            }])
+        })];
+        let forecast = inspect(
+            (2025, 11),
+            (2026, 2),
+            (2025, 11),
+            &DummyAccountRepo::new(),
+            &DummySavingRepo::new(),
+            income_factories,
+            outcome_factories,
+        )
+        .unwrap();
+        let opening = forecast.opening;
+        let monthly = forecast.monthly_rollups(&AccountingPeriod::default());
+        assert_eq!(monthly.len(), 4);
+        assert_eq!(monthly[0].0, (2025, 11));
+        assert_eq!(
+            monthly[1].1,
+            Rollup {
+                opening: opening - Decimal::from(30),
+                total_income: Decimal::from(100),
+                total_outcome: Decimal::from(130),
+                closing: opening - Decimal::from(60),
+                minimum: opening - Decimal::from(160),
+            },
+        );
+        assert_eq!(monthly[1].1.net(), Decimal::from(-30));
+        assert!(monthly.windows(2).all(|pair| pair[0].1.closing == pair[1].1.opening));
 
-        results.push(InspectResult {
-            date,
-            balance_status,
-            incomes,
-            outcomes,
-        });
+        let yearly = yearly_rollups(&monthly);
+        assert_eq!(yearly.iter().map(|(year, _)| *year).collect::<Vec<_>>(), vec![2025, 2026]);
+        assert_eq!(yearly[0].1.opening, opening);
+        assert_eq!(yearly[0].1.total_outcome, Decimal::from(260));
+        assert_eq!(yearly[0].1.closing, monthly[1].1.closing);
+        assert_eq!(yearly[1].1.minimum, opening - Decimal::from(220));
+        assert_eq!(yearly[1].1.closing, opening - Decimal::from(120));
     }
 
-    Ok(results)
+    #[test]
+    fn carries_the_balance_to_a_later_start_month() {
+        let income_factories: Vec<IncomeFactory> = vec![Box::new(|year, month| {
+            Ok(vec![Income {
+                name: "給料".to_string(),
+                date: date(year, month, 25),
+                amount: Decimal::from(100),
+                source: EntrySource::new(SourceKind::MonthlyIncome, Some(1)),
+            }])
+        })];
+        let outcome_factories: Vec<OutcomeFactory> = vec![Box::new(|year, month| {
+            Ok(vec![Outcome {
+                name: "家賃".to_string(),
+                date: date(year, month, 27),
+                amount: Decimal::from(30),
+                source: EntrySource::new(SourceKind::MonthlyOutcome, Some(1)),
+            }])
+        })];
+        // 仮のデータでは 2025年2月の残高 235000 が最も新しい
+        let forecast = inspect(
+            (2025, 4),
+            (2025, 5),
+            (2025, 2),
+            &DummyAccountRepo::new(),
+            &DummySavingRepo::new(),
+            income_factories,
+            outcome_factories,
+        )
+        .unwrap();
+        // 2月と3月の分は返さずに残高に含める
+        assert_eq!(forecast.opening, Decimal::from(235140));
+        let results = forecast.results;
+        assert_eq!(results.first().map(|result| (result.date, result.balance())), Some((date(2025, 4, 25), Decimal::from(235240))));
+        assert_eq!(results.len(), 4);
+    }
+
+    #[test]
+    fn fails_when_a_month_fails() {
+        let income_factories: Vec<IncomeFactory> = vec![Box::new(|_, month| {
+            if month == 2 {
+                anyhow::bail!("broken");
+            }
+            Ok(vec![])
+        })];
+        let forecast = inspect(
+            (2025, 1),
+            (2025, 12),
+            (2025, 1),
+            &DummyAccountRepo::new(),
+            &DummySavingRepo::new(),
+            income_factories,
+            vec![],
+        );
+        assert!(forecast.is_err());
+    }
 }
@@ -1,11 +1,14 @@
 use chrono::{DateTime, Local};
 use rust_decimal::Decimal;
 
+use crate::finance::plan::source::EntrySource;
+
 #[derive(Debug, Clone)]
 pub struct Income {
     pub name: String,
     pub amount: Decimal,
     pub date: DateTime<Local>,
+    pub source: EntrySource,
 }
 
 pub trait ToIncome {
@@ -19,15 +22,3 @@ pub trait IncomeRepo {
         end_date: &DateTime<Local>,
     ) -> Result<Vec<Income>, anyhow::Error>;
 }
-
-pub fn get_incomes<T: IncomeRepo>(
-    repos: Vec<&T>,
-    start_date: &DateTime<Local>,
-    end_date: &DateTime<Local>,
-) -> Result<Vec<Income>, anyhow::Error> {
-    let mut incomes = Vec::new();
-    for repo in repos {
-        incomes.append(&mut repo.list_incomes(start_date, end_date)?);
-    }
-    Ok(incomes)
-}
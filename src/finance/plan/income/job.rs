@@ -1,17 +1,22 @@
-use chrono::{DateTime, Local, TimeZone, Datelike};
+use chrono::{DateTime, Local, Datelike, Months, NaiveDate, NaiveTime, TimeZone};
 use rust_decimal::Decimal;
+use crate::finance::error::FinanceError;
 use super::income::{Income, ToIncome, IncomeRepo};
-use crate::util::{get_end_of_month, get_next_ym};
-use crate::finance::setting::get_opening_and_closing_date;
+use crate::util::{get_next_ym, get_prev_ym};
+use crate::finance::detail::calendar::{BusinessCalendar, BusinessDayRoll};
+use crate::finance::plan::recurrence::{to_local_datetime, MissingDayPolicy, RecurrenceRule};
+use crate::finance::plan::source::{EntrySource, SourceKind};
+use crate::finance::setting::AccountingPeriod;
 
 // 入金日
-// 月末, 月中(何日か)
+// 働いた月の month_offset か月後の月に rule で決まる日 (当月末なら 0 か月後の毎月末日)
+// 毎週・隔週払いならその月に何回か入金される
 #[derive(Debug, Clone)]
-pub enum PaymentTiming {
-    End,
-    Mid(u32),
-    NextMonthEnd,
-    NextMonthMid(u32),
+pub struct PaymentTiming {
+    pub month_offset: u32,
+    pub rule: RecurrenceRule,
+    // 31日払いの 2月のように入金日がない月の扱い
+    pub missing_day: MissingDayPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -19,8 +24,12 @@ pub struct PartTimeJob {
     pub id: Option<u64>,
     pub name: String,
     pub payment_timing: PaymentTiming,
+    // 入金日が休日のときにずらす向き
+    pub business_day_roll: BusinessDayRoll,
     pub start_date: DateTime<Local>,
     pub end_date: Option<DateTime<Local>>,
+    // 明細に記録する口座 (None なら既定の口座)
+    pub account_id: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,16 +55,41 @@ impl ToIncome for PartTimeJobIncome {
             name: self.name.clone(),
             amount: self.hourly_wage * self.hour,
             date: self.payment_date,
+            source: EntrySource::new(SourceKind::PartTimeJob, Some(self.part_time_job_id)),
         }
     }
 }
 
 impl PartTimeJob {
+    pub fn try_new(
+        name: String,
+        payment_timing: PaymentTiming,
+        business_day_roll: BusinessDayRoll,
+        start_date: NaiveDate,
+    ) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
+        payment_timing.rule.validate()?;
+        let start_date = Local.from_local_datetime(&start_date.and_time(NaiveTime::MIN))
+            .single()
+            .ok_or_else(|| FinanceError::InvalidDate(start_date.to_string()))?;
+        Ok(Self {
+            id: None,
+            name: name.trim().to_string(),
+            payment_timing,
+            business_day_roll,
+            start_date,
+            end_date: None,
+            account_id: None,
+        })
+    }
+
     pub fn get_hourly_wage(
         &self,
         year: i32,
         month: u32,
-        repo: &impl PartTimeJobRepo,
+        repo: &dyn PartTimeJobRepo,
     ) -> Option<PartTimeHourlyWage> {
         repo.get_part_time_job_hourly_wage(self.id.unwrap(), year, month)
             .unwrap_or(None)
@@ -65,7 +99,7 @@ impl PartTimeJob {
         &self,
         hourly_wage: Decimal,
         start_year_and_month: (i32, u32),
-        repo: &impl PartTimeJobRepo,
+        repo: &dyn PartTimeJobRepo,
     ) -> Result<(), anyhow::Error> {
         match repo.get_part_time_job_hourly_wage_by_start_year_and_month(
             self.id.unwrap(),
@@ -89,54 +123,89 @@ impl PartTimeJob {
         Ok(())
     }
 
-    pub fn get_payment_date(
+    // 働いた月 (year, month) が働き始めてから辞めるまでに入っているか
+    fn is_working_in(&self, year_and_month: (i32, u32)) -> bool {
+        (self.start_date.year(), self.start_date.month()) <= year_and_month
+            && self.end_date.is_none_or(|end_date| year_and_month <= (end_date.year(), end_date.month()))
+    }
+
+    // 入金日がない月を飛ばす設定なら空になる
+    // 休日でずらしても入金される月の中に収まる
+    pub fn get_payment_dates(
         &self,
         year: i32,
         month: u32,
-    ) -> Result<DateTime<Local>, anyhow::Error> {
-        match self.payment_timing {
-            PaymentTiming::End => get_end_of_month(year, month),
-            PaymentTiming::Mid(day) => Local
-                .with_ymd_and_hms(year, month, day, 0, 0, 0)
-                .single()
-                .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day)),
-            PaymentTiming::NextMonthEnd => {
-                let (year, month) = get_next_ym((year, month));
-                return get_end_of_month(year, month)
-            },
-            PaymentTiming::NextMonthMid(day) => {
-                let (year, month) = get_next_ym((year, month));
-                Local
-                    .with_ymd_and_hms(year, month, day, 0, 0, 0)
-                    .single()
-                    .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))
-            },
-        }
+        calendar: &BusinessCalendar,
+    ) -> Result<Vec<DateTime<Local>>, anyhow::Error> {
+        let timing = &self.payment_timing;
+        let shift = |ym| (0..timing.month_offset).fold(ym, |ym, _| get_next_ym(ym));
+        let first_day = |(year, month)| NaiveDate::from_ymd_opt(year, month, 1);
+        // 隔週などの起点は働き始めた月の分が入金される月の 1 日
+        let start_ym = (self.start_date.year(), self.start_date.month());
+        let (Some(first), Some(anchor)) = (first_day(shift((year, month))), first_day(shift(start_ym))) else {
+            return Ok(vec![]);
+        };
+        let last = first.checked_add_months(Months::new(1)).and_then(|date| date.pred_opt()).unwrap_or(first);
+        timing
+            .rule
+            .occurrences(anchor, first, last, timing.missing_day)
+            .into_iter()
+            .map(|date| to_local_datetime(calendar.roll_within_month(date, self.business_day_roll)))
+            .collect()
     }
 
-    pub fn to_part_time_job_income(
+    // 保存する前の給料
+    // 時給はその月に有効な時給、時間はまだ分からないので 0
+    fn new_part_time_job_income(
         &self,
         year: i32,
         month: u32,
-        hour: Decimal,
-        repo: &impl PartTimeJobRepo,
-    ) -> Result<PartTimeJobIncome, anyhow::Error> {
+        payment_date: DateTime<Local>,
+        repo: &dyn PartTimeJobRepo,
+    ) -> PartTimeJobIncome {
         let hourly_wage = self
             .get_hourly_wage(year, month, repo)
             .map_or(Decimal::ZERO, |wage| wage.hourly_wage);
-
-        let mut income = PartTimeJobIncome {
+        PartTimeJobIncome {
             id: None,
             part_time_job_id: self.id.unwrap(),
             name: self.name.clone(),
             hourly_wage,
-            hour,
-            payment_date: self.get_payment_date(year, month)?,
-        };
+            hour: Decimal::ZERO,
+            payment_date,
+        }
+    }
 
-        let id = repo.store_part_time_job_income(income.clone())?;
-        income.id = Some(id);
-        Ok(income)
+    // 保存済みの給料と、まだない入金日の分は保存せずに見込んだ給料 (id は None)
+    // 入金日がない月を飛ばす設定ならその月の給料はない
+    pub fn project_part_time_job_incomes(
+        &self,
+        year: i32,
+        month: u32,
+        calendar: &BusinessCalendar,
+        repo: &dyn PartTimeJobRepo,
+    ) -> Result<Vec<PartTimeJobIncome>, anyhow::Error> {
+        let payment_dates = self.get_payment_dates(year, month, calendar)?;
+        let Some(payment_date) = payment_dates.first() else {
+            return Ok(vec![]);
+        };
+        let mut incomes = repo.list_part_time_job_incomes_by_part_time_job_id(
+            self.id.unwrap(),
+            payment_date.year(),
+            payment_date.month(),
+        )?;
+        // 入金日を編集したものもあるので、足りない件数だけ見込む
+        let missing = payment_dates.len().saturating_sub(incomes.len());
+        let missing_dates: Vec<_> = payment_dates
+            .into_iter()
+            .filter(|date| !incomes.iter().any(|income| income.payment_date == *date))
+            .take(missing)
+            .collect();
+        for payment_date in missing_dates {
+            incomes.push(self.new_part_time_job_income(year, month, payment_date, repo));
+        }
+        incomes.sort_by_key(|income| income.payment_date);
+        Ok(incomes)
     }
 }
 
@@ -198,12 +267,13 @@ pub trait PartTimeJobRepo: IncomeRepo {
         &self,
         id: u64,
     ) -> Result<Option<PartTimeJobIncome>, anyhow::Error>;
-    fn get_part_time_job_income_by_part_time_job_id(
+    // year 年 month 月に入金される給料
+    fn list_part_time_job_incomes_by_part_time_job_id(
         &self,
         part_time_job_id: u64,
         year: i32,
         month: u32,
-    ) -> Result<Option<PartTimeJobIncome>, anyhow::Error>;
+    ) -> Result<Vec<PartTimeJobIncome>, anyhow::Error>;
     fn store_part_time_job_income(
         &self,
         part_time_job_income: PartTimeJobIncome,
@@ -214,26 +284,156 @@ pub trait PartTimeJobRepo: IncomeRepo {
     ) -> Result<(), anyhow::Error>;
 }
 
-pub fn get_or_create_part_time_job_incomes(
+// アルバイトを保存し、働き始めた月からの時給を設定する
+pub fn store_part_time_job(
+    mut part_time_job: PartTimeJob,
+    hourly_wage: Decimal,
+    repo: &dyn PartTimeJobRepo,
+) -> Result<u64, anyhow::Error> {
+    let start_year_and_month = (part_time_job.start_date.year(), part_time_job.start_date.month());
+    let id = repo.store_part_time_job(part_time_job.clone())?;
+    part_time_job.id = Some(id);
+    part_time_job.set_hourly_wage(hourly_wage, start_year_and_month, repo)?;
+    Ok(id)
+}
+
+// 保存せずにその月の見込みを返す
+pub fn project_part_time_job_incomes(
     year: i32,
     month: u32,
-    repo: &impl PartTimeJobRepo,
+    period: &AccountingPeriod,
+    calendar: &BusinessCalendar,
+    repo: &dyn PartTimeJobRepo,
 ) -> Result<Vec<Income>, anyhow::Error> {
-    let (start_date, end_date) = get_opening_and_closing_date(year, month)?;
-    let part_time_jobs = repo.list_part_time_jobs(&start_date, &end_date)?;
+    let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+    let mut incomes = Vec::new();
+    for job in repo.list_part_time_jobs(&start_date, &end_date)? {
+        let projected = job.project_part_time_job_incomes(year, month, calendar, repo)?;
+        incomes.extend(projected.iter().map(|income| income.to_income()));
+    }
+    Ok(incomes)
+}
 
+// start_date から end_date までに入金される給料
+// 入金は働いた月の month_offset か月後なので、辞めた後に入金されるアルバイトも含めて探す
+pub fn project_part_time_job_incomes_paid_between(
+    start_date: &DateTime<Local>,
+    end_date: &DateTime<Local>,
+    calendar: &BusinessCalendar,
+    repo: &dyn PartTimeJobRepo,
+) -> Result<Vec<PartTimeJobIncome>, anyhow::Error> {
+    let since = DateTime::UNIX_EPOCH.with_timezone(&Local);
     let mut incomes = Vec::new();
-    for job in part_time_jobs {
-        let job_payment_date = job.get_payment_date(year, month)?;
-        let income = match repo.get_part_time_job_income_by_part_time_job_id(
-            job.id.unwrap(),
-            job_payment_date.year(),
-            job_payment_date.month(),
-        )? {
-            Some(income) => income,
-            None => job.to_part_time_job_income(year, month, Decimal::ZERO, repo)?,
-        };
-        incomes.push(income.to_income());
+    for job in repo.list_part_time_jobs(&since, end_date)? {
+        let shift_back = |ym| (0..job.payment_timing.month_offset).fold(ym, |ym, _| get_prev_ym(ym));
+        let mut working_yms = vec![
+            shift_back((start_date.year(), start_date.month())),
+            shift_back((end_date.year(), end_date.month())),
+        ];
+        working_yms.dedup();
+        for (year, month) in working_yms.into_iter().filter(|ym| job.is_working_in(*ym)) {
+            incomes.extend(
+                job.project_part_time_job_incomes(year, month, calendar, repo)?
+                    .into_iter()
+                    .filter(|income| *start_date <= income.payment_date && income.payment_date <= *end_date),
+            );
+        }
     }
     Ok(incomes)
-}
\ No newline at end of file
+}
+
+// 月が始まったらその月の分を保存する
+pub fn create_part_time_job_incomes(
+    year: i32,
+    month: u32,
+    period: &AccountingPeriod,
+    calendar: &BusinessCalendar,
+    repo: &dyn PartTimeJobRepo,
+) -> Result<(), anyhow::Error> {
+    let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+    for job in repo.list_part_time_jobs(&start_date, &end_date)? {
+        for income in job.project_part_time_job_incomes(year, month, calendar, repo)? {
+            if income.id.is_none() {
+                repo.store_part_time_job_income(income)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::plan::recurrence::MonthDay;
+    use crate::finance::plan::repository::DummyPartTimeJobRepo;
+    use chrono::Weekday;
+
+    fn store_job(repo: &DummyPartTimeJobRepo, month_offset: u32, rule: RecurrenceRule) -> PartTimeJob {
+        let payment_timing = PaymentTiming { month_offset, rule, missing_day: MissingDayPolicy::Clamp };
+        let start_date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let mut job = PartTimeJob::try_new("塾講師".to_string(), payment_timing, BusinessDayRoll::Previous, start_date).unwrap();
+        job.id = Some(store_part_time_job(job.clone(), Decimal::from(1300), repo).unwrap());
+        job
+    }
+
+    fn days(incomes: &[PartTimeJobIncome]) -> Vec<(bool, u32)> {
+        incomes.iter().map(|income| (income.id.is_some(), income.payment_date.day())).collect()
+    }
+
+    #[test]
+    fn pays_on_every_payday_of_the_lagged_month() {
+        let (repo, calendar) = (DummyPartTimeJobRepo::new(), BusinessCalendar::japanese());
+        // 3月に働いた分が 4月の毎週金曜日に入る
+        let weekly = store_job(&repo, 1, RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Fri });
+        let projected = weekly.project_part_time_job_incomes(2025, 3, &calendar, &repo).unwrap();
+        assert_eq!(days(&projected), vec![(false, 4), (false, 11), (false, 18), (false, 25)]);
+        assert!(projected.iter().all(|income| income.hourly_wage == Decimal::from(1300)));
+
+        // 2025年4月の第 2 土曜日は 12日なので前営業日の 11日になる
+        let nth = store_job(&repo, 1, RecurrenceRule::monthly(MonthDay::NthWeekday(2, Weekday::Sat)));
+        assert_eq!(days(&nth.project_part_time_job_incomes(2025, 3, &calendar, &repo).unwrap()), vec![(false, 11)]);
+    }
+
+    #[test]
+    fn projects_only_the_paydays_not_yet_stored() {
+        let (repo, calendar) = (DummyPartTimeJobRepo::new(), BusinessCalendar::japanese());
+        let biweekly = store_job(&repo, 0, RecurrenceRule::Weekly { interval: 2, weekday: Weekday::Fri });
+        // 起点は 3月 1日なので 3月 7日, 21日
+        let projected = biweekly.project_part_time_job_incomes(2025, 3, &calendar, &repo).unwrap();
+        assert_eq!(days(&projected), vec![(false, 7), (false, 21)]);
+
+        let first = PartTimeJobIncome { hour: Decimal::from(12), ..projected[0].clone() };
+        repo.store_part_time_job_income(first).unwrap();
+        let projected = biweekly.project_part_time_job_incomes(2025, 3, &calendar, &repo).unwrap();
+        assert_eq!(days(&projected), vec![(true, 7), (false, 21)]);
+        assert_eq!(projected[0].hour, Decimal::from(12));
+
+        let period = AccountingPeriod::default();
+        create_part_time_job_incomes(2025, 3, &period, &calendar, &repo).unwrap();
+        let stored = repo.list_part_time_job_incomes_by_part_time_job_id(biweekly.id.unwrap(), 2025, 3).unwrap();
+        assert_eq!(days(&stored), vec![(true, 7), (true, 21)]);
+    }
+
+    #[test]
+    fn finds_incomes_paid_after_the_job_ended() {
+        let (repo, calendar) = (DummyPartTimeJobRepo::new(), BusinessCalendar::japanese());
+        let mut job = store_job(&repo, 1, RecurrenceRule::monthly(MonthDay::Day(10)));
+        job.end_date = Some(to_local_datetime(NaiveDate::from_ymd_opt(2025, 3, 31).unwrap()).unwrap());
+        repo.update_part_time_job(job.clone()).unwrap();
+
+        let period = AccountingPeriod::default();
+        let paid = |month| {
+            let (start_date, end_date) = period.get_opening_and_closing_date(2025, month).unwrap();
+            project_part_time_job_incomes_paid_between(&start_date, &end_date, &calendar, &repo)
+                .unwrap()
+                .into_iter()
+                .filter(|income| income.part_time_job_id == job.id.unwrap())
+                .map(|income| income.payment_date.day())
+                .collect::<Vec<_>>()
+        };
+        // 3月に働いた分は辞めた後の 4月 10日に入り、5月にはない
+        assert_eq!(paid(4), vec![10]);
+        assert!(paid(5).is_empty());
+        assert!(project_part_time_job_incomes(2025, 4, &period, &calendar, &repo).unwrap().iter().all(|income| income.name != "塾講師"));
+    }
+}
@@ -1,6 +1,6 @@
 pub mod income;
 pub mod job;
+pub mod monthly_income;
 pub mod temporary_income;
 
 pub use income::{Income, IncomeRepo, ToIncome};
-pub use income::get_incomes;
\ No newline at end of file
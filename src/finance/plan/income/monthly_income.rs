@@ -0,0 +1,38 @@
+use crate::finance::detail::calendar::BusinessCalendar;
+use crate::finance::plan::monthly_template::{project_monthly_entries, MonthlyEntry, MonthlyTemplate, MonthlyTemplateRepo};
+use crate::finance::setting::AccountingPeriod;
+use crate::finance::plan::source::{EntrySource, SourceKind};
+use super::income::{Income, ToIncome, IncomeRepo};
+
+// 給料や仕送り、奨学金のように毎月決まった額が入る収入
+pub type MonthlyIncomeTemplate = MonthlyTemplate<Income>;
+
+pub type MonthlyIncome = MonthlyEntry<Income>;
+
+impl ToIncome for MonthlyIncome {
+    fn to_income(&self) -> Income {
+        Income {
+            name: self.name.clone(),
+            amount: self.amount,
+            date: self.payment_date,
+            source: EntrySource::new(SourceKind::MonthlyIncome, Some(self.template_id)),
+        }
+    }
+}
+
+pub trait MonthlyIncomeRepo: IncomeRepo + MonthlyTemplateRepo<Income> {}
+
+// 保存せずにその月の見込みを返す
+pub fn project_monthly_incomes(
+    year: i32,
+    month: u32,
+    period: &AccountingPeriod,
+    calendar: &BusinessCalendar,
+    repo: &dyn MonthlyIncomeRepo,
+) -> Result<Vec<Income>, anyhow::Error> {
+    Ok(project_monthly_entries(year, month, period, calendar, repo)?
+        .iter()
+        .map(ToIncome::to_income)
+        .collect())
+}
+
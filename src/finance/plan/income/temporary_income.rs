@@ -1,6 +1,5 @@
 use chrono::{DateTime, Local};
 use rust_decimal::Decimal;
-use super::income::{Income, ToIncome};
 
 pub struct TemporaryIncome {
     id: Option<u64>,
@@ -16,13 +15,3 @@ pub trait TemporaryIncomeRepo {
         end_date: &DateTime<Local>,
     ) -> Result<Vec<TemporaryIncome>, anyhow::Error>;
 }
-
-impl ToIncome for TemporaryIncome {
-    fn to_income(&self) -> Income {
-        Income {
-            name: self.name.clone(),
-            amount: self.amount.clone(),
-            date: self.date.clone(),
-        }
-    }
-}
\ No newline at end of file
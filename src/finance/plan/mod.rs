@@ -1,8 +1,11 @@
 pub mod income;
 pub mod outcome;
+pub mod recurrence;
 pub mod repository;
 pub mod future_inspector;
+pub mod monthly_template;
+pub mod reconciliation;
+pub mod scenario;
+pub mod source;
 
-pub use income::get_incomes;
-pub use income::job::PartTimeJobRepo;
-pub use repository::{get_part_time_job_repo, get_monthly_outcome_repo, get_temporary_outcome_repo};
\ No newline at end of file
+pub use repository::*;
\ No newline at end of file
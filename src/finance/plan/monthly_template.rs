@@ -0,0 +1,283 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use rust_decimal::prelude::*;
+use std::marker::PhantomData;
+
+use crate::finance::detail::calendar::{BusinessCalendar, BusinessDayRoll};
+use crate::finance::error::FinanceError;
+use crate::finance::plan::recurrence::{to_local_datetime, MissingDayPolicy, RecurrenceRule};
+use crate::finance::setting::AccountingPeriod;
+
+// 毎月決まった額が入る収入・出ていく支出のテンプレート
+// T は見込みから作る収入か支出 (Income か Outcome)
+#[derive(Debug, Clone)]
+pub struct MonthlyTemplate<T> {
+    pub id: Option<u64>,
+    pub name: String,
+    pub amount: Decimal,
+    pub payment_timing: RecurrenceRule,
+    // 31日払いの 2月のように支払日がない月の扱い
+    pub missing_day: MissingDayPolicy,
+    // 支払日が休日のときにずらす向き
+    pub business_day_roll: BusinessDayRoll,
+    pub start_date: DateTime<Local>,
+    pub end_date: Option<DateTime<Local>>,
+    // 明細に記録する口座 (None なら既定の口座)
+    pub account_id: Option<u64>,
+    pub entry: PhantomData<T>,
+}
+
+// テンプレートから作った支払日 1 回分
+#[derive(Debug, Clone)]
+pub struct MonthlyEntry<T> {
+    pub id: Option<u64>,
+    pub template_id: u64,
+    pub name: String,
+    pub amount: Decimal,
+    pub payment_date: DateTime<Local>,
+    pub entry: PhantomData<T>,
+}
+
+pub trait MonthlyTemplateRepo<T> {
+    fn list_monthly_templates(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyTemplate<T>>, anyhow::Error>;
+    // 終わったテンプレートも返す
+    fn get_monthly_template_by_id(&self, id: u64) -> Result<Option<MonthlyTemplate<T>>, anyhow::Error>;
+    fn store_monthly_template(&self, template: MonthlyTemplate<T>) -> Result<u64, anyhow::Error>;
+    fn store_monthly_entry(&self, entry: MonthlyEntry<T>) -> Result<u64, anyhow::Error>;
+    fn list_monthly_entries_by_template_id(
+        &self,
+        template_id: u64,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyEntry<T>>, anyhow::Error>;
+}
+
+impl<T: Clone> MonthlyTemplate<T> {
+    pub fn try_new(
+        name: String,
+        amount: String,
+        payment_timing: RecurrenceRule,
+        missing_day: MissingDayPolicy,
+        business_day_roll: BusinessDayRoll,
+        start_date: NaiveDate,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
+        payment_timing.validate()?;
+        let amount = Decimal::from_str(&amount)
+            .map_err(|_| FinanceError::InvalidAmount(amount))?;
+        let to_datetime = |date: NaiveDate| {
+            Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .single()
+                .ok_or_else(|| FinanceError::InvalidDate(date.to_string()))
+        };
+        Ok(Self {
+            id: None,
+            name: name.trim().to_string(),
+            amount,
+            payment_timing,
+            missing_day,
+            business_day_roll,
+            start_date: to_datetime(start_date)?,
+            end_date: end_date.map(to_datetime).transpose()?,
+            account_id: None,
+            entry: PhantomData,
+        })
+    }
+
+    // 保存する前の支払日の分
+    fn new_entry(&self, payment_date: DateTime<Local>) -> MonthlyEntry<T> {
+        MonthlyEntry {
+            id: None,
+            template_id: self.id.unwrap(),
+            name: self.name.clone(),
+            amount: self.amount,
+            payment_date,
+            entry: PhantomData,
+        }
+    }
+
+    // 毎週などの規則では 1 か月に複数回、毎年などの規則では 0 回になる
+    // 休日でずらしてもその月の中に収まる
+    pub fn get_payment_dates(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+    ) -> Result<Vec<DateTime<Local>>, anyhow::Error> {
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(year, month)?;
+        let (first, last) = (opening_date.date_naive(), closing_date.date_naive());
+        self.payment_timing
+            .get_dates_in_period(&opening_date, &closing_date, &self.start_date, self.end_date.as_ref(), self.missing_day)?
+            .into_iter()
+            .map(|date| to_local_datetime(calendar.roll_within(date.date_naive(), self.business_day_roll, first, last)))
+            .collect()
+    }
+
+    // まだ作られていない支払日の分は保存せずに見込む (id は None)
+    pub fn project_entries<R: MonthlyTemplateRepo<T> + ?Sized>(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+        repo: &R,
+    ) -> Result<Vec<MonthlyEntry<T>>, anyhow::Error> {
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(year, month)?;
+        let mut entries = repo.list_monthly_entries_by_template_id(self.id.unwrap(), &opening_date, &closing_date)?;
+        let payment_dates = self.get_payment_dates(year, month, period, calendar)?;
+        // 支払日を編集したものもあるので、足りない件数だけ見込む
+        let missing = payment_dates.len().saturating_sub(entries.len());
+        let missing_dates: Vec<_> = payment_dates
+            .into_iter()
+            .filter(|date| !entries.iter().any(|entry| entry.payment_date == *date))
+            .take(missing)
+            .collect();
+        for payment_date in missing_dates {
+            entries.push(self.new_entry(payment_date));
+        }
+        entries.sort_by_key(|entry| entry.payment_date);
+        Ok(entries)
+    }
+
+    // 見込みの分を保存して、その月の分を確定させる
+    pub fn get_or_create_entries<R: MonthlyTemplateRepo<T> + ?Sized>(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+        repo: &R,
+    ) -> Result<Vec<MonthlyEntry<T>>, anyhow::Error> {
+        let mut entries = self.project_entries(year, month, period, calendar, repo)?;
+        for entry in entries.iter_mut().filter(|entry| entry.id.is_none()) {
+            entry.id = Some(repo.store_monthly_entry(entry.clone())?);
+        }
+        Ok(entries)
+    }
+}
+
+// 保存せずにその月の見込みを返す
+pub fn project_monthly_entries<T: Clone, R: MonthlyTemplateRepo<T> + ?Sized>(
+    year: i32,
+    month: u32,
+    period: &AccountingPeriod,
+    calendar: &BusinessCalendar,
+    repo: &R,
+) -> Result<Vec<MonthlyEntry<T>>, anyhow::Error> {
+    let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+    let mut entries = Vec::new();
+    for template in repo.list_monthly_templates(&start_date, &end_date)? {
+        entries.extend(template.project_entries(year, month, period, calendar, repo)?);
+    }
+    Ok(entries)
+}
+
+// 月が始まったらその月の分を保存する
+pub fn create_monthly_entries<T: Clone, R: MonthlyTemplateRepo<T> + ?Sized>(
+    year: i32,
+    month: u32,
+    period: &AccountingPeriod,
+    calendar: &BusinessCalendar,
+    repo: &R,
+) -> Result<(), anyhow::Error> {
+    let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+    for template in repo.list_monthly_templates(&start_date, &end_date)? {
+        template.get_or_create_entries(year, month, period, calendar, repo)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::plan::income::monthly_income::{MonthlyIncome, MonthlyIncomeTemplate};
+    use crate::finance::plan::recurrence::MonthDay;
+    use crate::finance::plan::repository::DummyMonthlyIncomeRepo;
+    use chrono::{Datelike, Weekday};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn store_template(
+        repo: &DummyMonthlyIncomeRepo,
+        payment_timing: RecurrenceRule,
+        business_day_roll: BusinessDayRoll,
+        end_date: Option<NaiveDate>,
+    ) -> MonthlyIncomeTemplate {
+        let mut template = MonthlyIncomeTemplate::try_new(
+            "給料".to_string(),
+            "200000".to_string(),
+            payment_timing,
+            MissingDayPolicy::Clamp,
+            business_day_roll,
+            date(4, 1),
+            end_date,
+        )
+        .unwrap();
+        template.id = Some(repo.store_monthly_template(template.clone()).unwrap());
+        template
+    }
+
+    fn days(entries: &[MonthlyIncome]) -> Vec<(Option<u64>, u32)> {
+        entries.iter().map(|entry| (entry.id, entry.payment_date.day())).collect()
+    }
+
+    #[test]
+    fn projects_every_payment_date_in_the_month() {
+        let (repo, period, calendar) = (DummyMonthlyIncomeRepo::new(), AccountingPeriod::default(), BusinessCalendar::japanese());
+        let weekly = store_template(&repo, RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Fri }, BusinessDayRoll::None, None);
+        let projected = weekly.project_entries(2025, 4, &period, &calendar, &repo).unwrap();
+        assert_eq!(days(&projected), vec![(None, 4), (None, 11), (None, 18), (None, 25)]);
+
+        // 2025年5月25日は日曜日なので前営業日の 23日になる
+        let monthly = store_template(&repo, RecurrenceRule::monthly(MonthDay::Day(25)), BusinessDayRoll::Previous, None);
+        let projected = monthly.project_entries(2025, 5, &period, &calendar, &repo).unwrap();
+        assert_eq!(days(&projected), vec![(None, 23)]);
+    }
+
+    #[test]
+    fn stops_projecting_after_the_end_date() {
+        let (repo, period, calendar) = (DummyMonthlyIncomeRepo::new(), AccountingPeriod::default(), BusinessCalendar::japanese());
+        let template = store_template(&repo, RecurrenceRule::monthly(MonthDay::Day(10)), BusinessDayRoll::None, Some(date(5, 5)));
+        assert_eq!(days(&template.project_entries(2025, 4, &period, &calendar, &repo).unwrap()), vec![(None, 10)]);
+        // 5月は支払日より前に終わる
+        assert!(template.project_entries(2025, 5, &period, &calendar, &repo).unwrap().is_empty());
+
+        let (start_date, end_date) = period.get_opening_and_closing_date(2025, 6).unwrap();
+        let ids: Vec<Option<u64>> = repo
+            .list_monthly_templates(&start_date, &end_date)
+            .unwrap()
+            .iter()
+            .map(|template| template.id)
+            .collect();
+        assert!(!ids.contains(&template.id));
+    }
+
+    #[test]
+    fn stores_only_the_missing_entries() {
+        let (repo, period, calendar) = (DummyMonthlyIncomeRepo::new(), AccountingPeriod::default(), BusinessCalendar::japanese());
+        let template = store_template(&repo, RecurrenceRule::monthly(MonthDay::Day(10)), BusinessDayRoll::None, None);
+        let created = template.get_or_create_entries(2025, 4, &period, &calendar, &repo).unwrap();
+        assert!(created.iter().all(|entry| entry.id.is_some()));
+        // 2 回目は保存した分を返す
+        assert_eq!(days(&template.get_or_create_entries(2025, 4, &period, &calendar, &repo).unwrap()), days(&created));
+
+        // 支払日を動かした分があれば、その月の分は見込まない
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(2025, 5).unwrap();
+        let moved = to_local_datetime(date(5, 12)).unwrap();
+        let id = repo.store_monthly_entry(MonthlyEntry { payment_date: moved, ..created[0].clone() }).unwrap();
+        assert_eq!(days(&template.project_entries(2025, 5, &period, &calendar, &repo).unwrap()), vec![(Some(id), 12)]);
+
+        create_monthly_entries(2025, 5, &period, &calendar, &repo).unwrap();
+        let stored = repo.list_monthly_entries_by_template_id(template.id.unwrap(), &opening_date, &closing_date).unwrap();
+        assert_eq!(days(&stored), vec![(Some(id), 12)]);
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Local};
+use rust_decimal::Decimal;
+
+use super::Outcome;
+use crate::finance::detail::budget::{get_budget_status, Budget, BudgetRepo, BudgetTarget};
+use crate::finance::detail::category::CategoryRepo;
+use crate::finance::detail::outcome::OutcomeRepo as DetailOutcomeRepo;
+use crate::finance::plan::source::{EntrySource, SourceKind};
+use crate::finance::setting::AccountingPeriod;
+
+// 予算に含まれる、予測済みでまだ来ていない支出の合計
+// 全体の予算はすべての支出を、名前の予算は同じ名前の支出を含む
+// 計画の支出にはカテゴリがないので、カテゴリの予算は何も含まない
+fn get_covered_amount(budget: &Budget, covered: &[Outcome], today: &DateTime<Local>) -> Decimal {
+    covered
+        .iter()
+        .filter(|outcome| outcome.date.date_naive() >= today.date_naive())
+        .filter(|outcome| match &budget.target {
+            BudgetTarget::Overall => true,
+            BudgetTarget::OutcomeName(name) => outcome.name.trim() == name.trim(),
+            BudgetTarget::Category(_) => false,
+        })
+        .map(|outcome| outcome.amount)
+        .sum()
+}
+
+// まだ終わっていない月について、予算を月の終わりの支出見込みとして扱う
+// 今月は残りの予算だけを、来月以降は予算の全額を見込む
+// 全体の予算があれば、項目別の予算と二重に数えないよう全体の予算だけを使う
+// covered はその月の毎月の支出やカードの引き落としなど予測済みの支出で、予算から差し引く
+pub fn get_budget_outcomes(
+    (year, month): (i32, u32),
+    today: &DateTime<Local>,
+    period: &AccountingPeriod,
+    covered: &[Outcome],
+    budget_repo: &dyn BudgetRepo,
+    outcome_repo: &dyn DetailOutcomeRepo,
+    category_repo: &dyn CategoryRepo,
+) -> Result<Vec<Outcome>, anyhow::Error> {
+    let current_ym = period.get_year_month(today);
+    if (year, month) < current_ym {
+        return Ok(vec![]);
+    }
+    let budgets: Vec<_> = budget_repo
+        .list()?
+        .into_iter()
+        .filter(|budget| budget.is_active(year, month))
+        .collect();
+    let has_overall = budgets.iter().any(|budget| budget.target == BudgetTarget::Overall);
+    let categories = category_repo.list()?;
+    let (_, closing_date) = period.get_opening_and_closing_date(year, month)?;
+
+    let mut outcomes = vec![];
+    for budget in budgets {
+        if has_overall && budget.target != BudgetTarget::Overall {
+            continue;
+        }
+        let remaining = if (year, month) == current_ym {
+            get_budget_status(&budget, year, month, today, period, outcome_repo, &categories)?.remaining
+        } else {
+            budget.amount
+        };
+        let amount = (remaining - get_covered_amount(&budget, covered, today)).max(Decimal::ZERO);
+        if amount > Decimal::ZERO {
+            outcomes.push(Outcome {
+                name: format!("予算: {}", budget.label(&categories)),
+                date: closing_date,
+                amount,
+                source: EntrySource::new(SourceKind::Budget, budget.id),
+            });
+        }
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::repository::{DummyBudgetRepo, DummyCategoryRepo, DummyOutcomeRepo};
+    use crate::util::get_next_ym;
+    use chrono::TimeZone;
+
+    fn outcome(name: &str, amount: i64, date: DateTime<Local>) -> Outcome {
+        Outcome {
+            name: name.to_string(),
+            amount: Decimal::from(amount),
+            date,
+            source: EntrySource::new(SourceKind::MonthlyOutcome, Some(1)),
+        }
+    }
+
+    fn store_budget(repo: &dyn BudgetRepo, target: BudgetTarget, amount: &str) {
+        repo.store(Budget::try_new(target, amount.to_string(), false, (2000, 1)).unwrap()).unwrap();
+    }
+
+    fn next_month(today: &DateTime<Local>, period: &AccountingPeriod) -> ((i32, u32), DateTime<Local>) {
+        let (year, month) = get_next_ym(period.get_year_month(today));
+        ((year, month), Local.with_ymd_and_hms(year, month, 10, 0, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn deducts_forecast_outcomes_from_the_overall_budget() {
+        let (budget_repo, outcome_repo, category_repo) = (DummyBudgetRepo::new(), DummyOutcomeRepo::new(), DummyCategoryRepo::new());
+        let (today, period) = (Local::now(), AccountingPeriod::default());
+        let (ym, date) = next_month(&today, &period);
+        store_budget(&budget_repo, BudgetTarget::Overall, "200000");
+        let covered = vec![outcome("家賃", 80000, date), outcome("カード 引き落とし", 20000, date)];
+
+        let outcomes = get_budget_outcomes(ym, &today, &period, &covered, &budget_repo, &outcome_repo, &category_repo).unwrap();
+        let amounts: Vec<Decimal> = outcomes.iter().map(|outcome| outcome.amount).collect();
+        assert_eq!(amounts, vec![Decimal::from(100000)]);
+
+        // 予測済みの支出だけで予算を超えていれば、予算の分は見込まない
+        let covered = vec![outcome("家賃", 250000, date)];
+        let outcomes = get_budget_outcomes(ym, &today, &period, &covered, &budget_repo, &outcome_repo, &category_repo).unwrap();
+        assert!(outcomes.is_empty());
+    }
+
+    #[test]
+    fn deducts_only_outcomes_with_the_same_name_from_a_name_budget() {
+        let (budget_repo, outcome_repo, category_repo) = (DummyBudgetRepo::new(), DummyOutcomeRepo::new(), DummyCategoryRepo::new());
+        let (today, period) = (Local::now(), AccountingPeriod::default());
+        let (ym, date) = next_month(&today, &period);
+        store_budget(&budget_repo, BudgetTarget::OutcomeName("ジム".to_string()), "10000");
+        let covered = vec![outcome("ジム", 8000, date), outcome("家賃", 80000, date)];
+
+        let outcomes = get_budget_outcomes(ym, &today, &period, &covered, &budget_repo, &outcome_repo, &category_repo).unwrap();
+        let amounts: Vec<(String, Decimal)> = outcomes.into_iter().map(|outcome| (outcome.name, outcome.amount)).collect();
+        // カテゴリの予算は計画の支出と重ならないので全額を見込む
+        assert_eq!(
+            amounts,
+            vec![("予算: 食費".to_string(), Decimal::from(30000)), ("予算: ジム".to_string(), Decimal::from(2000))],
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Local};
+use rust_decimal::Decimal;
+
+use super::Outcome;
+use crate::finance::detail::account::AccountRepo;
+use crate::finance::detail::calendar::BusinessCalendar;
+use crate::finance::detail::income::IncomeRepo as DetailIncomeRepo;
+use crate::finance::detail::outcome::OutcomeRepo as DetailOutcomeRepo;
+use crate::finance::detail::statement::get_statement;
+use crate::finance::plan::source::{EntrySource, SourceKind};
+use crate::util::get_prev_ym;
+
+// クレジットカードの利用分を、利用日ではなく引き落とし日の 1 回の支出として見込む
+// 引き落とし日が過ぎた請求は支払い済みとして扱う
+pub fn get_card_statement_outcomes(
+    year: i32,
+    month: u32,
+    today: &DateTime<Local>,
+    calendar: &BusinessCalendar,
+    account_repo: &dyn AccountRepo,
+    income_repo: &dyn DetailIncomeRepo,
+    outcome_repo: &dyn DetailOutcomeRepo,
+) -> Result<Vec<Outcome>, anyhow::Error> {
+    // 引き落としは締めた月の翌月
+    let (closing_year, closing_month) = get_prev_ym((year, month));
+    let mut outcomes = vec![];
+    for account in account_repo.list()? {
+        let Some(statement) = get_statement(&account, closing_year, closing_month, calendar, income_repo, outcome_repo)? else {
+            continue;
+        };
+        if statement.payment_date.date_naive() < today.date_naive() || statement.amount <= Decimal::ZERO {
+            continue;
+        }
+        outcomes.push(Outcome {
+            name: format!("{} 引き落とし", account.name),
+            date: statement.payment_date,
+            amount: statement.amount,
+            source: EntrySource::new(SourceKind::CardStatement, account.id),
+        });
+    }
+    Ok(outcomes)
+}
@@ -1,5 +1,7 @@
 pub mod outcome;
 pub mod monthly_outcome;
 pub mod temporary_outcome;
+pub mod budget_outcome;
+pub mod card_outcome;
 
 pub use outcome::{Outcome, OutcomeRepo, ToOutcome};
\ No newline at end of file
@@ -1,11 +1,14 @@
 use chrono::{DateTime, Local};
 use rust_decimal::Decimal;
 
+use crate::finance::plan::source::EntrySource;
+
 #[derive(Debug, Clone)]
 pub struct Outcome {
     pub name: String,
     pub date: DateTime<Local>,
     pub amount: Decimal,
+    pub source: EntrySource,
 }
 
 pub trait ToOutcome {
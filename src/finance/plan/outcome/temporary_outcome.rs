@@ -1,7 +1,8 @@
 use chrono::{DateTime, Local};
 use rust_decimal::Decimal;
 use super::{Outcome, ToOutcome, OutcomeRepo};
-use crate::finance::setting::get_opening_and_closing_date;
+use crate::finance::plan::source::{EntrySource, SourceKind};
+use crate::finance::setting::AccountingPeriod;
 
 #[derive(Debug, Clone)]
 pub struct TemporaryOutcome {
@@ -17,6 +18,7 @@ impl ToOutcome for TemporaryOutcome {
             name: self.name.clone(),
             date: self.date.clone(),
             amount: self.amount.clone(),
+            source: EntrySource::new(SourceKind::TemporaryOutcome, self.id),
         }
     }
 }
@@ -35,9 +37,10 @@ pub trait TemporaryOutcomeRepo: OutcomeRepo {
 pub fn get_temporary_outcomes(
     year: i32,
     month: u32,
-    repo: &impl TemporaryOutcomeRepo,
+    period: &AccountingPeriod,
+    repo: &dyn TemporaryOutcomeRepo,
 ) -> Result<Vec<Outcome>, anyhow::Error> {
-    let (start_date, end_date) = get_opening_and_closing_date(year, month)?;
+    let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
     repo.list_temporary_outcomes(&start_date, &end_date)?
         .iter()
         .map(|outcome| Ok(outcome.to_outcome()))
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Local};
+use rust_decimal::Decimal;
+
+use super::income::Income;
+use super::outcome::Outcome;
+use super::source::EntrySource;
+
+// 計画した収入・支出 1 件
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedEntry {
+    pub name: String,
+    pub amount: Decimal,
+    pub date: DateTime<Local>,
+    pub source: EntrySource,
+    // 記録する口座 (None なら既定の口座)
+    pub account_id: Option<u64>,
+}
+
+impl From<Income> for PlannedEntry {
+    fn from(income: Income) -> Self {
+        Self { name: income.name, amount: income.amount, date: income.date, source: income.source, account_id: None }
+    }
+}
+
+impl From<Outcome> for PlannedEntry {
+    fn from(outcome: Outcome) -> Self {
+        Self { name: outcome.name, amount: outcome.amount, date: outcome.date, source: outcome.source, account_id: None }
+    }
+}
+
+// 明細に記録した収入・支出 1 件
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEntry {
+    pub id: u64,
+    pub name: String,
+    pub amount: Decimal,
+    pub date: DateTime<Local>,
+}
+
+// 計画と記録の組
+// 計画だけなら未記録、記録だけなら計画外
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconciledEntry {
+    pub planned: Option<PlannedEntry>,
+    pub recorded: Option<RecordedEntry>,
+}
+
+impl ReconciledEntry {
+    // 記録 - 計画
+    pub fn variance(&self) -> Decimal {
+        let planned = self.planned.as_ref().map_or(Decimal::ZERO, |planned| planned.amount);
+        let recorded = self.recorded.as_ref().map_or(Decimal::ZERO, |recorded| recorded.amount);
+        recorded - planned
+    }
+}
+
+// 残高合わせの調整金は計画外に数えない
+pub fn exclude_adjustments(recorded: Vec<RecordedEntry>, adjustment_ids: &[u64]) -> Vec<RecordedEntry> {
+    recorded.into_iter().filter(|entry| !adjustment_ids.contains(&entry.id)).collect()
+}
+
+// 同じ名前の記録のうち、日付が最も近いものを計画に対応させる
+// 記録は 1 件の計画にだけ対応する
+pub fn reconcile(mut planned: Vec<PlannedEntry>, recorded: Vec<RecordedEntry>) -> Vec<ReconciledEntry> {
+    planned.sort_by_key(|entry| entry.date);
+    let mut unmatched: Vec<Option<RecordedEntry>> = recorded.into_iter().map(Some).collect();
+    let mut reconciled: Vec<ReconciledEntry> = planned
+        .into_iter()
+        .map(|planned| {
+            let nearest = unmatched
+                .iter()
+                .enumerate()
+                .filter_map(|(i, recorded)| {
+                    let recorded = recorded.as_ref()?;
+                    (recorded.name.trim() == planned.name.trim())
+                        .then(|| (i, (recorded.date - planned.date).num_seconds().abs()))
+                })
+                .min_by_key(|(_, distance)| *distance)
+                .map(|(i, _)| i);
+            ReconciledEntry {
+                recorded: nearest.and_then(|i| unmatched[i].take()),
+                planned: Some(planned),
+            }
+        })
+        .collect();
+    let mut unplanned: Vec<RecordedEntry> = unmatched.into_iter().flatten().collect();
+    unplanned.sort_by_key(|entry| entry.date);
+    reconciled.extend(unplanned.into_iter().map(|recorded| ReconciledEntry { planned: None, recorded: Some(recorded) }));
+    reconciled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::plan::source::SourceKind;
+    use chrono::TimeZone;
+
+    fn date(day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 4, day, 0, 0, 0).unwrap()
+    }
+
+    fn planned(name: &str, amount: i64, day: u32) -> PlannedEntry {
+        PlannedEntry {
+            name: name.to_string(),
+            amount: Decimal::from(amount),
+            date: date(day),
+            source: EntrySource::new(SourceKind::MonthlyOutcome, Some(1)),
+            account_id: None,
+        }
+    }
+
+    fn recorded(id: u64, name: &str, amount: i64, day: u32) -> RecordedEntry {
+        RecordedEntry { id, name: name.to_string(), amount: Decimal::from(amount), date: date(day) }
+    }
+
+    #[test]
+    fn matches_entries_with_the_same_name_and_nearest_date() {
+        let reconciled = reconcile(
+            vec![planned("ジム", 1000, 21), planned("家賃", 80000, 27), planned("ジム", 1000, 7)],
+            vec![recorded(1, "ジム", 1000, 8), recorded(2, " 家賃", 82000, 27), recorded(3, "ジム", 1200, 20)],
+        );
+        let pairs: Vec<(Option<DateTime<Local>>, Option<u64>)> = reconciled
+            .iter()
+            .map(|entry| (entry.planned.as_ref().map(|p| p.date), entry.recorded.as_ref().map(|r| r.id)))
+            .collect();
+        assert_eq!(pairs, vec![(Some(date(7)), Some(1)), (Some(date(21)), Some(3)), (Some(date(27)), Some(2))]);
+        assert_eq!(reconciled[0].variance(), Decimal::ZERO);
+        assert_eq!(reconciled[1].variance(), Decimal::from(200));
+        assert_eq!(reconciled[2].variance(), Decimal::from(2000));
+    }
+
+    #[test]
+    fn keeps_unrecorded_and_unplanned_entries() {
+        let reconciled = reconcile(
+            vec![planned("家賃", 80000, 27)],
+            vec![recorded(2, "外食", 3000, 12), recorded(1, "コンビニ", 500, 3)],
+        );
+        assert_eq!(reconciled.len(), 3);
+        assert_eq!(reconciled[0].recorded, None);
+        assert_eq!(reconciled[0].variance(), Decimal::from(-80000));
+        assert_eq!(reconciled[1].planned, None);
+        assert_eq!(reconciled[1].recorded.as_ref().map(|r| r.id), Some(1));
+        assert_eq!(reconciled[2].variance(), Decimal::from(3000));
+    }
+
+    #[test]
+    fn does_not_count_adjustments_as_unplanned() {
+        let recorded_entries = vec![recorded(1, "家賃", 80000, 27), recorded(2, "調整金", 1500, 30)];
+        let reconciled = reconcile(vec![planned("家賃", 80000, 27)], exclude_adjustments(recorded_entries, &[2]));
+        assert_eq!(reconciled.len(), 1);
+        assert_eq!(reconciled[0].recorded.as_ref().map(|r| r.id), Some(1));
+        assert!(reconciled.iter().all(|entry| entry.planned.is_some()));
+    }
+}
@@ -0,0 +1,463 @@
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveTime, TimeZone, Weekday};
+use crate::finance::error::FinanceError;
+
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+pub fn weekday_label(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "月",
+        Weekday::Tue => "火",
+        Weekday::Wed => "水",
+        Weekday::Thu => "木",
+        Weekday::Fri => "金",
+        Weekday::Sat => "土",
+        Weekday::Sun => "日",
+    }
+}
+
+// 月の中のどの日か
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MonthDay {
+    // 何日か
+    Day(u32),
+    // 末日
+    Last,
+    // 第何何曜日
+    NthWeekday(u32, Weekday),
+    // 最終何曜日
+    LastWeekday(Weekday),
+}
+
+// その月にない日 (2月30日や第5月曜日など) の扱い
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissingDayPolicy {
+    // 末日 (第何曜日なら最終何曜日) にする
+    Clamp,
+    // その月は発生しない
+    Skip,
+}
+
+impl MissingDayPolicy {
+    pub const ALL: [MissingDayPolicy; 2] = [MissingDayPolicy::Clamp, MissingDayPolicy::Skip];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MissingDayPolicy::Clamp => "月末にずらす",
+            MissingDayPolicy::Skip => "その月は飛ばす",
+        }
+    }
+}
+
+// 繰り返しの規則 (iCalendar の RRULE に近いもの)
+// 間隔は起点の日 (テンプレートの開始日など) から数える
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecurrenceRule {
+    // interval 週ごとの weekday
+    Weekly { interval: u32, weekday: Weekday },
+    // interval か月ごとの day
+    Monthly { interval: u32, day: MonthDay },
+    // 毎年 month 月の day
+    Yearly { month: u32, day: MonthDay },
+}
+
+impl MonthDay {
+    // year 年 month 月のこの日
+    // その月にない日は missing_day に従って末日にするか None にする
+    pub fn resolve(&self, year: i32, month: u32, missing_day: MissingDayPolicy) -> Option<NaiveDate> {
+        let clamp = missing_day == MissingDayPolicy::Clamp;
+        match *self {
+            MonthDay::Day(day) => NaiveDate::from_ymd_opt(year, month, day).or_else(|| {
+                let last = get_last_day(year, month)?;
+                (clamp && day > last.day()).then_some(last)
+            }),
+            MonthDay::Last => get_last_day(year, month),
+            MonthDay::NthWeekday(n, weekday) => {
+                let n = u8::try_from(n).ok()?;
+                NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).or_else(|| {
+                    clamp.then(|| MonthDay::LastWeekday(weekday).resolve(year, month, missing_day)).flatten()
+                })
+            }
+            MonthDay::LastWeekday(weekday) => {
+                let last = get_last_day(year, month)?;
+                let diff = (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+                last.checked_sub_days(Days::new(diff as u64))
+            }
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            MonthDay::Day(day) => format!("{}日", day),
+            MonthDay::Last => "末日".to_string(),
+            MonthDay::NthWeekday(n, weekday) => format!("第{}{}曜日", n, weekday_label(*weekday)),
+            MonthDay::LastWeekday(weekday) => format!("最終{}曜日", weekday_label(*weekday)),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match self {
+            MonthDay::Day(day) => (1..=31).contains(day),
+            MonthDay::Last | MonthDay::LastWeekday(_) => true,
+            MonthDay::NthWeekday(n, _) => (1..=5).contains(n),
+        }
+    }
+}
+
+impl RecurrenceRule {
+    // 毎月 day に発生する
+    pub fn monthly(day: MonthDay) -> Self {
+        RecurrenceRule::Monthly { interval: 1, day }
+    }
+
+    // 月によっては存在しない日を指定しているか
+    pub fn can_miss_day(&self) -> bool {
+        match self {
+            RecurrenceRule::Weekly { .. } => false,
+            RecurrenceRule::Monthly { day, .. } | RecurrenceRule::Yearly { day, .. } => {
+                matches!(day, MonthDay::Day(29..) | MonthDay::NthWeekday(5.., _))
+            }
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            RecurrenceRule::Weekly { interval: 1, weekday } => format!("毎週{}曜日", weekday_label(*weekday)),
+            RecurrenceRule::Weekly { interval: 2, weekday } => format!("隔週{}曜日", weekday_label(*weekday)),
+            RecurrenceRule::Weekly { interval, weekday } => format!("{}週ごとの{}曜日", interval, weekday_label(*weekday)),
+            RecurrenceRule::Monthly { interval: 1, day } => format!("毎月{}", day.label()),
+            RecurrenceRule::Monthly { interval, day } => format!("{}か月ごとの{}", interval, day.label()),
+            RecurrenceRule::Yearly { month, day } => format!("毎年{}月{}", month, day.label()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), FinanceError> {
+        let is_valid = match self {
+            RecurrenceRule::Weekly { interval, .. } => *interval >= 1,
+            RecurrenceRule::Monthly { interval, day } => *interval >= 1 && day.is_valid(),
+            RecurrenceRule::Yearly { month, day } => (1..=12).contains(month) && day.is_valid(),
+        };
+        if is_valid {
+            Ok(())
+        } else {
+            Err(FinanceError::InvalidRecurrence(self.label()))
+        }
+    }
+
+    // anchor を起点として from から to まで (両端を含む) に発生する日
+    // anchor より前には発生しない
+    pub fn occurrences(
+        &self,
+        anchor: NaiveDate,
+        from: NaiveDate,
+        to: NaiveDate,
+        missing_day: MissingDayPolicy,
+    ) -> Vec<NaiveDate> {
+        let from = from.max(anchor);
+        if from > to {
+            return vec![];
+        }
+        match *self {
+            RecurrenceRule::Weekly { interval, weekday } => {
+                let step = 7 * interval.max(1) as u64;
+                // anchor 以降で最初の weekday から step 日ごと
+                let offset = (weekday.num_days_from_monday() + 7 - anchor.weekday().num_days_from_monday()) % 7;
+                let first = anchor + Days::new(offset as u64);
+                let skipped = if from > first {
+                    ((from - first).num_days() as u64).div_ceil(step)
+                } else {
+                    0
+                };
+                let mut dates = Vec::new();
+                let mut date = first + Days::new(skipped * step);
+                while date <= to {
+                    dates.push(date);
+                    date = date + Days::new(step);
+                }
+                dates
+            }
+            RecurrenceRule::Monthly { interval, day } => {
+                let anchor_index = month_index(anchor);
+                (month_index(from)..=month_index(to))
+                    .filter(|index| (index - anchor_index) % interval.max(1) as i32 == 0)
+                    .filter_map(|index| day.resolve(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, missing_day))
+                    .filter(|date| from <= *date && *date <= to)
+                    .collect()
+            }
+            RecurrenceRule::Yearly { month, day } => (from.year()..=to.year())
+                .filter_map(|year| day.resolve(year, month, missing_day))
+                .filter(|date| from <= *date && *date <= to)
+                .collect(),
+        }
+    }
+
+    // opening_date から closing_date までの 1 か月分に発生する日の 0 時
+    // 起点は start_date で、start_date から end_date までに限る
+    pub fn get_dates_in_period(
+        &self,
+        opening_date: &DateTime<Local>,
+        closing_date: &DateTime<Local>,
+        start_date: &DateTime<Local>,
+        end_date: Option<&DateTime<Local>>,
+        missing_day: MissingDayPolicy,
+    ) -> Result<Vec<DateTime<Local>>, anyhow::Error> {
+        let last = closing_date.date_naive();
+        let last = end_date.map_or(last, |end_date| end_date.date_naive().min(last));
+        self.occurrences(start_date.date_naive(), opening_date.date_naive(), last, missing_day)
+            .into_iter()
+            .map(to_local_datetime)
+            .collect()
+    }
+}
+
+// その日の 0 時
+pub fn to_local_datetime(date: NaiveDate) -> Result<DateTime<Local>, anyhow::Error> {
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid date: {}", date))
+}
+
+fn get_last_day(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+}
+
+// 0 年 1 月からの月数
+fn month_index(date: NaiveDate) -> i32 {
+    date.year() * 12 + date.month0() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MissingDayPolicy::{Clamp, Skip};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn resolves_month_days() {
+        assert_eq!(MonthDay::Day(15).resolve(2025, 4, Skip), Some(date(2025, 4, 15)));
+        assert_eq!(MonthDay::Day(31).resolve(2025, 4, Skip), None);
+        assert_eq!(MonthDay::Day(29).resolve(2024, 2, Skip), Some(date(2024, 2, 29)));
+        assert_eq!(MonthDay::Day(29).resolve(2025, 2, Skip), None);
+        assert_eq!(MonthDay::Last.resolve(2025, 2, Skip), Some(date(2025, 2, 28)));
+        assert_eq!(MonthDay::Last.resolve(2024, 2, Skip), Some(date(2024, 2, 29)));
+        assert_eq!(MonthDay::Last.resolve(2025, 12, Skip), Some(date(2025, 12, 31)));
+        // 2025年5月の金曜日は 2, 9, 16, 23, 30 日
+        assert_eq!(MonthDay::NthWeekday(1, Weekday::Fri).resolve(2025, 5, Skip), Some(date(2025, 5, 2)));
+        assert_eq!(MonthDay::NthWeekday(2, Weekday::Fri).resolve(2025, 5, Skip), Some(date(2025, 5, 9)));
+        assert_eq!(MonthDay::NthWeekday(5, Weekday::Fri).resolve(2025, 5, Skip), Some(date(2025, 5, 30)));
+        assert_eq!(MonthDay::NthWeekday(5, Weekday::Mon).resolve(2025, 5, Skip), None);
+        assert_eq!(MonthDay::LastWeekday(Weekday::Fri).resolve(2025, 5, Skip), Some(date(2025, 5, 30)));
+        // 末日がその曜日の場合
+        assert_eq!(MonthDay::LastWeekday(Weekday::Sat).resolve(2025, 5, Skip), Some(date(2025, 5, 31)));
+        assert_eq!(MonthDay::LastWeekday(Weekday::Sun).resolve(2025, 5, Skip), Some(date(2025, 5, 25)));
+    }
+
+    #[test]
+    fn expands_weekly() {
+        let rule = RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Fri };
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 5, 1), date(2025, 5, 31), Skip),
+            vec![date(2025, 5, 2), date(2025, 5, 9), date(2025, 5, 16), date(2025, 5, 23), date(2025, 5, 30)],
+        );
+        // 起点が金曜日ならその日から
+        assert_eq!(
+            rule.occurrences(date(2025, 5, 9), date(2025, 5, 1), date(2025, 5, 20), Skip),
+            vec![date(2025, 5, 9), date(2025, 5, 16)],
+        );
+    }
+
+    #[test]
+    fn expands_biweekly_from_anchor() {
+        let rule = RecurrenceRule::Weekly { interval: 2, weekday: Weekday::Fri };
+        // 2025-01-03 は金曜日
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 3), date(2025, 1, 1), date(2025, 2, 28), Skip),
+            vec![date(2025, 1, 3), date(2025, 1, 17), date(2025, 1, 31), date(2025, 2, 14), date(2025, 2, 28)],
+        );
+        // 範囲の途中から数えても起点からの間隔を保つ
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 3), date(2025, 5, 1), date(2025, 5, 31), Skip),
+            vec![date(2025, 5, 9), date(2025, 5, 23)],
+        );
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 3), date(2025, 5, 9), date(2025, 5, 9), Skip),
+            vec![date(2025, 5, 9)],
+        );
+    }
+
+    #[test]
+    fn expands_monthly() {
+        let rule = RecurrenceRule::monthly(MonthDay::Day(25));
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2025, 4, 30), Skip),
+            vec![date(2025, 1, 25), date(2025, 2, 25), date(2025, 3, 25), date(2025, 4, 25)],
+        );
+        // 起点の月でも起点より前の日には発生しない
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 26), date(2025, 1, 1), date(2025, 2, 28), Skip),
+            vec![date(2025, 2, 25)],
+        );
+    }
+
+    #[test]
+    fn monthly_skips_missing_days() {
+        let rule = RecurrenceRule::monthly(MonthDay::Day(31));
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2025, 5, 31), Skip),
+            vec![date(2025, 1, 31), date(2025, 3, 31), date(2025, 5, 31)],
+        );
+        let rule = RecurrenceRule::monthly(MonthDay::Last);
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2025, 4, 30), Skip),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 31), date(2025, 4, 30)],
+        );
+    }
+
+    #[test]
+    fn clamps_missing_days_to_month_end() {
+        assert_eq!(MonthDay::Day(31).resolve(2025, 4, Clamp), Some(date(2025, 4, 30)));
+        assert_eq!(MonthDay::Day(30).resolve(2025, 2, Clamp), Some(date(2025, 2, 28)));
+        assert_eq!(MonthDay::Day(30).resolve(2024, 2, Clamp), Some(date(2024, 2, 29)));
+        assert_eq!(MonthDay::Day(15).resolve(2025, 2, Clamp), Some(date(2025, 2, 15)));
+        // 第5月曜日がない月は最終月曜日
+        assert_eq!(MonthDay::NthWeekday(5, Weekday::Mon).resolve(2025, 5, Clamp), Some(date(2025, 5, 26)));
+        let rule = RecurrenceRule::monthly(MonthDay::Day(31));
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2025, 4, 30), Clamp),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 31), date(2025, 4, 30)],
+        );
+        let rule = RecurrenceRule::Yearly { month: 2, day: MonthDay::Day(29) };
+        assert_eq!(
+            rule.occurrences(date(2024, 1, 1), date(2024, 1, 1), date(2025, 12, 31), Clamp),
+            vec![date(2024, 2, 29), date(2025, 2, 28)],
+        );
+    }
+
+    #[test]
+    fn detects_days_missing_in_some_months() {
+        assert!(RecurrenceRule::monthly(MonthDay::Day(31)).can_miss_day());
+        assert!(RecurrenceRule::Yearly { month: 2, day: MonthDay::Day(29) }.can_miss_day());
+        assert!(RecurrenceRule::monthly(MonthDay::NthWeekday(5, Weekday::Fri)).can_miss_day());
+        assert!(!RecurrenceRule::monthly(MonthDay::Day(28)).can_miss_day());
+        assert!(!RecurrenceRule::monthly(MonthDay::Last).can_miss_day());
+        assert!(!RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Fri }.can_miss_day());
+    }
+
+    #[test]
+    fn expands_every_n_months() {
+        let rule = RecurrenceRule::Monthly { interval: 3, day: MonthDay::Day(10) };
+        assert_eq!(
+            rule.occurrences(date(2024, 11, 1), date(2025, 1, 1), date(2025, 12, 31), Skip),
+            vec![date(2025, 2, 10), date(2025, 5, 10), date(2025, 8, 10), date(2025, 11, 10)],
+        );
+        // 年をまたいでも間隔を保つ
+        assert_eq!(
+            rule.occurrences(date(2024, 11, 1), date(2026, 1, 1), date(2026, 3, 31), Skip),
+            vec![date(2026, 2, 10)],
+        );
+    }
+
+    #[test]
+    fn expands_nth_weekday() {
+        let rule = RecurrenceRule::monthly(MonthDay::NthWeekday(2, Weekday::Wed));
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2025, 3, 31), Skip),
+            vec![date(2025, 1, 8), date(2025, 2, 12), date(2025, 3, 12)],
+        );
+        let rule = RecurrenceRule::monthly(MonthDay::LastWeekday(Weekday::Fri));
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2025, 3, 31), Skip),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 28)],
+        );
+    }
+
+    #[test]
+    fn expands_yearly() {
+        let rule = RecurrenceRule::Yearly { month: 6, day: MonthDay::Day(30) };
+        assert_eq!(
+            rule.occurrences(date(2025, 1, 1), date(2025, 1, 1), date(2027, 12, 31), Skip),
+            vec![date(2025, 6, 30), date(2026, 6, 30), date(2027, 6, 30)],
+        );
+        // 起点がその年の発生日より後なら翌年から
+        assert_eq!(
+            rule.occurrences(date(2025, 7, 1), date(2025, 1, 1), date(2026, 12, 31), Skip),
+            vec![date(2026, 6, 30)],
+        );
+        // うるう年にしかない日
+        let rule = RecurrenceRule::Yearly { month: 2, day: MonthDay::Day(29) };
+        assert_eq!(
+            rule.occurrences(date(2023, 1, 1), date(2023, 1, 1), date(2028, 12, 31), Skip),
+            vec![date(2024, 2, 29), date(2028, 2, 29)],
+        );
+    }
+
+    #[test]
+    fn empty_when_range_is_before_anchor() {
+        let rule = RecurrenceRule::monthly(MonthDay::Day(1));
+        assert!(rule.occurrences(date(2025, 6, 1), date(2025, 1, 1), date(2025, 5, 31), Skip).is_empty());
+        assert!(rule.occurrences(date(2025, 1, 1), date(2025, 5, 31), date(2025, 5, 1), Skip).is_empty());
+    }
+
+    #[test]
+    fn dates_in_period_respect_template_period() {
+        let rule = RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Fri };
+        let start_date = to_local_datetime(date(2025, 5, 10)).unwrap();
+        let end_date = to_local_datetime(date(2025, 5, 25)).unwrap();
+        let period = |first, last| (to_local_datetime(first).unwrap(), to_local_datetime(last).unwrap());
+        let (opening_date, closing_date) = period(date(2025, 5, 1), date(2025, 5, 31));
+        let dates = rule
+            .get_dates_in_period(&opening_date, &closing_date, &start_date, Some(&end_date), Skip)
+            .unwrap();
+        assert_eq!(
+            dates.iter().map(|date| date.date_naive()).collect::<Vec<_>>(),
+            vec![date(2025, 5, 16), date(2025, 5, 23)],
+        );
+        let (opening_date, closing_date) = period(date(2025, 6, 1), date(2025, 6, 30));
+        assert!(rule
+            .get_dates_in_period(&opening_date, &closing_date, &start_date, Some(&end_date), Skip)
+            .unwrap()
+            .is_empty());
+        // 給料日始まりの月
+        let rule = RecurrenceRule::monthly(MonthDay::Day(25));
+        let (opening_date, closing_date) = period(date(2025, 4, 25), date(2025, 5, 24));
+        let dates = rule.get_dates_in_period(&opening_date, &closing_date, &start_date, None, Skip).unwrap();
+        assert!(dates.is_empty());
+        let (opening_date, closing_date) = period(date(2025, 5, 25), date(2025, 6, 24));
+        let dates = rule.get_dates_in_period(&opening_date, &closing_date, &start_date, None, Skip).unwrap();
+        assert_eq!(dates.iter().map(|date| date.date_naive()).collect::<Vec<_>>(), vec![date(2025, 5, 25)]);
+    }
+
+    #[test]
+    fn validates_rules() {
+        assert!(RecurrenceRule::monthly(MonthDay::Day(31)).validate().is_ok());
+        assert!(RecurrenceRule::monthly(MonthDay::Day(0)).validate().is_err());
+        assert!(RecurrenceRule::monthly(MonthDay::Day(32)).validate().is_err());
+        assert!(RecurrenceRule::monthly(MonthDay::NthWeekday(6, Weekday::Mon)).validate().is_err());
+        assert!(RecurrenceRule::Monthly { interval: 0, day: MonthDay::Last }.validate().is_err());
+        assert!(RecurrenceRule::Weekly { interval: 0, weekday: Weekday::Mon }.validate().is_err());
+        assert!(RecurrenceRule::Yearly { month: 13, day: MonthDay::Day(1) }.validate().is_err());
+    }
+
+    #[test]
+    fn labels_rules() {
+        assert_eq!(RecurrenceRule::monthly(MonthDay::Day(25)).label(), "毎月25日");
+        assert_eq!(RecurrenceRule::monthly(MonthDay::Last).label(), "毎月末日");
+        assert_eq!(RecurrenceRule::monthly(MonthDay::NthWeekday(2, Weekday::Fri)).label(), "毎月第2金曜日");
+        assert_eq!(RecurrenceRule::Monthly { interval: 3, day: MonthDay::Day(10) }.label(), "3か月ごとの10日");
+        assert_eq!(RecurrenceRule::Weekly { interval: 2, weekday: Weekday::Fri }.label(), "隔週金曜日");
+        assert_eq!(RecurrenceRule::Yearly { month: 6, day: MonthDay::Day(30) }.label(), "毎年6月30日");
+    }
+}
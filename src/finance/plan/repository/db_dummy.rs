@@ -1,12 +1,17 @@
 use super::income::{Income, IncomeRepo, ToIncome};
 use super::income::job;
 use super::income::job::{PartTimeHourlyWage, PartTimeJob, PartTimeJobIncome, PartTimeJobRepo};
-use super::outcome::monthly_outcome;
+use super::income::monthly_income::{MonthlyIncome, MonthlyIncomeRepo, MonthlyIncomeTemplate};
 use super::outcome::monthly_outcome::{MonthlyOutcome, MonthlyOutcomeRepo, MonthlyOutcomeTemplate};
 use super::outcome::temporary_outcome::{TemporaryOutcome, TemporaryOutcomeRepo};
+use super::recurrence::{MissingDayPolicy, MonthDay, RecurrenceRule};
+use super::scenario::{Scenario, ScenarioChange, ScenarioRepo};
+use crate::finance::detail::calendar::BusinessDayRoll;
 use super::outcome::{Outcome, OutcomeRepo, ToOutcome};
+use super::monthly_template::MonthlyTemplateRepo;
 use chrono::prelude::*;
 use rust_decimal_macros::dec;
+use std::marker::PhantomData;
 use std::vec;
 use std::{cell::RefCell, collections::HashMap};
 
@@ -15,16 +20,28 @@ thread_local! {
         (1, PartTimeJob {
             id: Some(1),
             name: "アルバイト1".to_string(),
-            payment_timing: job::PaymentTiming::NextMonthMid(21),
+            payment_timing: job::PaymentTiming {
+                month_offset: 1,
+                rule: RecurrenceRule::monthly(MonthDay::Day(21)),
+                missing_day: MissingDayPolicy::Clamp,
+            },
+            business_day_roll: BusinessDayRoll::Previous,
             start_date: Local.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single().unwrap(),
             end_date: Some(Local.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).single().unwrap()),
+            account_id: None,
         }),
         (2, PartTimeJob {
             id: Some(2),
             name: "アルバイト2".to_string(),
-            payment_timing: job::PaymentTiming::End,
+            payment_timing: job::PaymentTiming {
+                month_offset: 0,
+                rule: RecurrenceRule::monthly(MonthDay::Last),
+                missing_day: MissingDayPolicy::Clamp,
+            },
+            business_day_roll: BusinessDayRoll::None,
             start_date: Local.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).single().unwrap(),
             end_date: None,
+            account_id: None,
         })
     ]));
     static PART_TIME_JOB_HOURLY_WAGE_COLLECTION: RefCell<Vec<PartTimeHourlyWage>> = RefCell::new(vec![
@@ -54,22 +71,57 @@ thread_local! {
             payment_date: Local.with_ymd_and_hms(2025, 4, 21, 0, 0, 0).single().unwrap(),
         })
     ]));
+    static MONTHLY_INCOME_TEMPLATE_COLLECTION: RefCell<HashMap<u64, MonthlyIncomeTemplate>> = RefCell::new(HashMap::from_iter(vec![
+        (1, MonthlyIncomeTemplate {
+            id: Some(1),
+            name: "仕送り".to_string(),
+            amount: dec!(50000),
+            payment_timing: RecurrenceRule::monthly(MonthDay::Day(25)),
+            missing_day: MissingDayPolicy::Clamp,
+            business_day_roll: BusinessDayRoll::Previous,
+            start_date: Local.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single().unwrap(),
+            end_date: None,
+            account_id: None,
+            entry: PhantomData,
+        }),
+    ]));
+    static MONTHLY_INCOME_COLLECTION: RefCell<HashMap<u64, MonthlyIncome>> = RefCell::new(HashMap::from_iter(vec![]));
     static MONTHLY_OUTCOME_TEMPLATE_COLLECTION: RefCell<HashMap<u64, MonthlyOutcomeTemplate>> = RefCell::new(HashMap::from_iter(vec![
         (1, MonthlyOutcomeTemplate {
             id: Some(1),
             name: "支出1".to_string(),
             amount: dec!(10000),
-            payment_timing: monthly_outcome::PaymentTiming::End,
+            payment_timing: RecurrenceRule::monthly(MonthDay::Last),
+            missing_day: MissingDayPolicy::Clamp,
+            business_day_roll: BusinessDayRoll::None,
             start_date: Local.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single().unwrap(),
             end_date: Some(Local.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).single().unwrap()),
+            account_id: None,
+            entry: PhantomData,
         }),
         (2, MonthlyOutcomeTemplate {
             id: Some(2),
             name: "支出2".to_string(),
             amount: dec!(5000),
-            payment_timing: monthly_outcome::PaymentTiming::Mid(15),
+            payment_timing: RecurrenceRule::monthly(MonthDay::Day(15)),
+            missing_day: MissingDayPolicy::Clamp,
+            business_day_roll: BusinessDayRoll::None,
             start_date: Local.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).single().unwrap(),
             end_date: None,
+            account_id: None,
+            entry: PhantomData,
+        }),
+        (3, MonthlyOutcomeTemplate {
+            id: Some(3),
+            name: "自動車保険".to_string(),
+            amount: dec!(30000),
+            payment_timing: RecurrenceRule::Yearly { month: 6, day: MonthDay::Day(27) },
+            missing_day: MissingDayPolicy::Clamp,
+            business_day_roll: BusinessDayRoll::None,
+            start_date: Local.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single().unwrap(),
+            end_date: None,
+            account_id: None,
+            entry: PhantomData,
         }),
     ]));
     static MONTHLY_OUTCOME_COLLECTION: RefCell<HashMap<u64, MonthlyOutcome>> = RefCell::new(HashMap::from_iter(vec![]));
@@ -87,6 +139,8 @@ thread_local! {
             date: Local.with_ymd_and_hms(2025, 5, 31, 0 , 0, 0).single().unwrap(),
         }),
     ]));
+    static SCENARIO_COLLECTION: RefCell<HashMap<u64, Scenario>> = RefCell::new(HashMap::new());
+    static NEXT_SCENARIO_CHANGE_ID: RefCell<u64> = const { RefCell::new(1) };
 }
 
 pub struct DummyPartTimeJobRepo;
@@ -122,6 +176,10 @@ impl PartTimeJobRepo for DummyPartTimeJobRepo {
     fn store_part_time_job(&self, part_time_job: PartTimeJob) -> Result<u64, anyhow::Error> {
         let id = PART_TIME_JOB_COLLECTION.with(|collection| {
             let id = collection.borrow().len() as u64 + 1;
+            let part_time_job = PartTimeJob {
+                id: Some(id),
+                ..part_time_job
+            };
             collection.borrow_mut().insert(id, part_time_job);
             id
         });
@@ -212,22 +270,25 @@ impl PartTimeJobRepo for DummyPartTimeJobRepo {
                 .with(|collection| collection.borrow().get(&id).cloned()),
         )
     }
-    fn get_part_time_job_income_by_part_time_job_id(
+    fn list_part_time_job_incomes_by_part_time_job_id(
         &self,
         part_time_job_id: u64,
         year: i32,
         month: u32,
-    ) -> Result<Option<PartTimeJobIncome>, anyhow::Error> {
+    ) -> Result<Vec<PartTimeJobIncome>, anyhow::Error> {
         Ok(PART_TIME_JOB_INCOME_COLLECTION.with(|collection| {
-            return collection
+            let mut incomes: Vec<PartTimeJobIncome> = collection
                 .borrow()
                 .values()
-                .find(|income| {
+                .filter(|income| {
                     income.part_time_job_id == part_time_job_id
                         && income.payment_date.year() == year
                         && income.payment_date.month() == month
                 })
-                .cloned();
+                .cloned()
+                .collect();
+            incomes.sort_by_key(|income| (income.payment_date, income.id));
+            incomes
         }))
     }
     fn store_part_time_job_income(
@@ -284,10 +345,105 @@ impl DummyPartTimeJobRepo {
     }
 }
 
+pub struct DummyMonthlyIncomeRepo;
+
+impl MonthlyTemplateRepo<Income> for DummyMonthlyIncomeRepo {
+    fn list_monthly_templates(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyIncomeTemplate>, anyhow::Error> {
+        let mut templates: Vec<MonthlyIncomeTemplate> = MONTHLY_INCOME_TEMPLATE_COLLECTION.with(|collection| {
+            collection
+                .borrow()
+                .values()
+                .filter(|template| {
+                    template.start_date <= *end_date
+                        && template.end_date.is_none_or(|end_date| end_date >= *start_date)
+                })
+                .cloned()
+                .collect()
+        });
+        templates.sort_by_key(|template| template.id);
+        Ok(templates)
+    }
+    fn get_monthly_template_by_id(&self, id: u64) -> Result<Option<MonthlyIncomeTemplate>, anyhow::Error> {
+        Ok(MONTHLY_INCOME_TEMPLATE_COLLECTION.with(|collection| collection.borrow().get(&id).cloned()))
+    }
+    fn store_monthly_template(&self, template: MonthlyIncomeTemplate) -> Result<u64, anyhow::Error> {
+        let id = MONTHLY_INCOME_TEMPLATE_COLLECTION.with(|collection| {
+            let id = collection.borrow().len() as u64 + 1;
+            let template = MonthlyIncomeTemplate {
+                id: Some(id),
+                ..template
+            };
+            collection.borrow_mut().insert(id, template);
+            id
+        });
+        Ok(id)
+    }
+    fn store_monthly_entry(&self, monthly_income: MonthlyIncome) -> Result<u64, anyhow::Error> {
+        let id = MONTHLY_INCOME_COLLECTION.with(|collection| {
+            let id = collection.borrow().len() as u64 + 1;
+            let monthly_income = MonthlyIncome {
+                id: Some(id),
+                ..monthly_income
+            };
+            collection.borrow_mut().insert(id, monthly_income);
+            id
+        });
+        Ok(id)
+    }
+    fn list_monthly_entries_by_template_id(
+        &self,
+        template_id: u64,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyIncome>, anyhow::Error> {
+        Ok(MONTHLY_INCOME_COLLECTION.with(|collection| {
+            collection
+                .borrow()
+                .values()
+                .filter(|income| {
+                    income.template_id == template_id
+                        && income.payment_date >= *start_date
+                        && income.payment_date <= *end_date
+                })
+                .cloned()
+                .collect()
+        }))
+    }
+}
+
+impl MonthlyIncomeRepo for DummyMonthlyIncomeRepo {}
+
+impl IncomeRepo for DummyMonthlyIncomeRepo {
+    fn list_incomes(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<Income>, anyhow::Error> {
+        Ok(MONTHLY_INCOME_COLLECTION.with(|collection| {
+            collection
+                .borrow()
+                .values()
+                .filter(|income| income.payment_date >= *start_date && income.payment_date <= *end_date)
+                .map(|income| income.to_income())
+                .collect()
+        }))
+    }
+}
+
+impl DummyMonthlyIncomeRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
 pub struct DummyMonthlyOutcomeRepo;
 
-impl MonthlyOutcomeRepo for DummyMonthlyOutcomeRepo {
-    fn list_monthly_outcome_template(
+impl MonthlyTemplateRepo<Outcome> for DummyMonthlyOutcomeRepo {
+    fn list_monthly_templates(
         &self,
         start_date: &DateTime<Local>,
         end_date: &DateTime<Local>,
@@ -307,7 +463,22 @@ impl MonthlyOutcomeRepo for DummyMonthlyOutcomeRepo {
                 .collect()
         }))
     }
-    fn store_monthly_outcome(&self, monthly_outcome: MonthlyOutcome) -> Result<u64, anyhow::Error> {
+    fn get_monthly_template_by_id(&self, id: u64) -> Result<Option<MonthlyOutcomeTemplate>, anyhow::Error> {
+        Ok(MONTHLY_OUTCOME_TEMPLATE_COLLECTION.with(|collection| collection.borrow().get(&id).cloned()))
+    }
+    fn store_monthly_template(&self, template: MonthlyOutcomeTemplate) -> Result<u64, anyhow::Error> {
+        let id = MONTHLY_OUTCOME_TEMPLATE_COLLECTION.with(|collection| {
+            let id = collection.borrow().len() as u64 + 1;
+            let template = MonthlyOutcomeTemplate {
+                id: Some(id),
+                ..template
+            };
+            collection.borrow_mut().insert(id, template);
+            id
+        });
+        Ok(id)
+    }
+    fn store_monthly_entry(&self, monthly_outcome: MonthlyOutcome) -> Result<u64, anyhow::Error> {
         let id = MONTHLY_OUTCOME_COLLECTION.with(|collection| {
             let id = collection.borrow().len() as u64 + 1;
             let monthly_outcome = MonthlyOutcome {
@@ -319,34 +490,38 @@ impl MonthlyOutcomeRepo for DummyMonthlyOutcomeRepo {
         });
         Ok(id)
     }
-    fn update_monthly_outcome(&self, monthly_outcome: MonthlyOutcome) -> Result<(), anyhow::Error> {
-        MONTHLY_OUTCOME_COLLECTION.with(|collection| {
-            collection
-                .borrow_mut()
-                .insert(monthly_outcome.id.unwrap(), monthly_outcome);
-        });
-        Ok(())
-    }
-    fn get_monthly_outcome_by_template_id(
+    fn list_monthly_entries_by_template_id(
         &self,
-        monthly_outcome_template_id: u64,
-        year: i32,
-        month: u32,
-    ) -> Result<Option<MonthlyOutcome>, anyhow::Error> {
+        template_id: u64,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyOutcome>, anyhow::Error> {
         Ok(MONTHLY_OUTCOME_COLLECTION.with(|collection| {
             collection
                 .borrow()
                 .values()
-                .find(|outcome| {
-                    outcome.monthly_outcome_template_id == monthly_outcome_template_id
-                        && outcome.payment_date.year() == year
-                        && outcome.payment_date.month() == month
+                .filter(|outcome| {
+                    outcome.template_id == template_id
+                        && outcome.payment_date >= *start_date
+                        && outcome.payment_date <= *end_date
                 })
                 .cloned()
+                .collect()
         }))
     }
 }
 
+impl MonthlyOutcomeRepo for DummyMonthlyOutcomeRepo {
+    fn update_monthly_outcome(&self, monthly_outcome: MonthlyOutcome) -> Result<(), anyhow::Error> {
+        MONTHLY_OUTCOME_COLLECTION.with(|collection| {
+            collection
+                .borrow_mut()
+                .insert(monthly_outcome.id.unwrap(), monthly_outcome);
+        });
+        Ok(())
+    }
+}
+
 impl OutcomeRepo for DummyMonthlyOutcomeRepo {
     fn list_outcomes(
         &self,
@@ -448,4 +623,86 @@ impl DummyTemporaryOutcomeRepo {
     pub fn new() -> Self {
         Self
     }
-}
\ No newline at end of file
+}
+pub struct DummyScenarioRepo;
+
+impl ScenarioRepo for DummyScenarioRepo {
+    fn list_scenarios(&self) -> Result<Vec<Scenario>, anyhow::Error> {
+        let mut scenarios: Vec<Scenario> = SCENARIO_COLLECTION.with(|collection| collection.borrow().values().cloned().collect());
+        scenarios.sort_by_key(|scenario| scenario.id);
+        Ok(scenarios)
+    }
+    fn get_scenario_by_id(&self, id: u64) -> Result<Option<Scenario>, anyhow::Error> {
+        Ok(SCENARIO_COLLECTION.with(|collection| collection.borrow().get(&id).cloned()))
+    }
+    fn store_scenario(&self, scenario: Scenario) -> Result<u64, anyhow::Error> {
+        let id = SCENARIO_COLLECTION.with(|collection| {
+            let id = collection.borrow().keys().max().map_or(1, |id| id + 1);
+            let scenario = Scenario {
+                id: Some(id),
+                changes: vec![],
+                ..scenario.clone()
+            };
+            collection.borrow_mut().insert(id, scenario);
+            id
+        });
+        for change in scenario.changes {
+            self.store_scenario_change(id, change)?;
+        }
+        Ok(id)
+    }
+    fn delete_scenario(&self, id: u64) -> Result<(), anyhow::Error> {
+        SCENARIO_COLLECTION.with(|collection| collection.borrow_mut().remove(&id));
+        Ok(())
+    }
+    fn store_scenario_change(&self, scenario_id: u64, change: ScenarioChange) -> Result<u64, anyhow::Error> {
+        let id = NEXT_SCENARIO_CHANGE_ID.with(|next_id| next_id.replace_with(|id| *id + 1));
+        SCENARIO_COLLECTION.with(|collection| {
+            let mut collection = collection.borrow_mut();
+            let scenario = collection
+                .get_mut(&scenario_id)
+                .ok_or_else(|| anyhow::anyhow!("Scenario not found: {}", scenario_id))?;
+            scenario.changes.push(ScenarioChange { id: Some(id), ..change });
+            scenario.changes.sort_by_key(|change| (change.start_date, change.id));
+            Ok(id)
+        })
+    }
+    fn delete_scenario_change(&self, id: u64) -> Result<(), anyhow::Error> {
+        SCENARIO_COLLECTION.with(|collection| {
+            for scenario in collection.borrow_mut().values_mut() {
+                scenario.changes.retain(|change| change.id != Some(id));
+            }
+        });
+        Ok(())
+    }
+}
+
+impl DummyScenarioRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// 現在のコレクションを退避し、それを書き戻す関数を返す
+pub fn snapshot_collections() -> Box<dyn FnOnce()> {
+    let part_time_jobs = PART_TIME_JOB_COLLECTION.with(|collection| collection.borrow().clone());
+    let hourly_wages = PART_TIME_JOB_HOURLY_WAGE_COLLECTION.with(|collection| collection.borrow().clone());
+    let part_time_job_incomes = PART_TIME_JOB_INCOME_COLLECTION.with(|collection| collection.borrow().clone());
+    let monthly_income_templates = MONTHLY_INCOME_TEMPLATE_COLLECTION.with(|collection| collection.borrow().clone());
+    let monthly_incomes = MONTHLY_INCOME_COLLECTION.with(|collection| collection.borrow().clone());
+    let monthly_outcome_templates = MONTHLY_OUTCOME_TEMPLATE_COLLECTION.with(|collection| collection.borrow().clone());
+    let monthly_outcomes = MONTHLY_OUTCOME_COLLECTION.with(|collection| collection.borrow().clone());
+    let temporary_outcomes = TEMPORARY_OUTCOME_COLLECTION.with(|collection| collection.borrow().clone());
+    let scenarios = SCENARIO_COLLECTION.with(|collection| collection.borrow().clone());
+    Box::new(move || {
+        PART_TIME_JOB_COLLECTION.with(|collection| *collection.borrow_mut() = part_time_jobs);
+        PART_TIME_JOB_HOURLY_WAGE_COLLECTION.with(|collection| *collection.borrow_mut() = hourly_wages);
+        PART_TIME_JOB_INCOME_COLLECTION.with(|collection| *collection.borrow_mut() = part_time_job_incomes);
+        MONTHLY_INCOME_TEMPLATE_COLLECTION.with(|collection| *collection.borrow_mut() = monthly_income_templates);
+        MONTHLY_INCOME_COLLECTION.with(|collection| *collection.borrow_mut() = monthly_incomes);
+        MONTHLY_OUTCOME_TEMPLATE_COLLECTION.with(|collection| *collection.borrow_mut() = monthly_outcome_templates);
+        MONTHLY_OUTCOME_COLLECTION.with(|collection| *collection.borrow_mut() = monthly_outcomes);
+        TEMPORARY_OUTCOME_COLLECTION.with(|collection| *collection.borrow_mut() = temporary_outcomes);
+        SCENARIO_COLLECTION.with(|collection| *collection.borrow_mut() = scenarios);
+    })
+}
@@ -0,0 +1,1319 @@
+use super::income::{Income, IncomeRepo, ToIncome};
+use super::income::job;
+use super::income::job::{PartTimeHourlyWage, PartTimeJob, PartTimeJobIncome, PartTimeJobRepo};
+use super::income::monthly_income::{MonthlyIncome, MonthlyIncomeRepo, MonthlyIncomeTemplate};
+use super::outcome::monthly_outcome::{MonthlyOutcome, MonthlyOutcomeRepo, MonthlyOutcomeTemplate};
+use super::outcome::temporary_outcome::{TemporaryOutcome, TemporaryOutcomeRepo};
+use super::recurrence::{MissingDayPolicy, MonthDay, RecurrenceRule};
+use super::scenario::{Scenario, ScenarioChange, ScenarioChangeKind, ScenarioRepo, TemplateKind};
+use crate::finance::detail::calendar::BusinessDayRoll;
+use super::outcome::{Outcome, OutcomeRepo, ToOutcome};
+use super::monthly_template::{MonthlyEntry, MonthlyTemplate, MonthlyTemplateRepo};
+use crate::finance::database::{get_datetime, get_decimal, to_timestamp};
+use crate::util::get_next_ym;
+use chrono::prelude::*;
+use rusqlite::{params, types::Type, Connection, OptionalExtension, Row};
+use rust_decimal::Decimal;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+fn get_optional_datetime(row: &Row, idx: usize) -> rusqlite::Result<Option<DateTime<Local>>> {
+    match row.get::<_, Option<i64>>(idx)? {
+        Some(_) => Ok(Some(get_datetime(row, idx)?)),
+        None => Ok(None),
+    }
+}
+
+// 月初から翌月初までのタイムスタンプ
+fn month_range(year: i32, month: u32) -> Result<(i64, i64), anyhow::Error> {
+    let (next_year, next_month) = get_next_ym((year, month));
+    let start = Local.with_ymd_and_hms(year, month, 1, 0, 0, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-01", year, month))?;
+    let end = Local.with_ymd_and_hms(next_year, next_month, 1, 0, 0, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-01", next_year, next_month))?;
+    Ok((to_timestamp(&start), to_timestamp(&end)))
+}
+
+fn encode_weekday(weekday: &Weekday) -> String {
+    weekday.to_string().to_lowercase()
+}
+
+fn encode_month_day(day: &MonthDay) -> String {
+    match day {
+        MonthDay::Day(day) => format!("day:{}", day),
+        MonthDay::Last => "last".to_string(),
+        MonthDay::NthWeekday(n, weekday) => format!("nth:{}:{}", n, encode_weekday(weekday)),
+        MonthDay::LastWeekday(weekday) => format!("last_weekday:{}", encode_weekday(weekday)),
+    }
+}
+
+fn parse_month_day(parts: &[&str]) -> Option<MonthDay> {
+    match parts {
+        ["day", day] => day.parse().ok().map(MonthDay::Day),
+        ["last"] => Some(MonthDay::Last),
+        ["nth", n, weekday] => Some(MonthDay::NthWeekday(n.parse().ok()?, weekday.parse().ok()?)),
+        ["last_weekday", weekday] => weekday.parse().ok().map(MonthDay::LastWeekday),
+        _ => None,
+    }
+}
+
+// 毎月の規則は以前からある "end", "mid:N" などの形で書く
+fn encode_job_payment_timing(payment_timing: &job::PaymentTiming) -> String {
+    match (payment_timing.month_offset, &payment_timing.rule) {
+        (0, RecurrenceRule::Monthly { interval: 1, day: MonthDay::Last }) => "end".to_string(),
+        (0, RecurrenceRule::Monthly { interval: 1, day: MonthDay::Day(day) }) => format!("mid:{}", day),
+        (1, RecurrenceRule::Monthly { interval: 1, day: MonthDay::Last }) => "next_month_end".to_string(),
+        (1, RecurrenceRule::Monthly { interval: 1, day: MonthDay::Day(day) }) => format!("next_month_mid:{}", day),
+        (month_offset, rule) => format!("after:{}:{}", month_offset, encode_recurrence_rule(rule)),
+    }
+}
+
+fn parse_job_payment_timing(text: &str, missing_day: MissingDayPolicy) -> Option<job::PaymentTiming> {
+    let parts: Vec<&str> = text.split(':').collect();
+    let (month_offset, rule) = match parts.as_slice() {
+        ["end"] => (0, RecurrenceRule::monthly(MonthDay::Last)),
+        ["mid", day] => (0, RecurrenceRule::monthly(MonthDay::Day(day.parse().ok()?))),
+        ["next_month_end"] => (1, RecurrenceRule::monthly(MonthDay::Last)),
+        ["next_month_mid", day] => (1, RecurrenceRule::monthly(MonthDay::Day(day.parse().ok()?))),
+        // 毎月の日付だけを書いていたころの "after:N:day:M" も読む
+        ["after", month_offset, rule @ ..] => (
+            month_offset.parse().ok()?,
+            parse_month_day(rule)
+                .map(RecurrenceRule::monthly)
+                .or_else(|| parse_recurrence_rule(&rule.join(":")))?,
+        ),
+        _ => return None,
+    };
+    Some(job::PaymentTiming { month_offset, rule, missing_day })
+}
+
+// 入金日がない月の扱いは別の列に入っている
+fn decode_job_payment_timing(
+    row: &Row,
+    idx: usize,
+    missing_day_idx: usize,
+) -> rusqlite::Result<job::PaymentTiming> {
+    let text: String = row.get(idx)?;
+    let missing_day = decode_missing_day_policy(row, missing_day_idx)?;
+    parse_job_payment_timing(&text, missing_day).ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        Type::Text,
+        format!("Invalid payment timing: {}", text).into(),
+    ))
+}
+
+// 毎月の規則は以前からある "end", "mid:N" の形で書く
+fn encode_recurrence_rule(rule: &RecurrenceRule) -> String {
+    match rule {
+        RecurrenceRule::Monthly { interval: 1, day: MonthDay::Last } => "end".to_string(),
+        RecurrenceRule::Monthly { interval: 1, day: MonthDay::Day(day) } => format!("mid:{}", day),
+        RecurrenceRule::Monthly { interval, day } => format!("monthly:{}:{}", interval, encode_month_day(day)),
+        RecurrenceRule::Weekly { interval, weekday } => format!("weekly:{}:{}", interval, encode_weekday(weekday)),
+        RecurrenceRule::Yearly { month, day } => format!("yearly:{}:{}", month, encode_month_day(day)),
+    }
+}
+
+fn parse_recurrence_rule(text: &str) -> Option<RecurrenceRule> {
+    let parts: Vec<&str> = text.split(':').collect();
+    match parts.as_slice() {
+        ["end"] => Some(RecurrenceRule::monthly(MonthDay::Last)),
+        ["mid", day] => day.parse().ok().map(|day| RecurrenceRule::monthly(MonthDay::Day(day))),
+        ["weekly", interval, weekday] => Some(RecurrenceRule::Weekly {
+            interval: interval.parse().ok()?,
+            weekday: weekday.parse().ok()?,
+        }),
+        ["monthly", interval, day @ ..] => Some(RecurrenceRule::Monthly {
+            interval: interval.parse().ok()?,
+            day: parse_month_day(day)?,
+        }),
+        ["yearly", month, day @ ..] => Some(RecurrenceRule::Yearly {
+            month: month.parse().ok()?,
+            day: parse_month_day(day)?,
+        }),
+        _ => None,
+    }
+}
+
+fn decode_recurrence_rule(row: &Row, idx: usize) -> rusqlite::Result<RecurrenceRule> {
+    let text: String = row.get(idx)?;
+    parse_recurrence_rule(&text).ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        Type::Text,
+        format!("Invalid payment timing: {}", text).into(),
+    ))
+}
+
+fn encode_business_day_roll(roll: BusinessDayRoll) -> &'static str {
+    match roll {
+        BusinessDayRoll::None => "none",
+        BusinessDayRoll::Previous => "previous",
+        BusinessDayRoll::Next => "next",
+    }
+}
+
+fn parse_business_day_roll(text: &str) -> Option<BusinessDayRoll> {
+    match text {
+        "none" => Some(BusinessDayRoll::None),
+        "previous" => Some(BusinessDayRoll::Previous),
+        "next" => Some(BusinessDayRoll::Next),
+        _ => None,
+    }
+}
+
+fn decode_business_day_roll(row: &Row, idx: usize) -> rusqlite::Result<BusinessDayRoll> {
+    let text: String = row.get(idx)?;
+    parse_business_day_roll(&text).ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        Type::Text,
+        format!("Invalid business day roll: {}", text).into(),
+    ))
+}
+
+fn encode_missing_day_policy(policy: MissingDayPolicy) -> &'static str {
+    match policy {
+        MissingDayPolicy::Clamp => "clamp",
+        MissingDayPolicy::Skip => "skip",
+    }
+}
+
+fn parse_missing_day_policy(text: &str) -> Option<MissingDayPolicy> {
+    match text {
+        "clamp" => Some(MissingDayPolicy::Clamp),
+        "skip" => Some(MissingDayPolicy::Skip),
+        _ => None,
+    }
+}
+
+fn decode_missing_day_policy(row: &Row, idx: usize) -> rusqlite::Result<MissingDayPolicy> {
+    let text: String = row.get(idx)?;
+    parse_missing_day_policy(&text).ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        Type::Text,
+        format!("Invalid missing day policy: {}", text).into(),
+    ))
+}
+
+fn part_time_job_from_row(row: &Row) -> rusqlite::Result<PartTimeJob> {
+    Ok(PartTimeJob {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        payment_timing: decode_job_payment_timing(row, 2, 6)?,
+        business_day_roll: decode_business_day_roll(row, 5)?,
+        start_date: get_datetime(row, 3)?,
+        end_date: get_optional_datetime(row, 4)?,
+        account_id: row.get(7)?,
+    })
+}
+
+fn part_time_hourly_wage_from_row(row: &Row) -> rusqlite::Result<PartTimeHourlyWage> {
+    Ok(PartTimeHourlyWage {
+        part_time_job_id: row.get(0)?,
+        hourly_wage: get_decimal(row, 1)?,
+        start_year_and_month: (row.get(2)?, row.get(3)?),
+    })
+}
+
+fn part_time_job_income_from_row(row: &Row) -> rusqlite::Result<PartTimeJobIncome> {
+    Ok(PartTimeJobIncome {
+        id: Some(row.get(0)?),
+        part_time_job_id: row.get(1)?,
+        name: row.get(2)?,
+        hourly_wage: get_decimal(row, 3)?,
+        hour: get_decimal(row, 4)?,
+        payment_date: get_datetime(row, 5)?,
+    })
+}
+
+pub struct SqlitePartTimeJobRepo {
+    conn: Rc<Connection>,
+}
+
+impl PartTimeJobRepo for SqlitePartTimeJobRepo {
+    fn list_part_time_jobs(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<PartTimeJob>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id FROM part_time_jobs
+             WHERE start_date <= ?2 AND (end_date IS NULL OR end_date >= ?1)
+             ORDER BY id",
+        )?;
+        let jobs = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], part_time_job_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(jobs)
+    }
+    fn get_part_time_job_by_id(
+        &self,
+        part_time_job_id: u64,
+    ) -> Result<Option<PartTimeJob>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT id, name, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id FROM part_time_jobs WHERE id = ?1",
+                params![part_time_job_id],
+                part_time_job_from_row,
+            )
+            .optional()?)
+    }
+    fn store_part_time_job(&self, part_time_job: PartTimeJob) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO part_time_jobs (name, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                part_time_job.name,
+                encode_job_payment_timing(&part_time_job.payment_timing),
+                to_timestamp(&part_time_job.start_date),
+                part_time_job.end_date.as_ref().map(to_timestamp),
+                encode_business_day_roll(part_time_job.business_day_roll),
+                encode_missing_day_policy(part_time_job.payment_timing.missing_day),
+                part_time_job.account_id,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn update_part_time_job(&self, part_time_job: PartTimeJob) -> Result<(), anyhow::Error> {
+        let id = part_time_job.id.ok_or_else(|| anyhow::anyhow!("Part-time job has no id"))?;
+        self.conn.execute(
+            "UPDATE part_time_jobs SET name = ?1, payment_timing = ?2, start_date = ?3, end_date = ?4, business_day_roll = ?5,
+                missing_day_policy = ?6, account_id = ?7
+             WHERE id = ?8",
+            params![
+                part_time_job.name,
+                encode_job_payment_timing(&part_time_job.payment_timing),
+                to_timestamp(&part_time_job.start_date),
+                part_time_job.end_date.as_ref().map(to_timestamp),
+                encode_business_day_roll(part_time_job.business_day_roll),
+                encode_missing_day_policy(part_time_job.payment_timing.missing_day),
+                part_time_job.account_id,
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+    fn get_part_time_job_hourly_wage(
+        &self,
+        part_time_job_id: u64,
+        year: i32,
+        month: u32,
+    ) -> Result<Option<PartTimeHourlyWage>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT part_time_job_id, hourly_wage, start_year, start_month FROM part_time_job_hourly_wages
+                 WHERE part_time_job_id = ?1 AND (start_year < ?2 OR (start_year = ?2 AND start_month <= ?3))
+                 ORDER BY start_year DESC, start_month DESC
+                 LIMIT 1",
+                params![part_time_job_id, year, month],
+                part_time_hourly_wage_from_row,
+            )
+            .optional()?)
+    }
+    fn get_part_time_job_hourly_wage_by_start_year_and_month(
+        &self,
+        part_time_job_id: u64,
+        start_year_and_month: (i32, u32),
+    ) -> Result<Option<PartTimeHourlyWage>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT part_time_job_id, hourly_wage, start_year, start_month FROM part_time_job_hourly_wages
+                 WHERE part_time_job_id = ?1 AND start_year = ?2 AND start_month = ?3",
+                params![part_time_job_id, start_year_and_month.0, start_year_and_month.1],
+                part_time_hourly_wage_from_row,
+            )
+            .optional()?)
+    }
+    fn store_part_time_job_hourly_wage(
+        &self,
+        part_time_job_id: u64,
+        hourly_wage: Decimal,
+        start_year_and_month: (i32, u32),
+    ) -> Result<(), anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO part_time_job_hourly_wages (part_time_job_id, hourly_wage, start_year, start_month)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![part_time_job_id, hourly_wage.to_string(), start_year_and_month.0, start_year_and_month.1],
+        )?;
+        Ok(())
+    }
+    fn update_part_time_job_hourly_wage(
+        &self,
+        part_time_job_id: u64,
+        hourly_wage: Decimal,
+        start_year_and_month: (i32, u32),
+    ) -> Result<(), anyhow::Error> {
+        self.conn.execute(
+            "UPDATE part_time_job_hourly_wages SET hourly_wage = ?1
+             WHERE part_time_job_id = ?2 AND start_year = ?3 AND start_month = ?4",
+            params![hourly_wage.to_string(), part_time_job_id, start_year_and_month.0, start_year_and_month.1],
+        )?;
+        Ok(())
+    }
+    fn get_part_time_job_income_by_id(
+        &self,
+        id: u64,
+    ) -> Result<Option<PartTimeJobIncome>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT id, part_time_job_id, name, hourly_wage, hour, payment_date FROM part_time_job_incomes
+                 WHERE id = ?1",
+                params![id],
+                part_time_job_income_from_row,
+            )
+            .optional()?)
+    }
+    fn list_part_time_job_incomes_by_part_time_job_id(
+        &self,
+        part_time_job_id: u64,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<PartTimeJobIncome>, anyhow::Error> {
+        let (start, end) = month_range(year, month)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, part_time_job_id, name, hourly_wage, hour, payment_date FROM part_time_job_incomes
+             WHERE part_time_job_id = ?1 AND payment_date >= ?2 AND payment_date < ?3
+             ORDER BY payment_date, id",
+        )?;
+        let incomes = stmt
+            .query_map(params![part_time_job_id, start, end], part_time_job_income_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(incomes)
+    }
+    fn store_part_time_job_income(
+        &self,
+        part_time_job_income: PartTimeJobIncome,
+    ) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO part_time_job_incomes (part_time_job_id, name, hourly_wage, hour, payment_date)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                part_time_job_income.part_time_job_id,
+                part_time_job_income.name,
+                part_time_job_income.hourly_wage.to_string(),
+                part_time_job_income.hour.to_string(),
+                to_timestamp(&part_time_job_income.payment_date),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn update_part_time_job_income(
+        &self,
+        part_time_job_income: PartTimeJobIncome,
+    ) -> Result<(), anyhow::Error> {
+        let id = part_time_job_income.id.ok_or_else(|| anyhow::anyhow!("Part-time job income has no id"))?;
+        self.conn.execute(
+            "UPDATE part_time_job_incomes SET name = ?1, hourly_wage = ?2, hour = ?3, payment_date = ?4
+             WHERE id = ?5",
+            params![
+                part_time_job_income.name,
+                part_time_job_income.hourly_wage.to_string(),
+                part_time_job_income.hour.to_string(),
+                to_timestamp(&part_time_job_income.payment_date),
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl IncomeRepo for SqlitePartTimeJobRepo {
+    fn list_incomes(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<Income>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, part_time_job_id, name, hourly_wage, hour, payment_date FROM part_time_job_incomes
+             WHERE payment_date >= ?1 AND payment_date <= ?2
+             ORDER BY payment_date, id",
+        )?;
+        let incomes = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], part_time_job_income_from_row)?
+            .map(|income| income.map(|income| income.to_income()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(incomes)
+    }
+}
+
+impl SqlitePartTimeJobRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+fn monthly_template_from_row<T>(row: &Row) -> rusqlite::Result<MonthlyTemplate<T>> {
+    Ok(MonthlyTemplate {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        amount: get_decimal(row, 2)?,
+        payment_timing: decode_recurrence_rule(row, 3)?,
+        missing_day: decode_missing_day_policy(row, 7)?,
+        business_day_roll: decode_business_day_roll(row, 6)?,
+        start_date: get_datetime(row, 4)?,
+        end_date: get_optional_datetime(row, 5)?,
+        account_id: row.get(8)?,
+        entry: PhantomData,
+    })
+}
+
+fn monthly_entry_from_row<T>(row: &Row) -> rusqlite::Result<MonthlyEntry<T>> {
+    Ok(MonthlyEntry {
+        id: Some(row.get(0)?),
+        template_id: row.get(1)?,
+        name: row.get(2)?,
+        amount: get_decimal(row, 3)?,
+        payment_date: get_datetime(row, 4)?,
+        entry: PhantomData,
+    })
+}
+
+pub struct SqliteMonthlyIncomeRepo {
+    conn: Rc<Connection>,
+}
+
+impl MonthlyTemplateRepo<Income> for SqliteMonthlyIncomeRepo {
+    fn list_monthly_templates(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyIncomeTemplate>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, amount, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id
+             FROM monthly_income_templates
+             WHERE start_date <= ?2 AND (end_date IS NULL OR end_date >= ?1)
+             ORDER BY id",
+        )?;
+        let templates = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], monthly_template_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(templates)
+    }
+    fn get_monthly_template_by_id(&self, id: u64) -> Result<Option<MonthlyIncomeTemplate>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT id, name, amount, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id
+                 FROM monthly_income_templates WHERE id = ?1",
+                params![id],
+                monthly_template_from_row,
+            )
+            .optional()?)
+    }
+    fn store_monthly_template(&self, template: MonthlyIncomeTemplate) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO monthly_income_templates
+                (name, amount, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                template.name,
+                template.amount.to_string(),
+                encode_recurrence_rule(&template.payment_timing),
+                to_timestamp(&template.start_date),
+                template.end_date.as_ref().map(to_timestamp),
+                encode_business_day_roll(template.business_day_roll),
+                encode_missing_day_policy(template.missing_day),
+                template.account_id,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn store_monthly_entry(&self, monthly_income: MonthlyIncome) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO monthly_incomes (monthly_income_template_id, name, amount, payment_date)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                monthly_income.template_id,
+                monthly_income.name,
+                monthly_income.amount.to_string(),
+                to_timestamp(&monthly_income.payment_date),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn list_monthly_entries_by_template_id(
+        &self,
+        template_id: u64,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyIncome>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, monthly_income_template_id, name, amount, payment_date FROM monthly_incomes
+             WHERE monthly_income_template_id = ?1 AND payment_date >= ?2 AND payment_date <= ?3
+             ORDER BY payment_date, id",
+        )?;
+        let params = params![template_id, to_timestamp(start_date), to_timestamp(end_date)];
+        let incomes = stmt
+            .query_map(params, monthly_entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(incomes)
+    }
+}
+
+impl MonthlyIncomeRepo for SqliteMonthlyIncomeRepo {}
+
+impl IncomeRepo for SqliteMonthlyIncomeRepo {
+    fn list_incomes(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<Income>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, monthly_income_template_id, name, amount, payment_date FROM monthly_incomes
+             WHERE payment_date >= ?1 AND payment_date <= ?2
+             ORDER BY payment_date, id",
+        )?;
+        let incomes = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], monthly_entry_from_row)?
+            .map(|income| income.map(|income| income.to_income()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(incomes)
+    }
+}
+
+impl SqliteMonthlyIncomeRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+pub struct SqliteMonthlyOutcomeRepo {
+    conn: Rc<Connection>,
+}
+
+impl MonthlyTemplateRepo<Outcome> for SqliteMonthlyOutcomeRepo {
+    fn list_monthly_templates(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyOutcomeTemplate>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, amount, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id
+             FROM monthly_outcome_templates
+             WHERE start_date <= ?2 AND (end_date IS NULL OR end_date >= ?1)
+             ORDER BY id",
+        )?;
+        let templates = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], monthly_template_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(templates)
+    }
+    fn get_monthly_template_by_id(&self, id: u64) -> Result<Option<MonthlyOutcomeTemplate>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT id, name, amount, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id
+                 FROM monthly_outcome_templates WHERE id = ?1",
+                params![id],
+                monthly_template_from_row,
+            )
+            .optional()?)
+    }
+    fn store_monthly_template(&self, template: MonthlyOutcomeTemplate) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO monthly_outcome_templates
+                (name, amount, payment_timing, start_date, end_date, business_day_roll, missing_day_policy, account_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                template.name,
+                template.amount.to_string(),
+                encode_recurrence_rule(&template.payment_timing),
+                to_timestamp(&template.start_date),
+                template.end_date.as_ref().map(to_timestamp),
+                encode_business_day_roll(template.business_day_roll),
+                encode_missing_day_policy(template.missing_day),
+                template.account_id,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn store_monthly_entry(&self, monthly_outcome: MonthlyOutcome) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO monthly_outcomes (monthly_outcome_template_id, name, amount, payment_date)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                monthly_outcome.template_id,
+                monthly_outcome.name,
+                monthly_outcome.amount.to_string(),
+                to_timestamp(&monthly_outcome.payment_date),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn list_monthly_entries_by_template_id(
+        &self,
+        template_id: u64,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<MonthlyOutcome>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, monthly_outcome_template_id, name, amount, payment_date FROM monthly_outcomes
+             WHERE monthly_outcome_template_id = ?1 AND payment_date >= ?2 AND payment_date <= ?3
+             ORDER BY payment_date, id",
+        )?;
+        let params = params![template_id, to_timestamp(start_date), to_timestamp(end_date)];
+        let outcomes = stmt
+            .query_map(params, monthly_entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(outcomes)
+    }
+}
+
+impl MonthlyOutcomeRepo for SqliteMonthlyOutcomeRepo {
+    fn update_monthly_outcome(&self, monthly_outcome: MonthlyOutcome) -> Result<(), anyhow::Error> {
+        let id = monthly_outcome.id.ok_or_else(|| anyhow::anyhow!("Monthly outcome has no id"))?;
+        self.conn.execute(
+            "UPDATE monthly_outcomes SET name = ?1, amount = ?2, payment_date = ?3 WHERE id = ?4",
+            params![
+                monthly_outcome.name,
+                monthly_outcome.amount.to_string(),
+                to_timestamp(&monthly_outcome.payment_date),
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl OutcomeRepo for SqliteMonthlyOutcomeRepo {
+    fn list_outcomes(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<Outcome>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, monthly_outcome_template_id, name, amount, payment_date FROM monthly_outcomes
+             WHERE payment_date >= ?1 AND payment_date <= ?2
+             ORDER BY payment_date, id",
+        )?;
+        let outcomes = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], monthly_entry_from_row)?
+            .map(|outcome| outcome.map(|outcome| outcome.to_outcome()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(outcomes)
+    }
+}
+
+impl SqliteMonthlyOutcomeRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+fn temporary_outcome_from_row(row: &Row) -> rusqlite::Result<TemporaryOutcome> {
+    Ok(TemporaryOutcome {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        amount: get_decimal(row, 2)?,
+        date: get_datetime(row, 3)?,
+    })
+}
+
+pub struct SqliteTemporaryOutcomeRepo {
+    conn: Rc<Connection>,
+}
+
+impl TemporaryOutcomeRepo for SqliteTemporaryOutcomeRepo {
+    fn list_temporary_outcomes(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<TemporaryOutcome>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, amount, date FROM temporary_outcomes
+             WHERE date >= ?1 AND date <= ?2
+             ORDER BY date, id",
+        )?;
+        let outcomes = stmt
+            .query_map(params![to_timestamp(start_date), to_timestamp(end_date)], temporary_outcome_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(outcomes)
+    }
+    fn store_temporary_outcome(
+        &self,
+        temporary_outcome: TemporaryOutcome,
+    ) -> Result<u64, anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO temporary_outcomes (name, amount, date) VALUES (?1, ?2, ?3)",
+            params![
+                temporary_outcome.name,
+                temporary_outcome.amount.to_string(),
+                to_timestamp(&temporary_outcome.date),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn update_temporary_outcome(
+        &self,
+        temporary_outcome: TemporaryOutcome,
+    ) -> Result<(), anyhow::Error> {
+        let id = temporary_outcome.id.ok_or_else(|| anyhow::anyhow!("Temporary outcome has no id"))?;
+        self.conn.execute(
+            "UPDATE temporary_outcomes SET name = ?1, amount = ?2, date = ?3 WHERE id = ?4",
+            params![
+                temporary_outcome.name,
+                temporary_outcome.amount.to_string(),
+                to_timestamp(&temporary_outcome.date),
+                id,
+            ],
+        )?;
+        Ok(())
+    }
+    fn get_temporary_outcome_by_id(
+        &self,
+        id: u64,
+    ) -> Result<Option<TemporaryOutcome>, anyhow::Error> {
+        Ok(self.conn
+            .query_row(
+                "SELECT id, name, amount, date FROM temporary_outcomes WHERE id = ?1",
+                params![id],
+                temporary_outcome_from_row,
+            )
+            .optional()?)
+    }
+}
+
+impl OutcomeRepo for SqliteTemporaryOutcomeRepo {
+    fn list_outcomes(
+        &self,
+        start_date: &DateTime<Local>,
+        end_date: &DateTime<Local>,
+    ) -> Result<Vec<Outcome>, anyhow::Error> {
+        Ok(self
+            .list_temporary_outcomes(start_date, end_date)?
+            .iter()
+            .map(|outcome| outcome.to_outcome())
+            .collect())
+    }
+}
+
+impl SqliteTemporaryOutcomeRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+fn encode_template_kind(kind: TemplateKind) -> &'static str {
+    match kind {
+        TemplateKind::Income => "income",
+        TemplateKind::Outcome => "outcome",
+    }
+}
+
+fn parse_template_kind(text: &str) -> Option<TemplateKind> {
+    match text {
+        "income" => Some(TemplateKind::Income),
+        "outcome" => Some(TemplateKind::Outcome),
+        _ => None,
+    }
+}
+
+// 変更の種類ごとに使う列だけを埋める
+struct ScenarioChangeColumns {
+    kind: String,
+    template_kind: Option<String>,
+    target_id: Option<u64>,
+    name: Option<String>,
+    amount: Option<String>,
+    hour: Option<String>,
+    payment_timing: Option<String>,
+    missing_day_policy: Option<String>,
+    business_day_roll: Option<String>,
+}
+
+fn encode_scenario_change(kind: &ScenarioChangeKind) -> ScenarioChangeColumns {
+    let columns = ScenarioChangeColumns {
+        kind: String::new(),
+        template_kind: None,
+        target_id: None,
+        name: None,
+        amount: None,
+        hour: None,
+        payment_timing: None,
+        missing_day_policy: None,
+        business_day_roll: None,
+    };
+    match kind {
+        ScenarioChangeKind::AdjustMonthly { template_kind, template_id, delta } => ScenarioChangeColumns {
+            kind: "adjust_monthly".to_string(),
+            template_kind: Some(encode_template_kind(*template_kind).to_string()),
+            target_id: Some(*template_id),
+            amount: Some(delta.to_string()),
+            ..columns
+        },
+        ScenarioChangeKind::RemoveMonthly { template_kind, template_id } => ScenarioChangeColumns {
+            kind: "remove_monthly".to_string(),
+            template_kind: Some(encode_template_kind(*template_kind).to_string()),
+            target_id: Some(*template_id),
+            ..columns
+        },
+        ScenarioChangeKind::AddMonthly { template_kind, name, amount, payment_timing, missing_day, business_day_roll } => ScenarioChangeColumns {
+            kind: "add_monthly".to_string(),
+            template_kind: Some(encode_template_kind(*template_kind).to_string()),
+            name: Some(name.clone()),
+            amount: Some(amount.to_string()),
+            payment_timing: Some(encode_recurrence_rule(payment_timing)),
+            missing_day_policy: Some(encode_missing_day_policy(*missing_day).to_string()),
+            business_day_roll: Some(encode_business_day_roll(*business_day_roll).to_string()),
+            ..columns
+        },
+        ScenarioChangeKind::ChangePartTimeJob { part_time_job_id, hourly_wage, hour } => ScenarioChangeColumns {
+            kind: "change_part_time_job".to_string(),
+            target_id: Some(*part_time_job_id),
+            amount: Some(hourly_wage.to_string()),
+            hour: Some(hour.to_string()),
+            ..columns
+        },
+        ScenarioChangeKind::RemovePartTimeJob { part_time_job_id } => ScenarioChangeColumns {
+            kind: "remove_part_time_job".to_string(),
+            target_id: Some(*part_time_job_id),
+            ..columns
+        },
+        ScenarioChangeKind::AddOneOffOutcome { name, amount } => ScenarioChangeColumns {
+            kind: "add_one_off_outcome".to_string(),
+            name: Some(name.clone()),
+            amount: Some(amount.to_string()),
+            ..columns
+        },
+    }
+}
+
+fn parse_scenario_change(columns: ScenarioChangeColumns) -> Option<ScenarioChangeKind> {
+    let ScenarioChangeColumns { kind, template_kind, target_id, name, amount, hour, payment_timing, missing_day_policy, business_day_roll } =
+        columns;
+    let template_kind = || template_kind.as_deref().and_then(parse_template_kind);
+    let amount = || amount.as_deref().and_then(|amount| amount.parse::<Decimal>().ok());
+    match kind.as_str() {
+        "adjust_monthly" => Some(ScenarioChangeKind::AdjustMonthly {
+            template_kind: template_kind()?,
+            template_id: target_id?,
+            delta: amount()?,
+        }),
+        "remove_monthly" => Some(ScenarioChangeKind::RemoveMonthly {
+            template_kind: template_kind()?,
+            template_id: target_id?,
+        }),
+        "add_monthly" => Some(ScenarioChangeKind::AddMonthly {
+            template_kind: template_kind()?,
+            name: name?,
+            amount: amount()?,
+            payment_timing: parse_recurrence_rule(&payment_timing?)?,
+            // 列がなかったころの変更は月末に寄せ、休日でずらさない
+            missing_day: missing_day_policy.map_or(Some(MissingDayPolicy::Clamp), |text| parse_missing_day_policy(&text))?,
+            business_day_roll: business_day_roll.map_or(Some(BusinessDayRoll::None), |text| parse_business_day_roll(&text))?,
+        }),
+        "change_part_time_job" => Some(ScenarioChangeKind::ChangePartTimeJob {
+            part_time_job_id: target_id?,
+            hourly_wage: amount()?,
+            hour: hour?.parse().ok()?,
+        }),
+        "remove_part_time_job" => Some(ScenarioChangeKind::RemovePartTimeJob { part_time_job_id: target_id? }),
+        "add_one_off_outcome" => Some(ScenarioChangeKind::AddOneOffOutcome { name: name?, amount: amount()? }),
+        _ => None,
+    }
+}
+
+fn scenario_change_from_row(row: &Row) -> rusqlite::Result<ScenarioChange> {
+    let kind: String = row.get(2)?;
+    let change_kind = parse_scenario_change(ScenarioChangeColumns {
+        kind: kind.clone(),
+        template_kind: row.get(3)?,
+        target_id: row.get(4)?,
+        name: row.get(5)?,
+        amount: row.get(6)?,
+        hour: row.get(7)?,
+        payment_timing: row.get(8)?,
+        missing_day_policy: row.get(9)?,
+        business_day_roll: row.get(10)?,
+    })
+    .ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(
+        2,
+        Type::Text,
+        format!("Invalid scenario change: {}", kind).into(),
+    ))?;
+    Ok(ScenarioChange {
+        id: Some(row.get(0)?),
+        start_date: get_datetime(row, 1)?,
+        kind: change_kind,
+    })
+}
+
+pub struct SqliteScenarioRepo {
+    conn: Rc<Connection>,
+}
+
+impl SqliteScenarioRepo {
+    fn list_scenario_changes(&self, scenario_id: u64) -> Result<Vec<ScenarioChange>, anyhow::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, start_date, kind, template_kind, target_id, name, amount, hour, payment_timing,
+                missing_day_policy, business_day_roll
+             FROM scenario_changes WHERE scenario_id = ?1
+             ORDER BY start_date, id",
+        )?;
+        let changes = stmt
+            .query_map(params![scenario_id], scenario_change_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(changes)
+    }
+}
+
+impl ScenarioRepo for SqliteScenarioRepo {
+    fn list_scenarios(&self) -> Result<Vec<Scenario>, anyhow::Error> {
+        let mut stmt = self.conn.prepare("SELECT id, name FROM scenarios ORDER BY id")?;
+        let scenarios = stmt
+            .query_map([], |row| Ok((row.get::<_, u64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        scenarios
+            .into_iter()
+            .map(|(id, name)| Ok(Scenario { id: Some(id), name, changes: self.list_scenario_changes(id)? }))
+            .collect()
+    }
+    fn get_scenario_by_id(&self, id: u64) -> Result<Option<Scenario>, anyhow::Error> {
+        let name: Option<String> = self.conn
+            .query_row("SELECT name FROM scenarios WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?;
+        name.map(|name| Ok(Scenario { id: Some(id), name, changes: self.list_scenario_changes(id)? }))
+            .transpose()
+    }
+    fn store_scenario(&self, scenario: Scenario) -> Result<u64, anyhow::Error> {
+        self.conn.execute("INSERT INTO scenarios (name) VALUES (?1)", params![scenario.name])?;
+        let id = self.conn.last_insert_rowid() as u64;
+        for change in scenario.changes {
+            self.store_scenario_change(id, change)?;
+        }
+        Ok(id)
+    }
+    fn delete_scenario(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM scenarios WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+    fn store_scenario_change(&self, scenario_id: u64, change: ScenarioChange) -> Result<u64, anyhow::Error> {
+        let columns = encode_scenario_change(&change.kind);
+        self.conn.execute(
+            "INSERT INTO scenario_changes
+                (scenario_id, start_date, kind, template_kind, target_id, name, amount, hour, payment_timing,
+                 missing_day_policy, business_day_roll)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                scenario_id,
+                to_timestamp(&change.start_date),
+                columns.kind,
+                columns.template_kind,
+                columns.target_id,
+                columns.name,
+                columns.amount,
+                columns.hour,
+                columns.payment_timing,
+                columns.missing_day_policy,
+                columns.business_day_roll,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+    fn delete_scenario_change(&self, id: u64) -> Result<(), anyhow::Error> {
+        self.conn.execute("DELETE FROM scenario_changes WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+impl SqliteScenarioRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_legacy_payment_timings() {
+        assert_eq!(parse_recurrence_rule("end"), Some(RecurrenceRule::monthly(MonthDay::Last)));
+        assert_eq!(parse_recurrence_rule("mid:15"), Some(RecurrenceRule::monthly(MonthDay::Day(15))));
+        let job_timing = parse_job_payment_timing("next_month_mid:21", MissingDayPolicy::Clamp).unwrap();
+        assert_eq!((job_timing.month_offset, job_timing.rule), (1, RecurrenceRule::monthly(MonthDay::Day(21))));
+        let job_timing = parse_job_payment_timing("end", MissingDayPolicy::Clamp).unwrap();
+        assert_eq!((job_timing.month_offset, job_timing.rule), (0, RecurrenceRule::monthly(MonthDay::Last)));
+        let job_timing = parse_job_payment_timing("after:2:nth:3:wed", MissingDayPolicy::Clamp).unwrap();
+        assert_eq!((job_timing.month_offset, job_timing.rule), (2, RecurrenceRule::monthly(MonthDay::NthWeekday(3, Weekday::Wed))));
+        assert_eq!(parse_recurrence_rule("mid:"), None);
+        assert_eq!(parse_recurrence_rule("weekly:1:someday"), None);
+    }
+
+    #[test]
+    fn round_trips_recurrence_rules() {
+        let rules = [
+            RecurrenceRule::monthly(MonthDay::Last),
+            RecurrenceRule::monthly(MonthDay::Day(25)),
+            RecurrenceRule::monthly(MonthDay::NthWeekday(2, Weekday::Fri)),
+            RecurrenceRule::monthly(MonthDay::LastWeekday(Weekday::Mon)),
+            RecurrenceRule::Monthly { interval: 3, day: MonthDay::Day(10) },
+            RecurrenceRule::Weekly { interval: 2, weekday: Weekday::Sun },
+            RecurrenceRule::Yearly { month: 6, day: MonthDay::Last },
+        ];
+        for rule in rules {
+            assert_eq!(parse_recurrence_rule(&encode_recurrence_rule(&rule)), Some(rule));
+        }
+        assert_eq!(encode_recurrence_rule(&RecurrenceRule::monthly(MonthDay::Day(25))), "mid:25");
+    }
+
+    #[test]
+    fn round_trips_job_payment_timings() {
+        let timings = [
+            (0, RecurrenceRule::monthly(MonthDay::Last)),
+            (0, RecurrenceRule::monthly(MonthDay::Day(25))),
+            (1, RecurrenceRule::monthly(MonthDay::Last)),
+            (1, RecurrenceRule::monthly(MonthDay::Day(21))),
+            (1, RecurrenceRule::monthly(MonthDay::NthWeekday(3, Weekday::Wed))),
+            (2, RecurrenceRule::monthly(MonthDay::Day(10))),
+            (0, RecurrenceRule::Weekly { interval: 1, weekday: Weekday::Fri }),
+            (1, RecurrenceRule::Weekly { interval: 2, weekday: Weekday::Mon }),
+        ];
+        for (month_offset, rule) in timings {
+            let timing = job::PaymentTiming { month_offset, rule, missing_day: MissingDayPolicy::Skip };
+            let decoded = parse_job_payment_timing(&encode_job_payment_timing(&timing), MissingDayPolicy::Skip).unwrap();
+            assert_eq!((decoded.month_offset, decoded.rule), (month_offset, rule));
+        }
+        assert_eq!(encode_job_payment_timing(&job::PaymentTiming {
+            month_offset: 1,
+            rule: RecurrenceRule::monthly(MonthDay::Day(21)),
+            missing_day: MissingDayPolicy::Clamp,
+        }), "next_month_mid:21");
+    }
+
+    #[test]
+    fn projects_monthly_outcomes_without_storing() {
+        use crate::finance::database::open_in_memory;
+        use crate::finance::detail::calendar::BusinessCalendar;
+        use crate::finance::setting::AccountingPeriod;
+
+        let repo = SqliteMonthlyOutcomeRepo::new(Rc::new(open_in_memory().unwrap()));
+        let mut template = MonthlyOutcomeTemplate::try_new(
+            "家賃".to_string(),
+            "80000".to_string(),
+            RecurrenceRule::monthly(MonthDay::Day(27)),
+            MissingDayPolicy::Clamp,
+            BusinessDayRoll::None,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            None,
+        )
+        .unwrap();
+        template.account_id = Some(1);
+        template.id = Some(repo.store_monthly_template(template.clone()).unwrap());
+        let (period, calendar) = (AccountingPeriod::default(), BusinessCalendar::japanese());
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(2025, 3).unwrap();
+        assert_eq!(repo.list_monthly_templates(&opening_date, &closing_date).unwrap()[0].account_id, Some(1));
+        assert_eq!(repo.get_monthly_template_by_id(template.id.unwrap()).unwrap().map(|template| template.name), Some("家賃".to_string()));
+        assert!(repo.get_monthly_template_by_id(template.id.unwrap() + 1).unwrap().is_none());
+        let stored = || repo.list_monthly_entries_by_template_id(template.id.unwrap(), &opening_date, &closing_date).unwrap();
+
+        // 見込みは何度作っても保存されない
+        for _ in 0..2 {
+            let projected = template.project_entries(2025, 3, &period, &calendar, &repo).unwrap();
+            assert_eq!(projected.len(), 1);
+            assert_eq!(projected[0].id, None);
+            assert!(stored().is_empty());
+        }
+
+        // 確定させると 1 件だけ保存され、以降の見込みはそれを使う
+        let created = template.get_or_create_entries(2025, 3, &period, &calendar, &repo).unwrap();
+        template.get_or_create_entries(2025, 3, &period, &calendar, &repo).unwrap();
+        assert_eq!(stored().len(), 1);
+        let projected = template.project_entries(2025, 3, &period, &calendar, &repo).unwrap();
+        assert_eq!(projected[0].id, created[0].id);
+        assert!(projected[0].id.is_some());
+    }
+
+    #[test]
+    fn round_trips_scenarios() {
+        use crate::finance::database::open_in_memory;
+
+        let repo = SqliteScenarioRepo::new(Rc::new(open_in_memory().unwrap()));
+        let start_date = Local.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap();
+        let kinds = vec![
+            ScenarioChangeKind::AdjustMonthly { template_kind: TemplateKind::Outcome, template_id: 1, delta: Decimal::from(-5000) },
+            ScenarioChangeKind::RemoveMonthly { template_kind: TemplateKind::Income, template_id: 2 },
+            ScenarioChangeKind::AddMonthly {
+                template_kind: TemplateKind::Income,
+                name: "副業".to_string(),
+                amount: Decimal::from(30000),
+                payment_timing: RecurrenceRule::monthly(MonthDay::Day(31)),
+                missing_day: MissingDayPolicy::Skip,
+                business_day_roll: BusinessDayRoll::Previous,
+            },
+            ScenarioChangeKind::ChangePartTimeJob { part_time_job_id: 1, hourly_wage: Decimal::from(1300), hour: Decimal::new(405, 1) },
+            ScenarioChangeKind::RemovePartTimeJob { part_time_job_id: 1 },
+            ScenarioChangeKind::AddOneOffOutcome { name: "引っ越し代".to_string(), amount: Decimal::from(120000) },
+        ];
+        let id = repo.store_scenario(Scenario::try_new("引っ越し".to_string()).unwrap()).unwrap();
+        for kind in kinds.clone() {
+            repo.store_scenario_change(id, ScenarioChange { id: None, start_date, kind }).unwrap();
+        }
+
+        let scenario = repo.get_scenario_by_id(id).unwrap().unwrap();
+        assert_eq!(scenario.name, "引っ越し");
+        assert_eq!(scenario.changes.iter().map(|change| change.kind.clone()).collect::<Vec<_>>(), kinds);
+        assert!(scenario.changes.iter().all(|change| change.start_date == start_date));
+
+        repo.delete_scenario_change(scenario.changes[0].id.unwrap()).unwrap();
+        assert_eq!(repo.list_scenarios().unwrap()[0].changes.len(), kinds.len() - 1);
+        // シナリオを消すと変更も消える
+        repo.delete_scenario(id).unwrap();
+        assert!(repo.get_scenario_by_id(id).unwrap().is_none());
+        let remaining: u64 = repo.conn.query_row("SELECT COUNT(*) FROM scenario_changes", [], |row| row.get(0)).unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn round_trips_part_time_jobs() {
+        use crate::finance::database::open_in_memory;
+
+        let repo = SqlitePartTimeJobRepo::new(Rc::new(open_in_memory().unwrap()));
+        let payment_timing = job::PaymentTiming { month_offset: 1, rule: RecurrenceRule::monthly(MonthDay::Day(31)), missing_day: MissingDayPolicy::Skip };
+        let part_time_job = PartTimeJob::try_new(
+            " 塾講師 ".to_string(),
+            payment_timing,
+            BusinessDayRoll::Previous,
+            NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+        )
+        .unwrap();
+        let part_time_job = PartTimeJob { account_id: Some(1), ..part_time_job };
+        let id = job::store_part_time_job(part_time_job, Decimal::from(1200), &repo).unwrap();
+
+        let (start_date, end_date) = (
+            Local.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 5, 31, 0, 0, 0).unwrap(),
+        );
+        let jobs = repo.list_part_time_jobs(&start_date, &end_date).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!((jobs[0].id, jobs[0].name.as_str()), (Some(id), "塾講師"));
+        assert_eq!((jobs[0].payment_timing.month_offset, &jobs[0].payment_timing.rule), (1, &RecurrenceRule::monthly(MonthDay::Day(31))));
+        assert_eq!(jobs[0].payment_timing.missing_day, MissingDayPolicy::Skip);
+        assert_eq!((jobs[0].business_day_roll, jobs[0].account_id), (BusinessDayRoll::Previous, Some(1)));
+        // 時給は働き始めた月から有効
+        assert_eq!(repo.get_part_time_job_hourly_wage(id, 2025, 3).unwrap().map(|w| w.hourly_wage), None);
+        assert_eq!(repo.get_part_time_job_hourly_wage(id, 2025, 5).unwrap().map(|w| w.hourly_wage), Some(Decimal::from(1200)));
+
+        // 同じ月の時給は上書きされる
+        jobs[0].set_hourly_wage(Decimal::from(1250), (2025, 4), &repo).unwrap();
+        assert_eq!(repo.get_part_time_job_hourly_wage(id, 2025, 5).unwrap().map(|w| w.hourly_wage), Some(Decimal::from(1250)));
+
+        // 辞めたら翌月以降は一覧に出ない
+        let ended = PartTimeJob { end_date: Some(Local.with_ymd_and_hms(2025, 4, 30, 0, 0, 0).unwrap()), ..jobs[0].clone() };
+        repo.update_part_time_job(ended).unwrap();
+        assert!(repo.list_part_time_jobs(&start_date, &end_date).unwrap().is_empty());
+        let ended = repo.get_part_time_job_by_id(id).unwrap().unwrap();
+        assert!(ended.end_date.is_some());
+        assert_eq!(ended.account_id, Some(1));
+    }
+
+    #[test]
+    fn round_trips_part_time_job_incomes() {
+        use crate::finance::database::open_in_memory;
+
+        let repo = SqlitePartTimeJobRepo::new(Rc::new(open_in_memory().unwrap()));
+        let payment_timing = job::PaymentTiming { month_offset: 0, rule: RecurrenceRule::monthly(MonthDay::Last), missing_day: MissingDayPolicy::Clamp };
+        let part_time_job = PartTimeJob::try_new(
+            "カフェ".to_string(),
+            payment_timing,
+            BusinessDayRoll::None,
+            NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+        )
+        .unwrap();
+        let part_time_job_id = job::store_part_time_job(part_time_job, Decimal::from(1100), &repo).unwrap();
+        let payment_date = Local.with_ymd_and_hms(2025, 4, 30, 0, 0, 0).unwrap();
+        let id = repo
+            .store_part_time_job_income(PartTimeJobIncome {
+                id: None,
+                part_time_job_id,
+                name: "カフェ".to_string(),
+                hourly_wage: Decimal::from(1100),
+                hour: Decimal::new(125, 1),
+                payment_date,
+            })
+            .unwrap();
+
+        let stored = repo.list_part_time_job_incomes_by_part_time_job_id(part_time_job_id, 2025, 4).unwrap();
+        assert_eq!(stored.len(), 1);
+        let stored = stored[0].clone();
+        assert_eq!((stored.id, stored.hour, stored.payment_date), (Some(id), Decimal::new(125, 1), payment_date));
+        assert!(repo.list_part_time_job_incomes_by_part_time_job_id(part_time_job_id, 2025, 5).unwrap().is_empty());
+
+        repo.update_part_time_job_income(stored.update(stored.name.clone(), Decimal::from(1150), Decimal::from(20), payment_date))
+            .unwrap();
+        let updated = repo.get_part_time_job_income_by_id(id).unwrap().unwrap();
+        assert_eq!((updated.hourly_wage, updated.hour), (Decimal::from(1150), Decimal::from(20)));
+        let incomes = repo
+            .list_incomes(&Local.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap(), &payment_date)
+            .unwrap();
+        assert_eq!(incomes.iter().map(|income| income.amount).collect::<Vec<_>>(), vec![Decimal::from(23000)]);
+    }
+
+    #[test]
+    fn round_trips_temporary_outcomes() {
+        use crate::finance::database::open_in_memory;
+
+        let repo = SqliteTemporaryOutcomeRepo::new(Rc::new(open_in_memory().unwrap()));
+        let date = Local.with_ymd_and_hms(2025, 4, 10, 0, 0, 0).unwrap();
+        let id = repo
+            .store_temporary_outcome(TemporaryOutcome { id: None, name: "車検".to_string(), amount: Decimal::new(8000050, 2), date })
+            .unwrap();
+
+        let (start_date, end_date) = (
+            Local.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 4, 30, 0, 0, 0).unwrap(),
+        );
+        let outcomes = repo.list_temporary_outcomes(&start_date, &end_date).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!((outcomes[0].id, outcomes[0].amount, outcomes[0].date), (Some(id), Decimal::new(8000050, 2), date));
+
+        // 翌月にずらすとその月の一覧から外れる
+        let moved = TemporaryOutcome { date: Local.with_ymd_and_hms(2025, 5, 10, 0, 0, 0).unwrap(), ..outcomes[0].clone() };
+        repo.update_temporary_outcome(moved.clone()).unwrap();
+        assert!(repo.list_temporary_outcomes(&start_date, &end_date).unwrap().is_empty());
+        assert_eq!(repo.get_temporary_outcome_by_id(id).unwrap().map(|outcome| outcome.date), Some(moved.date));
+        assert!(repo.get_temporary_outcome_by_id(id + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn round_trips_monthly_incomes() {
+        use crate::finance::database::open_in_memory;
+
+        let repo = SqliteMonthlyIncomeRepo::new(Rc::new(open_in_memory().unwrap()));
+        let template = MonthlyIncomeTemplate::try_new(
+            "仕送り".to_string(),
+            "50000".to_string(),
+            RecurrenceRule::monthly(MonthDay::NthWeekday(1, Weekday::Mon)),
+            MissingDayPolicy::Skip,
+            BusinessDayRoll::Next,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            Some(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()),
+        )
+        .unwrap();
+        let template_id = repo.store_monthly_template(MonthlyIncomeTemplate { account_id: Some(1), ..template }).unwrap();
+        let (start_date, end_date) = (
+            Local.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 4, 30, 0, 0, 0).unwrap(),
+        );
+        let templates = repo.list_monthly_templates(&start_date, &end_date).unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].payment_timing, RecurrenceRule::monthly(MonthDay::NthWeekday(1, Weekday::Mon)));
+        assert_eq!((templates[0].missing_day, templates[0].business_day_roll), (MissingDayPolicy::Skip, BusinessDayRoll::Next));
+        assert_eq!(templates[0].account_id, Some(1));
+        // 終わった後の月には出ない
+        let next_year = Local.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert!(repo.list_monthly_templates(&next_year, &next_year).unwrap().is_empty());
+
+        let payment_date = Local.with_ymd_and_hms(2025, 4, 7, 0, 0, 0).unwrap();
+        let id = repo
+            .store_monthly_entry(MonthlyIncome {
+                id: None,
+                template_id,
+                name: "仕送り".to_string(),
+                amount: Decimal::from(50000),
+                payment_date,
+                entry: PhantomData,
+            })
+            .unwrap();
+        let stored = repo.list_monthly_entries_by_template_id(template_id, &start_date, &end_date).unwrap();
+        assert_eq!(stored.iter().map(|income| (income.id, income.payment_date)).collect::<Vec<_>>(), vec![(Some(id), payment_date)]);
+    }
+}
@@ -1,16 +1,13 @@
 use super::income;
+use super::monthly_template;
 use super::outcome;
+use super::recurrence;
+use super::scenario;
 mod db_dummy;
-use db_dummy::{DummyMonthlyOutcomeRepo, DummyPartTimeJobRepo, DummyTemporaryOutcomeRepo};
+#[cfg(feature = "sqlite")]
+mod db_sqlite;
 
-pub fn get_part_time_job_repo() -> impl income::job::PartTimeJobRepo {
-    DummyPartTimeJobRepo::new()
-}
-
-pub fn get_monthly_outcome_repo() -> impl outcome::monthly_outcome::MonthlyOutcomeRepo {
-    DummyMonthlyOutcomeRepo::new()
-}
-
-pub fn get_temporary_outcome_repo() -> impl outcome::temporary_outcome::TemporaryOutcomeRepo {
-    DummyTemporaryOutcomeRepo::new()
-}
\ No newline at end of file
+pub use db_dummy::{DummyMonthlyIncomeRepo, DummyMonthlyOutcomeRepo, DummyPartTimeJobRepo, DummyScenarioRepo, DummyTemporaryOutcomeRepo};
+pub use db_dummy::snapshot_collections as snapshot_dummy_collections;
+#[cfg(feature = "sqlite")]
+pub use db_sqlite::{SqliteMonthlyIncomeRepo, SqliteMonthlyOutcomeRepo, SqlitePartTimeJobRepo, SqliteScenarioRepo, SqliteTemporaryOutcomeRepo};
@@ -0,0 +1,350 @@
+use chrono::{DateTime, Local};
+use rust_decimal::Decimal;
+use std::marker::PhantomData;
+
+use super::income::job::PartTimeJobRepo;
+use super::income::monthly_income::MonthlyIncomeRepo;
+use super::income::{Income, ToIncome};
+use super::outcome::monthly_outcome::MonthlyOutcomeRepo;
+use super::outcome::{Outcome, ToOutcome};
+use super::monthly_template::MonthlyTemplate;
+use super::recurrence::{MissingDayPolicy, RecurrenceRule};
+use super::source::{EntrySource, SourceKind};
+use crate::finance::detail::calendar::{BusinessCalendar, BusinessDayRoll};
+use crate::finance::error::FinanceError;
+use crate::finance::setting::AccountingPeriod;
+
+// 毎月の収入と支出のどちらか
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemplateKind {
+    Income,
+    Outcome,
+}
+
+// 計画に重ねる変更
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioChangeKind {
+    // 毎月の収入・支出の金額を増減する
+    AdjustMonthly { template_kind: TemplateKind, template_id: u64, delta: Decimal },
+    // 毎月の収入・支出をやめる
+    RemoveMonthly { template_kind: TemplateKind, template_id: u64 },
+    // 毎月の収入・支出を加える
+    // 支払日がない月と休日の扱いは毎月の収入・支出のテンプレートと同じ
+    AddMonthly {
+        template_kind: TemplateKind,
+        name: String,
+        amount: Decimal,
+        payment_timing: RecurrenceRule,
+        missing_day: MissingDayPolicy,
+        business_day_roll: BusinessDayRoll,
+    },
+    // アルバイトの時給と 1 回の入金分の勤務時間を決める (月払いなら 1 か月分)
+    ChangePartTimeJob { part_time_job_id: u64, hourly_wage: Decimal, hour: Decimal },
+    // アルバイトをやめる
+    RemovePartTimeJob { part_time_job_id: u64 },
+    // 開始日に一度だけ払う
+    AddOneOffOutcome { name: String, amount: Decimal },
+}
+
+// start_date 以降の収入と支出に効く変更
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioChange {
+    pub id: Option<u64>,
+    pub start_date: DateTime<Local>,
+    pub kind: ScenarioChangeKind,
+}
+
+// 実際のデータには書き込まずに計画を変えてみる「もしも」の計画
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Scenario {
+    pub id: Option<u64>,
+    pub name: String,
+    pub changes: Vec<ScenarioChange>,
+}
+
+impl Scenario {
+    pub fn try_new(name: String) -> Result<Self, FinanceError> {
+        if name.trim().is_empty() {
+            return Err(FinanceError::EmptyName);
+        }
+        Ok(Self {
+            id: None,
+            name: name.trim().to_string(),
+            changes: vec![],
+        })
+    }
+
+    // date の時点で効いている変更
+    fn active_changes<'a>(&'a self, date: &'a DateTime<Local>) -> impl Iterator<Item = &'a ScenarioChangeKind> + 'a {
+        self.changes
+            .iter()
+            .filter(move |change| change.start_date <= *date)
+            .map(|change| &change.kind)
+    }
+
+    // 変更後の毎月の収入・支出の金額 (やめたなら None)
+    fn apply_monthly(&self, kind: TemplateKind, id: u64, date: &DateTime<Local>, amount: Decimal) -> Option<Decimal> {
+        let mut amount = amount;
+        for change in self.active_changes(date) {
+            match change {
+                ScenarioChangeKind::RemoveMonthly { template_kind, template_id } if *template_kind == kind && *template_id == id => {
+                    return None;
+                }
+                ScenarioChangeKind::AdjustMonthly { template_kind, template_id, delta } if *template_kind == kind && *template_id == id => {
+                    amount += *delta;
+                }
+                _ => (),
+            }
+        }
+        Some(amount)
+    }
+
+    // 加えた毎月の収入・支出のその月の分
+    // to_entry で収入か支出を作る (名前, 金額, 日付, 元)
+    fn get_added_monthly<T: Clone>(
+        &self,
+        kind: TemplateKind,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+        to_entry: impl Fn(String, Decimal, DateTime<Local>, EntrySource) -> T,
+    ) -> Result<Vec<T>, anyhow::Error> {
+        let mut added = Vec::new();
+        for change in &self.changes {
+            let ScenarioChangeKind::AddMonthly { template_kind, name, amount, payment_timing, missing_day, business_day_roll } = &change.kind else {
+                continue;
+            };
+            if *template_kind != kind {
+                continue;
+            }
+            // 保存しない仮のテンプレートとして支払日を求める
+            let template = MonthlyTemplate::<T> {
+                id: None,
+                name: name.clone(),
+                amount: *amount,
+                payment_timing: *payment_timing,
+                missing_day: *missing_day,
+                business_day_roll: *business_day_roll,
+                start_date: change.start_date,
+                end_date: None,
+                account_id: None,
+                entry: PhantomData,
+            };
+            for date in template.get_payment_dates(year, month, period, calendar)? {
+                added.push(to_entry(name.clone(), *amount, date, EntrySource::new(SourceKind::Scenario, change.id)));
+            }
+        }
+        Ok(added)
+    }
+
+    pub fn project_monthly_incomes(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+        repo: &dyn MonthlyIncomeRepo,
+    ) -> Result<Vec<Income>, anyhow::Error> {
+        let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+        let mut incomes = Vec::new();
+        for template in repo.list_monthly_templates(&start_date, &end_date)? {
+            for income in template.project_entries(year, month, period, calendar, repo)? {
+                let mut income = income.to_income();
+                if let Some(amount) = self.apply_monthly(TemplateKind::Income, template.id.unwrap(), &income.date, income.amount) {
+                    income.amount = amount;
+                    incomes.push(income);
+                }
+            }
+        }
+        incomes.extend(self.get_added_monthly(TemplateKind::Income, year, month, period, calendar, |name, amount, date, source| {
+            Income { name, amount, date, source }
+        })?);
+        Ok(incomes)
+    }
+
+    pub fn project_monthly_outcomes(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+        repo: &dyn MonthlyOutcomeRepo,
+    ) -> Result<Vec<Outcome>, anyhow::Error> {
+        let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+        let mut outcomes = Vec::new();
+        for template in repo.list_monthly_templates(&start_date, &end_date)? {
+            for outcome in template.project_entries(year, month, period, calendar, repo)? {
+                let mut outcome = outcome.to_outcome();
+                if let Some(amount) = self.apply_monthly(TemplateKind::Outcome, template.id.unwrap(), &outcome.date, outcome.amount) {
+                    outcome.amount = amount;
+                    outcomes.push(outcome);
+                }
+            }
+        }
+        outcomes.extend(self.get_added_monthly(TemplateKind::Outcome, year, month, period, calendar, |name, amount, date, source| {
+            Outcome { name, date, amount, source }
+        })?);
+        Ok(outcomes)
+    }
+
+    pub fn project_part_time_job_incomes(
+        &self,
+        year: i32,
+        month: u32,
+        period: &AccountingPeriod,
+        calendar: &BusinessCalendar,
+        repo: &dyn PartTimeJobRepo,
+    ) -> Result<Vec<Income>, anyhow::Error> {
+        let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+        let mut incomes = Vec::new();
+        for job in repo.list_part_time_jobs(&start_date, &end_date)? {
+            'incomes: for mut income in job.project_part_time_job_incomes(year, month, calendar, repo)? {
+                for change in self.active_changes(&income.payment_date) {
+                    match change {
+                        ScenarioChangeKind::RemovePartTimeJob { part_time_job_id } if *part_time_job_id == income.part_time_job_id => {
+                            continue 'incomes;
+                        }
+                        // 時間は 1 回の入金分
+                        ScenarioChangeKind::ChangePartTimeJob { part_time_job_id, hourly_wage, hour } if *part_time_job_id == income.part_time_job_id => {
+                            income.hourly_wage = *hourly_wage;
+                            income.hour = *hour;
+                        }
+                        _ => (),
+                    }
+                }
+                incomes.push(income.to_income());
+            }
+        }
+        Ok(incomes)
+    }
+
+    pub fn get_one_off_outcomes(&self, year: i32, month: u32, period: &AccountingPeriod) -> Result<Vec<Outcome>, anyhow::Error> {
+        let (start_date, end_date) = period.get_opening_and_closing_date(year, month)?;
+        Ok(self
+            .changes
+            .iter()
+            .filter(|change| start_date <= change.start_date && change.start_date <= end_date)
+            .filter_map(|change| match &change.kind {
+                ScenarioChangeKind::AddOneOffOutcome { name, amount } => Some(Outcome {
+                    name: name.clone(),
+                    date: change.start_date,
+                    amount: *amount,
+                    source: EntrySource::new(SourceKind::Scenario, change.id),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+pub trait ScenarioRepo {
+    fn list_scenarios(&self) -> Result<Vec<Scenario>, anyhow::Error>;
+    fn get_scenario_by_id(&self, id: u64) -> Result<Option<Scenario>, anyhow::Error>;
+    fn store_scenario(&self, scenario: Scenario) -> Result<u64, anyhow::Error>;
+    fn delete_scenario(&self, id: u64) -> Result<(), anyhow::Error>;
+    fn store_scenario_change(&self, scenario_id: u64, change: ScenarioChange) -> Result<u64, anyhow::Error>;
+    fn delete_scenario_change(&self, id: u64) -> Result<(), anyhow::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::plan::recurrence::MonthDay;
+    use crate::finance::plan::repository::{DummyMonthlyIncomeRepo, DummyMonthlyOutcomeRepo};
+    use chrono::TimeZone;
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    fn change(start_date: DateTime<Local>, kind: ScenarioChangeKind) -> ScenarioChange {
+        ScenarioChange { id: None, start_date, kind }
+    }
+
+    #[test]
+    fn applies_changes_from_their_start_date() {
+        let scenario = Scenario {
+            changes: vec![
+                change(date(2025, 4, 1), ScenarioChangeKind::AdjustMonthly { template_kind: TemplateKind::Outcome, template_id: 1, delta: Decimal::from(10000) }),
+                change(date(2025, 6, 1), ScenarioChangeKind::RemoveMonthly { template_kind: TemplateKind::Outcome, template_id: 2 }),
+                change(date(2025, 5, 1), ScenarioChangeKind::AdjustMonthly { template_kind: TemplateKind::Income, template_id: 1, delta: Decimal::from(-5000) }),
+            ],
+            ..Scenario::try_new("引っ越し".to_string()).unwrap()
+        };
+        let rent = Decimal::from(80000);
+        assert_eq!(scenario.apply_monthly(TemplateKind::Outcome, 1, &date(2025, 3, 31), rent), Some(rent));
+        assert_eq!(scenario.apply_monthly(TemplateKind::Outcome, 1, &date(2025, 4, 1), rent), Some(Decimal::from(90000)));
+        // 収入のテンプレートの変更は同じ ID の支出には効かない
+        assert_eq!(scenario.apply_monthly(TemplateKind::Outcome, 1, &date(2025, 5, 1), rent), Some(Decimal::from(90000)));
+        assert_eq!(scenario.apply_monthly(TemplateKind::Outcome, 2, &date(2025, 5, 31), rent), Some(rent));
+        assert_eq!(scenario.apply_monthly(TemplateKind::Outcome, 2, &date(2025, 6, 1), rent), None);
+        assert!(Scenario::try_new(" ".to_string()).is_err());
+    }
+
+    #[test]
+    fn adds_monthly_and_one_off_outcomes() {
+        let scenario = Scenario {
+            changes: vec![
+                change(date(2025, 4, 1), ScenarioChangeKind::AddMonthly {
+                    template_kind: TemplateKind::Outcome,
+                    name: "ジム".to_string(),
+                    amount: Decimal::from(8000),
+                    payment_timing: RecurrenceRule::monthly(MonthDay::Day(10)),
+                    missing_day: MissingDayPolicy::Clamp,
+                    business_day_roll: BusinessDayRoll::None,
+                }),
+                change(date(2025, 4, 20), ScenarioChangeKind::AddOneOffOutcome { name: "引っ越し代".to_string(), amount: Decimal::from(120000) }),
+            ],
+            ..Scenario::default()
+        };
+        let (period, calendar, repo) = (AccountingPeriod::default(), BusinessCalendar::japanese(), DummyMonthlyOutcomeRepo::new());
+        let base = Scenario::default().project_monthly_outcomes(2025, 4, &period, &calendar, &repo).unwrap();
+        let march = scenario.project_monthly_outcomes(2025, 3, &period, &calendar, &repo).unwrap();
+        let april = scenario.project_monthly_outcomes(2025, 4, &period, &calendar, &repo).unwrap();
+        assert!(!march.iter().any(|outcome| outcome.name == "ジム"));
+        assert_eq!(april.len(), base.len() + 1);
+        let gym = april.iter().find(|outcome| outcome.name == "ジム").unwrap();
+        assert_eq!((gym.date, gym.amount), (date(2025, 4, 10), Decimal::from(8000)));
+
+        assert!(scenario.get_one_off_outcomes(2025, 3, &period).unwrap().is_empty());
+        let one_off = scenario.get_one_off_outcomes(2025, 4, &period).unwrap();
+        assert_eq!(one_off.len(), 1);
+        assert_eq!((one_off[0].date, one_off[0].amount), (date(2025, 4, 20), Decimal::from(120000)));
+    }
+
+    #[test]
+    fn added_monthly_follows_its_missing_day_and_holiday_roll() {
+        let add = |payment_timing, missing_day, business_day_roll| {
+            change(date(2025, 4, 1), ScenarioChangeKind::AddMonthly {
+                template_kind: TemplateKind::Income,
+                name: "副業".to_string(),
+                amount: Decimal::from(30000),
+                payment_timing,
+                missing_day,
+                business_day_roll,
+            })
+        };
+        let scenario = Scenario {
+            changes: vec![
+                add(RecurrenceRule::monthly(MonthDay::Day(25)), MissingDayPolicy::Clamp, BusinessDayRoll::Previous),
+                add(RecurrenceRule::monthly(MonthDay::Day(31)), MissingDayPolicy::Skip, BusinessDayRoll::None),
+            ],
+            ..Scenario::default()
+        };
+        let (period, calendar, repo) = (AccountingPeriod::default(), BusinessCalendar::japanese(), DummyMonthlyIncomeRepo::new());
+        let added = |month| -> Vec<DateTime<Local>> {
+            scenario
+                .project_monthly_incomes(2025, month, &period, &calendar, &repo)
+                .unwrap()
+                .into_iter()
+                .filter(|income| income.name == "副業")
+                .map(|income| income.date)
+                .collect()
+        };
+        // 2025年5月25日は日曜日なので前営業日の 23日、31日は土曜日だがずらさない
+        assert_eq!(added(5), vec![date(2025, 5, 23), date(2025, 5, 31)]);
+        // 4月に 31日はないので飛ばす
+        assert_eq!(added(4), vec![date(2025, 4, 25)]);
+    }
+}
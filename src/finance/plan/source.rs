@@ -0,0 +1,40 @@
+// 予測の収入・支出を作ったものの種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceKind {
+    PartTimeJob,
+    MonthlyIncome,
+    MonthlyOutcome,
+    TemporaryOutcome,
+    Budget,
+    CardStatement,
+    // シナリオで加えた分
+    Scenario,
+}
+
+impl SourceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SourceKind::PartTimeJob => "アルバイト",
+            SourceKind::MonthlyIncome => "毎月の収入",
+            SourceKind::MonthlyOutcome => "毎月の支出",
+            SourceKind::TemporaryOutcome => "臨時支出",
+            SourceKind::Budget => "予算",
+            SourceKind::CardStatement => "カードの引き落とし",
+            SourceKind::Scenario => "シナリオ",
+        }
+    }
+}
+
+// 予測の収入・支出の出どころ
+// id はアルバイト、テンプレート、予算、カードの口座、シナリオの変更などの ID
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntrySource {
+    pub kind: SourceKind,
+    pub id: Option<u64>,
+}
+
+impl EntrySource {
+    pub fn new(kind: SourceKind, id: Option<u64>) -> Self {
+        Self { kind, id }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod period;
+pub mod repository;
+pub mod settings;
+
+pub use period::*;
+pub use repository::*;
+pub use settings::*;
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
+use crate::finance::error::FinanceError;
+use crate::util::{get_clamped_day, get_next_ym, get_prev_ym};
+
+// 家計簿の 1 か月の区切り
+// 開始日が 25 日なら「2月」は 1月25日から 2月24日まで
+// 開始日がない月 (31日始まりの 2月など) は月末から始まる
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccountingPeriod {
+    pub start_day: u32,
+}
+
+impl Default for AccountingPeriod {
+    fn default() -> Self {
+        Self { start_day: 1 }
+    }
+}
+
+impl AccountingPeriod {
+    pub fn try_new(start_day: String) -> Result<Self, FinanceError> {
+        let day = start_day
+            .trim()
+            .parse()
+            .ok()
+            .filter(|day| (1..=31).contains(day))
+            .ok_or(FinanceError::InvalidPeriodStartDay(start_day))?;
+        Ok(Self { start_day: day })
+    }
+
+    // year 年 month 月分の始まり
+    fn get_opening_date(&self, year: i32, month: u32) -> Result<DateTime<Local>, anyhow::Error> {
+        let (year, month) = if self.start_day == 1 { (year, month) } else { get_prev_ym((year, month)) };
+        let day = get_clamped_day(year, month, self.start_day)?;
+        Local.with_ymd_and_hms(year, month, day, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-{:02}", year, month, day))
+    }
+
+    // year 年 month 月分の始まりと終わり
+    // 終わりは翌月分の始まりの 1 秒前
+    pub fn get_opening_and_closing_date(
+        &self,
+        year: i32,
+        month: u32,
+    ) -> Result<(DateTime<Local>, DateTime<Local>), anyhow::Error> {
+        let (next_year, next_month) = get_next_ym((year, month));
+        let opening_date = self.get_opening_date(year, month)?;
+        let closing_date = self.get_opening_date(next_year, next_month)? - Duration::seconds(1);
+        Ok((opening_date, closing_date))
+    }
+
+    // date が何年何月分に含まれるか
+    pub fn get_year_month(&self, date: &DateTime<Local>) -> (i32, u32) {
+        let ym = (date.year(), date.month());
+        if self.start_day == 1 {
+            return ym;
+        }
+        let start_day = get_clamped_day(ym.0, ym.1, self.start_day).unwrap_or(self.start_day);
+        if date.day() >= start_day { get_next_ym(ym) } else { ym }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, hour, 0, 0).single().unwrap()
+    }
+
+    #[test]
+    fn calendar_month_by_default() {
+        let period = AccountingPeriod::default();
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(2025, 2).unwrap();
+        assert_eq!(opening_date, datetime(2025, 2, 1, 0));
+        assert_eq!(closing_date, datetime(2025, 2, 28, 23) + Duration::seconds(59 * 60 + 59));
+        assert_eq!(period.get_year_month(&datetime(2025, 2, 28, 12)), (2025, 2));
+    }
+
+    #[test]
+    fn starts_on_payday() {
+        let period = AccountingPeriod { start_day: 25 };
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(2025, 1).unwrap();
+        assert_eq!(opening_date, datetime(2024, 12, 25, 0));
+        assert_eq!(closing_date + Duration::seconds(1), datetime(2025, 1, 25, 0));
+        assert_eq!(period.get_year_month(&datetime(2024, 12, 25, 0)), (2025, 1));
+        assert_eq!(period.get_year_month(&datetime(2025, 1, 24, 23)), (2025, 1));
+        assert_eq!(period.get_year_month(&datetime(2025, 1, 25, 0)), (2025, 2));
+    }
+
+    #[test]
+    fn clamps_start_day_to_month_end() {
+        let period = AccountingPeriod { start_day: 31 };
+        let (opening_date, closing_date) = period.get_opening_and_closing_date(2025, 3).unwrap();
+        assert_eq!(opening_date, datetime(2025, 2, 28, 0));
+        assert_eq!(closing_date + Duration::seconds(1), datetime(2025, 3, 31, 0));
+        assert_eq!(period.get_year_month(&datetime(2025, 2, 28, 0)), (2025, 3));
+        assert_eq!(period.get_year_month(&datetime(2025, 2, 27, 0)), (2025, 2));
+    }
+
+    #[test]
+    fn periods_cover_every_day_once() {
+        let period = AccountingPeriod { start_day: 30 };
+        let mut ym = (2024, 1);
+        let mut expected_opening = period.get_opening_and_closing_date(ym.0, ym.1).unwrap().0;
+        while ym <= (2025, 12) {
+            let (opening_date, closing_date) = period.get_opening_and_closing_date(ym.0, ym.1).unwrap();
+            assert_eq!(opening_date, expected_opening);
+            assert_eq!(period.get_year_month(&opening_date), ym);
+            assert_eq!(period.get_year_month(&closing_date), ym);
+            expected_opening = closing_date + Duration::seconds(1);
+            ym = get_next_ym(ym);
+        }
+    }
+
+    #[test]
+    fn rejects_days_outside_month() {
+        assert!(AccountingPeriod::try_new("0".to_string()).is_err());
+        assert!(AccountingPeriod::try_new("32".to_string()).is_err());
+        assert_eq!(AccountingPeriod::try_new(" 25 ".to_string()).unwrap().start_day, 25);
+    }
+}
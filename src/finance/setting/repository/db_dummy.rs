@@ -0,0 +1,33 @@
+use std::cell::RefCell;
+
+use crate::finance::setting::settings::*;
+
+pub struct DummySettingRepo;
+
+thread_local! {
+    static SETTINGS: RefCell<Settings> = RefCell::new(Settings::default());
+}
+
+impl SettingRepo for DummySettingRepo {
+    fn get_settings(&self) -> Result<Settings, anyhow::Error> {
+        Ok(SETTINGS.with(|settings| settings.borrow().clone()))
+    }
+    fn store_settings(&self, settings: &Settings) -> Result<(), anyhow::Error> {
+        SETTINGS.with(|current| *current.borrow_mut() = settings.clone());
+        Ok(())
+    }
+}
+
+impl DummySettingRepo {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// 現在の設定を退避し、それを書き戻す関数を返す
+pub fn snapshot_collections() -> Box<dyn FnOnce()> {
+    let settings = SETTINGS.with(|settings| settings.borrow().clone());
+    Box::new(move || {
+        SETTINGS.with(|current| *current.borrow_mut() = settings);
+    })
+}
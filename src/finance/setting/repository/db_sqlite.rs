@@ -0,0 +1,112 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use rust_decimal::Decimal;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::finance::setting::period::*;
+use crate::finance::setting::settings::*;
+
+// 設定はキーと値の組で保存する
+// 保存されていないキーは既定値を使う
+const CURRENCY_KEY: &str = "currency";
+const PERIOD_START_DAY_KEY: &str = "period_start_day";
+const FORECAST_HORIZON_MONTHS_KEY: &str = "forecast_horizon_months";
+const DEFAULT_ACCOUNT_ID_KEY: &str = "default_account_id";
+const LOCALE_KEY: &str = "locale";
+const LOW_BALANCE_THRESHOLD_KEY: &str = "low_balance_threshold";
+
+pub struct SqliteSettingRepo {
+    conn: Rc<Connection>,
+}
+
+impl SqliteSettingRepo {
+    fn get_value(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        Ok(self.conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()?)
+    }
+
+    fn set_value(&self, key: &str, value: String) -> Result<(), anyhow::Error> {
+        self.conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    fn decode_value<T>(&self, key: &str, default: T, decode: impl Fn(&str) -> Option<T>) -> Result<T, anyhow::Error> {
+        match self.get_value(key)? {
+            Some(value) => decode(&value).ok_or_else(|| anyhow::anyhow!("Invalid setting {}: {}", key, value)),
+            None => Ok(default),
+        }
+    }
+}
+
+impl SettingRepo for SqliteSettingRepo {
+    fn get_settings(&self) -> Result<Settings, anyhow::Error> {
+        let default = Settings::default();
+        Ok(Settings {
+            currency: self.decode_value(CURRENCY_KEY, default.currency, Currency::from_code)?,
+            period: AccountingPeriod {
+                start_day: self.decode_value(PERIOD_START_DAY_KEY, default.period.start_day, |value| value.parse().ok())?,
+            },
+            forecast_horizon_months: self.decode_value(
+                FORECAST_HORIZON_MONTHS_KEY,
+                default.forecast_horizon_months,
+                |value| value.parse().ok(),
+            )?,
+            default_account_id: self.decode_value(DEFAULT_ACCOUNT_ID_KEY, default.default_account_id, |value| value.parse().ok())?,
+            locale: self.decode_value(LOCALE_KEY, default.locale, Locale::from_code)?,
+            low_balance_threshold: self.decode_value(
+                LOW_BALANCE_THRESHOLD_KEY,
+                default.low_balance_threshold,
+                |value| Decimal::from_str(value).ok(),
+            )?,
+        })
+    }
+    fn store_settings(&self, settings: &Settings) -> Result<(), anyhow::Error> {
+        self.set_value(CURRENCY_KEY, settings.currency.code().to_string())?;
+        self.set_value(PERIOD_START_DAY_KEY, settings.period.start_day.to_string())?;
+        self.set_value(FORECAST_HORIZON_MONTHS_KEY, settings.forecast_horizon_months.to_string())?;
+        self.set_value(DEFAULT_ACCOUNT_ID_KEY, settings.default_account_id.to_string())?;
+        self.set_value(LOCALE_KEY, settings.locale.code().to_string())?;
+        self.set_value(LOW_BALANCE_THRESHOLD_KEY, settings.low_balance_threshold.to_string())
+    }
+}
+
+impl SqliteSettingRepo {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::database::open_in_memory;
+
+    #[test]
+    fn round_trips_settings() {
+        let repo = SqliteSettingRepo::new(Rc::new(open_in_memory().unwrap()));
+        assert_eq!(repo.get_settings().unwrap(), Settings::default());
+
+        let settings = Settings {
+            currency: Currency::Eur,
+            period: AccountingPeriod { start_day: 25 },
+            forecast_horizon_months: 6,
+            default_account_id: 3,
+            locale: Locale::En,
+            low_balance_threshold: Decimal::from_str("1500.5").unwrap(),
+        };
+        repo.store_settings(&settings).unwrap();
+        assert_eq!(repo.get_settings().unwrap(), settings);
+    }
+
+    #[test]
+    fn rejects_broken_values() {
+        let repo = SqliteSettingRepo::new(Rc::new(open_in_memory().unwrap()));
+        repo.set_value(LOCALE_KEY, "fr".to_string()).unwrap();
+        assert!(repo.get_settings().is_err());
+    }
+}
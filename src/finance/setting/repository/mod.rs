@@ -0,0 +1,8 @@
+mod db_dummy;
+#[cfg(feature = "sqlite")]
+mod db_sqlite;
+
+pub use db_dummy::DummySettingRepo;
+pub use db_dummy::snapshot_collections as snapshot_dummy_collections;
+#[cfg(feature = "sqlite")]
+pub use db_sqlite::SqliteSettingRepo;
@@ -0,0 +1,231 @@
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::prelude::*;
+
+use super::period::AccountingPeriod;
+use crate::finance::detail::account::DEFAULT_ACCOUNT_ID;
+use crate::finance::error::FinanceError;
+
+// 金額の表示に使う通貨
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Currency {
+    Jpy,
+    Usd,
+    Eur,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 3] = [Currency::Jpy, Currency::Usd, Currency::Eur];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Currency::Jpy => "円 (JPY)",
+            Currency::Usd => "ドル (USD)",
+            Currency::Eur => "ユーロ (EUR)",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Jpy => "JPY",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|currency| currency.code() == code)
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Jpy => "¥",
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+        }
+    }
+
+    // 円は小数点以下を持たない
+    fn decimal_places(&self) -> u32 {
+        match self {
+            Currency::Jpy => 0,
+            Currency::Usd | Currency::Eur => 2,
+        }
+    }
+
+    // ¥1,234 や -$1,234.50
+    pub fn format(&self, amount: &Decimal) -> String {
+        let rounded = amount.round_dp(self.decimal_places()).abs();
+        let text = format!("{:.*}", self.decimal_places() as usize, rounded);
+        let (integer, fraction) = match text.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (text.as_str(), None),
+        };
+        let mut grouped = String::new();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        let sign = if amount.is_sign_negative() && !rounded.is_zero() { "-" } else { "" };
+        match fraction {
+            Some(fraction) => format!("{}{}{}.{}", sign, self.symbol(), grouped, fraction),
+            None => format!("{}{}{}", sign, self.symbol(), grouped),
+        }
+    }
+}
+
+// 日付の表示に使う言語
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    Ja,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Ja, Locale::En];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::Ja => "日本語",
+            Locale::En => "English",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Ja => "ja",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|locale| locale.code() == code)
+    }
+
+    // 2025/01/25(土) や Sat, Jan 25, 2025
+    pub fn format_date(&self, date: &NaiveDate) -> String {
+        match self {
+            Locale::Ja => {
+                const WEEKDAYS: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+                let weekday = WEEKDAYS[date.weekday().num_days_from_monday() as usize];
+                format!("{}({})", date.format("%Y/%m/%d"), weekday)
+            }
+            Locale::En => date.format("%a, %b %d, %Y").to_string(),
+        }
+    }
+}
+
+// 予測する期間は 1 か月から 10 年まで
+const MAX_FORECAST_HORIZON_MONTHS: u32 = 120;
+
+// 利用者ごとの設定
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub currency: Currency,
+    pub period: AccountingPeriod,
+    // 何か月分の残高を予測するか
+    pub forecast_horizon_months: u32,
+    // 入力フォームで最初に選ばれている口座
+    pub default_account_id: u64,
+    pub locale: Locale,
+    // 残高がこれを下回る日を警告する
+    pub low_balance_threshold: Decimal,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            currency: Currency::Jpy,
+            period: AccountingPeriod::default(),
+            forecast_horizon_months: 24,
+            default_account_id: DEFAULT_ACCOUNT_ID,
+            locale: Locale::Ja,
+            low_balance_threshold: Decimal::ZERO,
+        }
+    }
+}
+
+impl Settings {
+    pub fn try_new(
+        currency: Currency,
+        period_start_day: String,
+        forecast_horizon_months: String,
+        default_account_id: u64,
+        locale: Locale,
+        low_balance_threshold: String,
+    ) -> Result<Self, FinanceError> {
+        let period = AccountingPeriod::try_new(period_start_day)?;
+        let forecast_horizon_months = forecast_horizon_months
+            .trim()
+            .parse()
+            .ok()
+            .filter(|months| (1..=MAX_FORECAST_HORIZON_MONTHS).contains(months))
+            .ok_or(FinanceError::InvalidForecastHorizon(forecast_horizon_months))?;
+        let low_balance_threshold = Decimal::from_str(low_balance_threshold.trim())
+            .map_err(|_| FinanceError::InvalidAmount(low_balance_threshold))?;
+        Ok(Self {
+            currency,
+            period,
+            forecast_horizon_months,
+            default_account_id,
+            locale,
+            low_balance_threshold,
+        })
+    }
+
+    pub fn is_low_balance(&self, balance: &Decimal) -> bool {
+        *balance < self.low_balance_threshold
+    }
+}
+
+pub trait SettingRepo {
+    fn get_settings(&self) -> Result<Settings, anyhow::Error>;
+    fn store_settings(&self, settings: &Settings) -> Result<(), anyhow::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_amounts_in_currency() {
+        let amount = Decimal::from_str("1234567.891").unwrap();
+        assert_eq!(Currency::Jpy.format(&amount), "¥1,234,568");
+        assert_eq!(Currency::Usd.format(&amount), "$1,234,567.89");
+        assert_eq!(Currency::Eur.format(&-amount), "-€1,234,567.89");
+        assert_eq!(Currency::Jpy.format(&Decimal::from(999)), "¥999");
+        assert_eq!(Currency::Jpy.format(&Decimal::from_str("-0.4").unwrap()), "¥0");
+    }
+
+    #[test]
+    fn formats_dates_in_locale() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 25).unwrap();
+        assert_eq!(Locale::Ja.format_date(&date), "2025/01/25(土)");
+        assert_eq!(Locale::En.format_date(&date), "Sat, Jan 25, 2025");
+    }
+
+    #[test]
+    fn validates_settings_input() {
+        let settings = Settings::try_new(
+            Currency::Usd,
+            "25".to_string(),
+            " 12 ".to_string(),
+            2,
+            Locale::En,
+            "30000".to_string(),
+        )
+        .unwrap();
+        assert_eq!(settings.period.start_day, 25);
+        assert_eq!(settings.forecast_horizon_months, 12);
+        assert!(settings.is_low_balance(&Decimal::from(29999)));
+        assert!(!settings.is_low_balance(&Decimal::from(30000)));
+
+        let input = |horizon: &str, threshold: &str| {
+            Settings::try_new(Currency::Jpy, "1".to_string(), horizon.to_string(), 1, Locale::Ja, threshold.to_string())
+        };
+        assert!(matches!(input("0", "0"), Err(FinanceError::InvalidForecastHorizon(_))));
+        assert!(matches!(input("121", "0"), Err(FinanceError::InvalidForecastHorizon(_))));
+        assert!(matches!(input("24", "たくさん"), Err(FinanceError::InvalidAmount(_))));
+    }
+}
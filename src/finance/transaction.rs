@@ -0,0 +1,196 @@
+#[cfg(feature = "sqlite")]
+use rusqlite::Connection;
+use std::cell::RefCell;
+#[cfg(feature = "sqlite")]
+use std::rc::Rc;
+
+use crate::finance::detail::repository as detail_repository;
+use crate::finance::plan::repository as plan_repository;
+use crate::finance::setting::repository as setting_repository;
+
+// 複数のリポジトリ操作をまとめてコミット/ロールバックする
+// 入れ子で呼ばれた場合は内側の単位だけを取り消せる
+pub trait UnitOfWork {
+    fn begin(&self) -> Result<(), anyhow::Error>;
+    fn commit(&self) -> Result<(), anyhow::Error>;
+    fn rollback(&self) -> Result<(), anyhow::Error>;
+}
+
+// 失敗時のエラーはそのまま返す
+// ロールバック自体に失敗した場合は、元のエラーを含めたストレージエラーにする
+pub fn transaction<T, E>(
+    unit_of_work: &dyn UnitOfWork,
+    f: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E>
+where
+    E: From<anyhow::Error> + std::fmt::Display,
+{
+    unit_of_work.begin()?;
+    match f() {
+        Ok(value) => {
+            unit_of_work.commit()?;
+            Ok(value)
+        }
+        Err(e) => match unit_of_work.rollback() {
+            Ok(_) => Err(e),
+            Err(rollback_error) => Err(E::from(rollback_error.context(format!("Rollback failed after: {}", e)))),
+        },
+    }
+}
+
+// 同じ接続を共有するリポジトリはすべてこのセーブポイントに参加する
+#[cfg(feature = "sqlite")]
+pub struct SqliteUnitOfWork {
+    conn: Rc<Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl UnitOfWork for SqliteUnitOfWork {
+    fn begin(&self) -> Result<(), anyhow::Error> {
+        self.conn.execute_batch("SAVEPOINT unit_of_work")?;
+        Ok(())
+    }
+    fn commit(&self) -> Result<(), anyhow::Error> {
+        self.conn.execute_batch("RELEASE unit_of_work")?;
+        Ok(())
+    }
+    fn rollback(&self) -> Result<(), anyhow::Error> {
+        self.conn.execute_batch("ROLLBACK TO unit_of_work; RELEASE unit_of_work")?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteUnitOfWork {
+    pub fn new(conn: Rc<Connection>) -> Self {
+        Self { conn }
+    }
+}
+
+// 呼ぶと退避したコレクションを書き戻す
+type Snapshot = Box<dyn FnOnce()>;
+
+// begin 時点のコレクションを退避しておき、ロールバックで書き戻す
+pub struct DummyUnitOfWork {
+    snapshots: RefCell<Vec<Vec<Snapshot>>>,
+}
+
+impl UnitOfWork for DummyUnitOfWork {
+    fn begin(&self) -> Result<(), anyhow::Error> {
+        self.snapshots.borrow_mut().push(vec![
+            detail_repository::snapshot_dummy_collections(),
+            plan_repository::snapshot_dummy_collections(),
+            setting_repository::snapshot_dummy_collections(),
+        ]);
+        Ok(())
+    }
+    fn commit(&self) -> Result<(), anyhow::Error> {
+        self.snapshots
+            .borrow_mut()
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("No transaction to commit"))?;
+        Ok(())
+    }
+    fn rollback(&self) -> Result<(), anyhow::Error> {
+        let restores = self.snapshots
+            .borrow_mut()
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("No transaction to roll back"))?;
+        for restore in restores {
+            restore();
+        }
+        Ok(())
+    }
+}
+
+impl DummyUnitOfWork {
+    pub fn new() -> Self {
+        Self { snapshots: RefCell::new(Vec::new()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finance::detail::{DummyIncomeRepo, Income, IncomeRepo};
+    use chrono::{DateTime, Local, TimeZone};
+    use rust_decimal::Decimal;
+
+    fn date(day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 4, day, 0, 0, 0).unwrap()
+    }
+
+    fn income(name: &str, day: u32) -> Income {
+        Income::new(name.to_string(), Decimal::from(1000), date(day), 1)
+    }
+
+    fn names(repo: &dyn IncomeRepo) -> Vec<String> {
+        let mut names: Vec<String> = repo
+            .list(&date(1), &date(30))
+            .unwrap()
+            .into_iter()
+            .map(|income| income.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    // 書き込んでから失敗すると、書き込む前に戻る
+    fn rolls_back_on_error(unit_of_work: &dyn UnitOfWork, repo: &dyn IncomeRepo) {
+        repo.store(income("既存", 1)).unwrap();
+        let before = names(repo);
+        let result: Result<(), anyhow::Error> = transaction(unit_of_work, || {
+            let id = repo.store(income("追加", 2))?;
+            repo.delete_by_id(id - 1)?;
+            Err(anyhow::anyhow!("failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(names(repo), before);
+    }
+
+    // 内側の失敗は内側の書き込みだけを取り消す
+    fn rolls_back_only_the_nested_unit(unit_of_work: &dyn UnitOfWork, repo: &dyn IncomeRepo) {
+        let result: Result<(), anyhow::Error> = transaction(unit_of_work, || {
+            repo.store(income("外側", 1))?;
+            let nested: Result<(), anyhow::Error> = transaction(unit_of_work, || {
+                repo.store(income("内側", 2))?;
+                Err(anyhow::anyhow!("failed"))
+            });
+            assert!(nested.is_err());
+            repo.store(income("外側の続き", 3))?;
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(names(repo), vec!["外側".to_string(), "外側の続き".to_string()]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn sqlite() -> (SqliteUnitOfWork, crate::finance::detail::SqliteIncomeRepo) {
+        let conn = Rc::new(crate::finance::database::open_in_memory().unwrap());
+        (SqliteUnitOfWork::new(conn.clone()), crate::finance::detail::SqliteIncomeRepo::new(conn))
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_rolls_back_on_error() {
+        let (unit_of_work, repo) = sqlite();
+        rolls_back_on_error(&unit_of_work, &repo);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_rolls_back_only_the_nested_unit() {
+        let (unit_of_work, repo) = sqlite();
+        rolls_back_only_the_nested_unit(&unit_of_work, &repo);
+    }
+
+    #[test]
+    fn dummy_rolls_back_on_error() {
+        rolls_back_on_error(&DummyUnitOfWork::new(), &DummyIncomeRepo::new());
+    }
+
+    #[test]
+    fn dummy_rolls_back_only_the_nested_unit() {
+        rolls_back_only_the_nested_unit(&DummyUnitOfWork::new(), &DummyIncomeRepo::new());
+    }
+}
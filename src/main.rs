@@ -2,7 +2,9 @@ use dioxus::prelude::*;
 use dioxus::logger::tracing::Level;
 
 use components::Navbar;
-use views::{Home, Detail, Plan};
+use views::{Home, Detail, Plan, Settings};
+use finance::api::plan::create_current_month_plans;
+use finance::context::{Backend, RepoContext};
 
 mod components;
 mod views;
@@ -15,10 +17,13 @@ enum Route {
     #[layout(Navbar)]
     #[route("/")]
     Home {},
-    #[route("/detail")]
-    Detail {},
-    #[route("/plan")]
-    Plan {},
+    // focus は移動した先で目立たせる要素の id
+    #[route("/detail?:focus")]
+    Detail { focus: String },
+    #[route("/plan?:focus")]
+    Plan { focus: String },
+    #[route("/settings")]
+    Settings {},
 }
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
@@ -27,11 +32,18 @@ const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
 fn main() {
     dioxus::logger::init(Level::DEBUG).expect("failed to initialize logger");
-    dioxus::launch(App);
+    let backend = Backend::from_args(std::env::args().skip(1)).expect("failed to parse arguments");
+    dioxus::LaunchBuilder::new()
+        .with_context(backend)
+        .launch(App);
 }
 
 #[component]
 fn App() -> Element {
+    let backend = use_context::<Backend>();
+    let ctx = use_context_provider(|| Signal::new(RepoContext::open(&backend).expect("failed to open repositories")));
+    // 予測では保存しないので、起動時に今月の計画だけ確定させる (失敗はログに残す)
+    use_hook(move || create_current_month_plans(&ctx.read()).ok());
 
     rsx! {
         // Global app resources
@@ -17,4 +17,9 @@ pub fn get_end_of_month(year: i32, month: u32) ->  Result<DateTime<Local>, anyho
         .single()
         .ok_or_else(|| anyhow::anyhow!("Invalid date: {}-{:02}-01", year, month))?;
     Ok(tmp + Months::new(1) - Days::new(1))
-}
\ No newline at end of file
+}
+
+// 月末を超える日は月末にする (31日締めの 2月など)
+pub fn get_clamped_day(year: i32, month: u32, day: u32) -> Result<u32, anyhow::Error> {
+    Ok(day.min(get_end_of_month(year, month)?.day()))
+}
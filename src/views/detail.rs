@@ -1,6 +1,11 @@
-use crate::components::detail::{IncomeList, OutcomeList, Summary};
+use crate::components::use_focus;
+use crate::components::detail::{
+    AccountList, CategoryList, IncomeList, LedgerFilterBar, OutcomeList, ReconciliationList, StatementList, Summary, TransferList,
+};
 use crate::finance::api::detail::*;
-use chrono::{Local, Datelike};
+use crate::finance::api::reconciliation::{get_reconciliation, promote_planned_entry, ReconciliationSchema};
+use crate::finance::api::setting::{get_current_year_month, get_default_account_id, get_period_label};
+use crate::finance::context::RepoContext;
 use rust_decimal::Decimal;
 use dioxus::prelude::*;
 use crate::util::get_prev_ym;
@@ -8,70 +13,138 @@ use crate::util::get_prev_ym;
 const DETAIL_CSS: Asset = asset!("/assets/styling/detail.css");
 
 #[component]
-pub fn Detail() -> Element {
-    let today = Local::now();
-    let mut year = use_signal(|| today.year());
-    let mut month = use_signal(|| today.month());
-    let mut incomes = use_signal(|| get_incomes(2025, 2));
-    let mut outcomes = use_signal(|| get_outcomes(2025, 2));
+pub fn Detail(focus: String) -> Element {
+    let ctx = use_context::<Signal<RepoContext>>();
+    use_focus(focus);
+    let (current_year, current_month) = get_current_year_month(&ctx.read());
+    let mut year = use_signal(|| current_year);
+    let mut month = use_signal(|| current_month);
+    let default_account_id = get_default_account_id(&ctx.read());
+    let filter = use_signal(LedgerFilter::default);
+    let mut accounts = use_signal(|| get_accounts(&ctx.read()));
+    let mut categories = use_signal(|| get_categories(&ctx.read()));
+    let mut incomes = use_signal(|| get_incomes(&ctx.read(), 2025, 2, &filter()));
+    let mut outcomes = use_signal(|| get_outcomes(&ctx.read(), 2025, 2, &filter()));
     let mut total_income = use_signal(|| Decimal::ZERO);
     let mut total_outcome = use_signal(|| Decimal::ZERO);
     let mut last_month_saving = use_signal(|| {
         let (last_year, last_month) = get_prev_ym((year(), month()));
-        get_saving(last_year, last_month).amount
+        get_total_saving(&ctx.read(), last_year, last_month)
     });
-    let mut current_month_saving = use_signal(|| get_saving(year(), month()).amount);
+    let mut current_month_saving = use_signal(|| get_total_saving(&ctx.read(), year(), month()));
+    let mut account_savings = use_signal(|| get_account_savings(&ctx.read(), year(), month()));
+    let mut account_error = use_signal(|| None::<String>);
+    let mut statements = use_signal(|| get_statements(&ctx.read(), year(), month()));
+    let mut transfers = use_signal(|| get_transfers(&ctx.read(), year(), month(), &filter()));
+    let mut transfer_error = use_signal(|| None::<String>);
+    let mut income_error = use_signal(|| None::<String>);
+    let mut outcome_error = use_signal(|| None::<String>);
+    let mut adjustment_error = use_signal(|| None::<String>);
+    let mut category_error = use_signal(|| None::<String>);
+    let mut budgets = use_signal(|| get_budget_statuses(&ctx.read(), year(), month()));
+    let mut budget_error = use_signal(|| None::<String>);
+    let mut reconciliation = use_signal(ReconciliationSchema::default);
+    let mut reconciliation_error = use_signal(|| None::<String>);
 
     use_effect(move || {
         total_income.set(incomes().iter().fold(Decimal::ZERO, |acc, x| acc + x.amount));
         total_outcome.set(outcomes().iter().fold(Decimal::ZERO, |acc, x| acc + x.amount));
+        budgets.set(get_budget_statuses(&ctx.read(), year(), month()));
         let (last_year, last_month) = get_prev_ym((year(), month()));
-        last_month_saving.set(get_saving(last_year, last_month).amount);
-        current_month_saving.set(get_saving(year(), month()).amount);
+        last_month_saving.set(get_total_saving(&ctx.read(), last_year, last_month));
+        current_month_saving.set(get_total_saving(&ctx.read(), year(), month()));
+        account_savings.set(get_account_savings(&ctx.read(), year(), month()));
+        statements.set(get_statements(&ctx.read(), year(), month()));
+        reconciliation.set(get_reconciliation(&ctx.read(), year(), month()));
     });
 
     use_effect(move || {
-        incomes.set(get_incomes(year(), month()));
-        outcomes.set(get_outcomes(year(), month()));
+        incomes.set(get_incomes(&ctx.read(), year(), month(), &filter()));
+        outcomes.set(get_outcomes(&ctx.read(), year(), month(), &filter()));
+        transfers.set(get_transfers(&ctx.read(), year(), month(), &filter()));
         let (last_year, last_month) = get_prev_ym((year(), month()));
-        last_month_saving.set(get_saving(last_year, last_month).amount);
-        current_month_saving.set(get_saving(year(), month()).amount);
+        last_month_saving.set(get_total_saving(&ctx.read(), last_year, last_month));
+        current_month_saving.set(get_total_saving(&ctx.read(), year(), month()));
+        account_savings.set(get_account_savings(&ctx.read(), year(), month()));
     });
 
-    let handle_add_income = move |(name, amount, date)| {
-        store_income(name, amount, date);
-        incomes.set(get_incomes(year(), month()));
+    let handle_add_income = move |input| {
+        income_error.set(store_income(&ctx.read(), input).err().map(|e| e.to_string()));
+        incomes.set(get_incomes(&ctx.read(), year(), month(), &filter()));
     };
 
-    let handle_edit_income = move |(id, name, amount, date)| {
-        update_income(id, name, amount, date);
-        incomes.set(get_incomes(year(), month()));
+    let handle_edit_income = move |(id, input)| {
+        income_error.set(update_income(&ctx.read(), id, input).err().map(|e| e.to_string()));
+        incomes.set(get_incomes(&ctx.read(), year(), month(), &filter()));
     };
 
     let handle_delete_income = move |id| {
-        delete_income(id);
-        incomes.set(get_incomes(year(), month()));
+        income_error.set(delete_income(&ctx.read(), id).err().map(|e| e.to_string()));
+        incomes.set(get_incomes(&ctx.read(), year(), month(), &filter()));
     };
 
-    let handle_add_outcome = move |(name, amount, date)| {
-        store_outcome(name, amount, date);
-        outcomes.set(get_outcomes(year(), month()));
+    let handle_add_outcome = move |input| {
+        outcome_error.set(store_outcome(&ctx.read(), input).err().map(|e| e.to_string()));
+        outcomes.set(get_outcomes(&ctx.read(), year(), month(), &filter()));
     };
 
-    let handle_edit_outcome = move |(id, name, amount, date)| {
-        update_outcome(id, name, amount, date);
-        outcomes.set(get_outcomes(year(), month()));
+    let handle_edit_outcome = move |(id, input)| {
+        outcome_error.set(update_outcome(&ctx.read(), id, input).err().map(|e| e.to_string()));
+        outcomes.set(get_outcomes(&ctx.read(), year(), month(), &filter()));
     };
 
     let handle_delete_outcome = move |id| {
-        delete_outcome(id);
-        outcomes.set(get_outcomes(year(), month()));
+        outcome_error.set(delete_outcome(&ctx.read(), id).err().map(|e| e.to_string()));
+        outcomes.set(get_outcomes(&ctx.read(), year(), month(), &filter()));
     };
 
-    let handle_create_adjustment = move |saving_input| {
-        create_adjustment(saving_input, year(), month());
-        incomes.set(get_incomes(year(), month()));
-        outcomes.set(get_outcomes(year(), month()));
+    // 振替は合計の残高を変えないので口座ごとの残高だけ更新する
+    let handle_add_transfer = move |(from_account_id, to_account_id, amount, date, memo)| {
+        transfer_error.set(
+            store_transfer(&ctx.read(), from_account_id, to_account_id, amount, date, memo).err().map(|e| e.to_string()),
+        );
+        transfers.set(get_transfers(&ctx.read(), year(), month(), &filter()));
+        account_savings.set(get_account_savings(&ctx.read(), year(), month()));
+    };
+
+    let handle_delete_transfer = move |id| {
+        transfer_error.set(delete_transfer(&ctx.read(), id).err().map(|e| e.to_string()));
+        transfers.set(get_transfers(&ctx.read(), year(), month(), &filter()));
+        account_savings.set(get_account_savings(&ctx.read(), year(), month()));
+    };
+
+    let handle_create_adjustment = move |(account_id, saving_input)| {
+        adjustment_error.set(create_adjustment(&ctx.read(), account_id, saving_input, year(), month()).err().map(|e| e.to_string()));
+        incomes.set(get_incomes(&ctx.read(), year(), month(), &filter()));
+        outcomes.set(get_outcomes(&ctx.read(), year(), month(), &filter()));
+    };
+
+    let handle_add_account = move |input| {
+        account_error.set(store_account(&ctx.read(), input).err().map(|e| e.to_string()));
+        accounts.set(get_accounts(&ctx.read()));
+        account_savings.set(get_account_savings(&ctx.read(), year(), month()));
+    };
+
+    let handle_add_category = move |(name, parent_id)| {
+        category_error.set(store_category(&ctx.read(), name, parent_id).err().map(|e| e.to_string()));
+        categories.set(get_categories(&ctx.read()));
+    };
+
+    let handle_add_budget = move |(target, amount, rollover)| {
+        budget_error.set(store_budget(&ctx.read(), target, amount, rollover, year(), month()).err().map(|e| e.to_string()));
+        budgets.set(get_budget_statuses(&ctx.read(), year(), month()));
+    };
+
+    let handle_delete_budget = move |id| {
+        budget_error.set(delete_budget(&ctx.read(), id).err().map(|e| e.to_string()));
+        budgets.set(get_budget_statuses(&ctx.read(), year(), month()));
+    };
+
+    // 記録したら明細の一覧が変わり、比較も更新される
+    let handle_promote = move |input| {
+        reconciliation_error.set(promote_planned_entry(&ctx.read(), input).err().map(|e| e.to_string()));
+        incomes.set(get_incomes(&ctx.read(), year(), month(), &filter()));
+        outcomes.set(get_outcomes(&ctx.read(), year(), month(), &filter()));
     };
 
     rsx! {
@@ -83,9 +156,9 @@ pub fn Detail() -> Element {
         input { 
             id: "year",
             type: "number",
-            max: "{today.year()}",
+            max: "{current_year}",
             value: year(),
-            onchange: move |e| year.set(e.value().parse().unwrap_or(Local::now().year()))
+            onchange: move |e| year.set(e.value().parse().unwrap_or(current_year))
         }
         label {
             for: "month",
@@ -95,12 +168,37 @@ pub fn Detail() -> Element {
             id: "month",
             type: "number",
             min: "1",
-            max: if year() < today.year() { "12" } else { today.month().to_string() },
+            max: if year() < current_year { "12" } else { current_month.to_string() },
             value: month(),
-            onchange: move |e| month.set(e.value().parse().unwrap_or(Local::now().month()))
+            onchange: move |e| month.set(e.value().parse().unwrap_or(current_month))
+        }
+        span { "{get_period_label(&ctx.read(), year(), month())}" }
+        LedgerFilterBar { accounts, categories, filter }
+        IncomeList { incomes, default_account_id, accounts, categories, handle_add: handle_add_income, handle_edit: handle_edit_income, handle_delete: handle_delete_income, error: income_error }
+        OutcomeList { outcomes, default_account_id, accounts, categories, handle_add: handle_add_outcome, handle_edit: handle_edit_outcome, handle_delete: handle_delete_outcome, error: outcome_error }
+        TransferList { transfers, default_account_id, accounts, handle_add: handle_add_transfer, handle_delete: handle_delete_transfer, error: transfer_error }
+        Summary {
+            total_income,
+            total_outcome,
+            last_month_saving,
+            current_month_saving,
+            budgets,
+            categories,
+            handle_add_budget,
+            handle_delete_budget,
+            budget_error,
+        }
+        AccountList {
+            accounts,
+            default_account_id,
+            savings: account_savings,
+            handle_add: handle_add_account,
+            handle_create_adjustment,
+            error: account_error,
+            adjustment_error,
         }
-        IncomeList { incomes, handle_add: handle_add_income, handle_edit: handle_edit_income, handle_delete: handle_delete_income }
-        OutcomeList { outcomes, handle_add: handle_add_outcome, handle_edit: handle_edit_outcome, handle_delete: handle_delete_outcome}
-        Summary { total_income, total_outcome, last_month_saving, current_month_saving, handle_create_adjustment }
+        StatementList { statements }
+        ReconciliationList { reconciliation, handle_promote, error: reconciliation_error }
+        CategoryList { categories, handle_add: handle_add_category, error: category_error }
     }
 }
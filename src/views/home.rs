@@ -1,23 +1,181 @@
-use chrono::{Local, Datelike};
 use dioxus::prelude::*;
-use crate::finance::api::plan::get_future_inspect;
+use crate::components::home::{BalanceChart, ForecastEntryList, ForecastRollupTable};
+use crate::finance::api::plan::{get_forecast, ForecastSchema};
+use crate::finance::api::scenario::{get_scenario_comparison, get_scenarios, ScenarioComparisonSchema};
+use crate::finance::api::setting::{get_current_year_month, get_forecast_horizon_months};
+use crate::finance::context::RepoContext;
 
 const HOME_CSS: Asset = asset!("/assets/styling/home.css");
 
+// 予測する期間の選択肢 (月数, 表示)
+const HORIZONS: [(u32, &str); 6] = [(3, "3か月"), (6, "半年"), (12, "1年"), (24, "2年"), (36, "3年"), (60, "5年")];
+// 一度に表示する日数
+const PAGE_SIZE: usize = 100;
+
 #[component]
 pub fn Home() -> Element {
-    let today = Local::now();
-    let mut future_inspect_results = use_signal(|| vec![]);
+    let ctx = use_context::<Signal<RepoContext>>();
+    let (current_year, current_month) = get_current_year_month(&ctx.read());
+    let mut start_month = use_signal(|| format!("{:04}-{:02}", current_year, current_month));
+    let mut horizon_months = use_signal(|| get_forecast_horizon_months(&ctx.read()));
+    let mut limit = use_signal(|| PAGE_SIZE);
+    // 予測期間全体の結果 (グラフと集計に使い、表はここから limit 日分を表示する)
+    let mut forecast = use_signal(ForecastSchema::default);
+    let scenarios = use_signal(|| get_scenarios(&ctx.read()));
+    // 比べるシナリオの ID
+    let mut selected_scenario_ids = use_signal(Vec::<u64>::new);
+    let mut scenario_comparison = use_signal(ScenarioComparisonSchema::default);
+
+    // 読めない月は今月から
+    let start_year_month = move || {
+        start_month()
+            .split_once('-')
+            .and_then(|(year, month)| Some((year.parse().ok()?, month.parse().ok()?)))
+            .filter(|(_, month)| (1..=12).contains(month))
+            .unwrap_or((current_year, current_month))
+    };
+
+    // 開始月か期間が変わったときだけ予測し直す
+    use_effect(move || {
+        let (year, month) = start_year_month();
+        forecast.set(get_forecast(&ctx.read(), year, month, horizon_months()));
+    });
 
+    // シナリオを選んだときだけ、計画と同じ期間で比べる
     use_effect(move || {
-        future_inspect_results.set(get_future_inspect(today.year(), today.month()));
+        let ids = selected_scenario_ids();
+        scenario_comparison.set(if ids.is_empty() {
+            ScenarioComparisonSchema::default()
+        } else {
+            get_scenario_comparison(&ctx.read(), &forecast.read(), &ids)
+        });
     });
 
+    // 設定の期間が選択肢になければ加える
+    let mut horizons: Vec<(u32, String)> = HORIZONS.iter().map(|(months, label)| (*months, label.to_string())).collect();
+    if !horizons.iter().any(|(months, _)| *months == horizon_months()) {
+        horizons.push((horizon_months(), format!("{}か月", horizon_months())));
+        horizons.sort();
+    }
+
+    let balance_alert = forecast.read().alert.clone();
+    let days = forecast.read().days.len();
+
     rsx! {
         document::Link { rel: "stylesheet", href: HOME_CSS }
         div {
             id: "future-inspect-result-list",
             h1 { "Home" }
+            if balance_alert.has_warning() {
+                div {
+                    class: "balance-alert",
+                    if let Some(deficit) = balance_alert.first_deficit {
+                        p {
+                            strong { "{deficit.date}" }
+                            " に残高がマイナス ({deficit.balance}) になります"
+                        }
+                    }
+                    if let Some(low) = balance_alert.first_below_threshold {
+                        p {
+                            strong { "{low.date}" }
+                            " に残高が下限の {balance_alert.threshold} を下回ります ({low.balance})"
+                        }
+                    }
+                    if let Some(minimum) = balance_alert.minimum {
+                        p { "期間中の最低残高は {minimum.date} の {minimum.balance} です" }
+                    }
+                }
+            }
+            label {
+                for: "start-month",
+                "開始月"
+            }
+            input {
+                id: "start-month",
+                type: "month",
+                value: "{start_month}",
+                onchange: move |e| {
+                    start_month.set(e.value());
+                    limit.set(PAGE_SIZE);
+                },
+            }
+            label {
+                for: "horizon",
+                "期間"
+            }
+            select {
+                id: "horizon",
+                onchange: move |e| {
+                    if let Ok(months) = e.value().parse() {
+                        horizon_months.set(months);
+                        limit.set(PAGE_SIZE);
+                    }
+                },
+                for (months, label) in horizons {
+                    option {
+                        value: "{months}",
+                        selected: horizon_months() == months,
+                        "{label}"
+                    }
+                }
+            }
+            BalanceChart { forecast, scenarios: scenario_comparison }
+            ForecastRollupTable { forecast }
+            if !scenarios().is_empty() {
+                div {
+                    id: "scenario-comparison",
+                    h2 { "シナリオの比較" }
+                    for scenario in scenarios() {
+                        label {
+                            key: "{scenario.id}",
+                            input {
+                                type: "checkbox",
+                                checked: selected_scenario_ids().contains(&scenario.id),
+                                onchange: move |e| {
+                                    let mut ids = selected_scenario_ids.write();
+                                    ids.retain(|id| *id != scenario.id);
+                                    if e.checked() {
+                                        ids.push(scenario.id);
+                                    }
+                                },
+                            }
+                            "{scenario.name}"
+                        }
+                    }
+                    if !scenario_comparison().rows.is_empty() {
+                        table {
+                            thead {
+                                tr {
+                                    th { "月末" }
+                                    for name in scenario_comparison().names {
+                                        th { "{name}" }
+                                    }
+                                }
+                            }
+                            tbody {
+                                for row in scenario_comparison().rows {
+                                    tr {
+                                        td { "{row.month}" }
+                                        for (balance, is_low_balance) in row.balances {
+                                            td {
+                                                class: if is_low_balance { "amount low-balance" } else { "amount" },
+                                                "{balance}"
+                                            }
+                                        }
+                                    }
+                                }
+                                tr {
+                                    class: "minimum",
+                                    td { "最低" }
+                                    for minimum in scenario_comparison().minimums {
+                                        td { class: "amount", "{minimum}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
             table {
                 colgroup {
                     col { style: "width: 15%;" }
@@ -34,31 +192,38 @@ pub fn Home() -> Element {
                     }
                 }
                 tbody {
-                    for result in future_inspect_results() {
+                    for result in forecast.read().days.iter().take(limit()).cloned() {
                         tr {
                             td {
                                 style: "text-align: center",
-                                "{result.date}"
+                                "{result.date_label}"
                             }
                             td {
-                                "{result.incomes}"
+                                ForecastEntryList { entries: result.incomes, total: result.total_income }
                             }
                             td {
-                                "{result.outcomes}"
+                                ForecastEntryList { entries: result.outcomes, total: result.total_outcome }
                             }
                             td {
                                 class: "amount",
-                                style: if result.amount.is_sign_positive() {
-                                    "background-color: lightgreen"
-                                } else {
+                                style: if result.is_low_balance {
                                     "background-color: red"
+                                } else {
+                                    "background-color: lightgreen"
                                 },
-                                "{result.amount}"
+                                "{result.balance}"
                             }
                         }
                     }
                 }
             }
+            // 表示しきれなかった日があれば続きを表示する
+            if days > limit() {
+                button {
+                    onclick: move |_| limit += PAGE_SIZE,
+                    "さらに表示"
+                }
+            }
         }
     }
 }
@@ -5,4 +5,6 @@ mod detail;
 pub use detail::Detail;
 
 mod plan;
-pub use plan::Plan;
\ No newline at end of file
+pub use plan::Plan;
+mod setting;
+pub use setting::Settings;
@@ -1,20 +1,30 @@
-use chrono::{Datelike, Local};
 use dioxus::prelude::*;
 
 use crate::util::get_next_ym;
+use crate::finance::context::RepoContext;
+use crate::finance::api::setting::get_current_year_month;
+use crate::finance::api::detail::get_accounts;
 
-use crate::components::plan::{CombinedList, PartTimeJobIncomes};
+use crate::components::use_focus;
+use crate::components::plan::{CombinedList, HolidayList, MonthlyTemplates, PartTimeJobIncomes, ScenarioList};
 use crate::finance::api::plan::{
-    get_incomes, get_part_time_job_incomes, update_part_time_job_income,
-    get_monthly_outcomes,
+    get_incomes, get_part_time_job_incomes, update_part_time_job_income, store_part_time_job,
+    get_monthly_income_templates, store_monthly_income_template,
+    get_monthly_outcome_templates, store_monthly_outcome_template,
+    get_monthly_outcomes, get_holidays, store_holiday, delete_holiday,
+};
+use crate::finance::api::scenario::{
+    get_scenarios, store_scenario, delete_scenario, store_scenario_change, delete_scenario_change,
 };
 
 const PLAN_CSS: Asset = asset!("/assets/styling/plan.css");
 
 #[component]
-pub fn Plan() -> Element {
-    let today = Local::now();
-    let (next_year, next_month) = get_next_ym((today.year(), today.month()));
+pub fn Plan(focus: String) -> Element {
+    let ctx = use_context::<Signal<RepoContext>>();
+    use_focus(focus);
+    let (current_year, current_month) = get_current_year_month(&ctx.read());
+    let (next_year, next_month) = get_next_ym((current_year, current_month));
     let (next_next_year, next_next_month) = get_next_ym((next_year, next_month));
     let mut year = use_signal(|| next_year);
     let mut month = use_signal(|| next_month);
@@ -25,13 +35,27 @@ pub fn Plan() -> Element {
     let mut next_month_incomes = use_signal(|| vec![]);
     let mut outcomes = use_signal(|| vec![]);
     let mut next_month_outcomes = use_signal(|| vec![]);
+    let mut part_time_job_error = use_signal(|| None::<String>);
+    let mut monthly_income_templates = use_signal(Vec::new);
+    let mut monthly_income_error = use_signal(|| None::<String>);
+    let mut monthly_outcome_templates = use_signal(Vec::new);
+    let mut monthly_outcome_error = use_signal(|| None::<String>);
+    let mut holidays = use_signal(Vec::new);
+    let mut holiday_error = use_signal(|| None::<String>);
+    let mut scenarios = use_signal(Vec::new);
+    let mut scenario_error = use_signal(|| None::<String>);
+    let accounts = use_signal(|| get_accounts(&ctx.read()));
 
     use_effect(move || {
-        part_time_job_incomes.set(get_part_time_job_incomes(year(), month()));
-        incomes.set(get_incomes(year(), month()));
-        outcomes.set(get_monthly_outcomes(year(), month()));
-        next_month_incomes.set(get_incomes(next_year(), next_month()));
-        next_month_outcomes.set(get_monthly_outcomes(next_year(), next_month()));
+        part_time_job_incomes.set(get_part_time_job_incomes(&ctx.read(), year(), month()));
+        incomes.set(get_incomes(&ctx.read(), year(), month()));
+        outcomes.set(get_monthly_outcomes(&ctx.read(), year(), month()));
+        next_month_incomes.set(get_incomes(&ctx.read(), next_year(), next_month()));
+        next_month_outcomes.set(get_monthly_outcomes(&ctx.read(), next_year(), next_month()));
+        monthly_income_templates.set(get_monthly_income_templates(&ctx.read(), year(), month()));
+        monthly_outcome_templates.set(get_monthly_outcome_templates(&ctx.read(), year(), month()));
+        holidays.set(get_holidays(&ctx.read()));
+        scenarios.set(get_scenarios(&ctx.read()));
     });
 
     let mut handle_change_year_month = move |y: i32, m: u32| {
@@ -41,13 +65,85 @@ pub fn Plan() -> Element {
         next_month.set(get_next_ym((y, m)).1);
     };
 
-    let handle_edit_part_time_job = move |(id, name, hourly_wage, hour, payment_date)| {
-        update_part_time_job_income(id, name, hourly_wage, hour, payment_date);
-        part_time_job_incomes.set(get_part_time_job_incomes(year(), month()));
-        incomes.set(get_incomes(year(), month()));
-        outcomes.set(get_monthly_outcomes(year(), month()));
-        next_month_incomes.set(get_incomes(next_year(), next_month()));
-        next_month_outcomes.set(get_monthly_outcomes(next_year(), next_month()));
+    let handle_edit_part_time_job = move |input| {
+        part_time_job_error.set(
+            update_part_time_job_income(&ctx.read(), input)
+                .err()
+                .map(|e| e.to_string()),
+        );
+        part_time_job_incomes.set(get_part_time_job_incomes(&ctx.read(), year(), month()));
+        incomes.set(get_incomes(&ctx.read(), year(), month()));
+        outcomes.set(get_monthly_outcomes(&ctx.read(), year(), month()));
+        next_month_incomes.set(get_incomes(&ctx.read(), next_year(), next_month()));
+        next_month_outcomes.set(get_monthly_outcomes(&ctx.read(), next_year(), next_month()));
+    };
+
+    let handle_add_part_time_job = move |input| {
+        part_time_job_error.set(
+            store_part_time_job(&ctx.read(), year(), month(), input)
+                .err()
+                .map(|e| e.to_string()),
+        );
+        part_time_job_incomes.set(get_part_time_job_incomes(&ctx.read(), year(), month()));
+        incomes.set(get_incomes(&ctx.read(), year(), month()));
+        next_month_incomes.set(get_incomes(&ctx.read(), next_year(), next_month()));
+    };
+
+    let handle_add_monthly_income = move |input| {
+        monthly_income_error.set(
+            store_monthly_income_template(&ctx.read(), input)
+                .err()
+                .map(|e| e.to_string()),
+        );
+        monthly_income_templates.set(get_monthly_income_templates(&ctx.read(), year(), month()));
+        incomes.set(get_incomes(&ctx.read(), year(), month()));
+        next_month_incomes.set(get_incomes(&ctx.read(), next_year(), next_month()));
+    };
+
+    let handle_add_monthly_outcome = move |input| {
+        monthly_outcome_error.set(
+            store_monthly_outcome_template(&ctx.read(), input)
+                .err()
+                .map(|e| e.to_string()),
+        );
+        monthly_outcome_templates.set(get_monthly_outcome_templates(&ctx.read(), year(), month()));
+        outcomes.set(get_monthly_outcomes(&ctx.read(), year(), month()));
+        next_month_outcomes.set(get_monthly_outcomes(&ctx.read(), next_year(), next_month()));
+    };
+
+    // 休日が変わると支払日もずれるので、まだ作られていない月の分に反映される
+    let handle_add_holiday = move |(date, name)| {
+        holiday_error.set(store_holiday(&ctx.read(), date, name).err().map(|e| e.to_string()));
+        holidays.set(get_holidays(&ctx.read()));
+    };
+
+    let handle_delete_holiday = move |id| {
+        holiday_error.set(delete_holiday(&ctx.read(), id).err().map(|e| e.to_string()));
+        holidays.set(get_holidays(&ctx.read()));
+    };
+
+    let handle_add_scenario = move |name| {
+        scenario_error.set(store_scenario(&ctx.read(), name).err().map(|e| e.to_string()));
+        scenarios.set(get_scenarios(&ctx.read()));
+    };
+
+    let handle_delete_scenario = move |id| {
+        scenario_error.set(delete_scenario(&ctx.read(), id).err().map(|e| e.to_string()));
+        scenarios.set(get_scenarios(&ctx.read()));
+    };
+
+    let handle_add_scenario_change = move |(scenario_id, input)| {
+        scenario_error.set(
+            store_scenario_change(&ctx.read(), scenario_id, input)
+                .err()
+                .map(|e| e.to_string()),
+        );
+        scenarios.set(get_scenarios(&ctx.read()));
+    };
+
+    let handle_delete_scenario_change = move |id| {
+        scenario_error.set(delete_scenario_change(&ctx.read(), id).err().map(|e| e.to_string()));
+        scenarios.set(get_scenarios(&ctx.read()));
     };
 
     rsx! {
@@ -64,9 +160,9 @@ pub fn Plan() -> Element {
         input {
             id: "year",
             type: "number",
-            min: "{today.year()}",
+            min: "{current_year}",
             value: year(),
-            onchange: move |e| handle_change_year_month(e.value().parse().unwrap_or(Local::now().year()), month())
+            onchange: move |e| handle_change_year_month(e.value().parse().unwrap_or(current_year), month())
         }
         label {
             for: "month",
@@ -75,12 +171,48 @@ pub fn Plan() -> Element {
         input {
             id: "month",
             type: "number",
-            min: if year() > today.year() { "1" } else { today.month().to_string() },
+            min: if year() > current_year { "1" } else { current_month.to_string() },
             max: "12",
             value: month(),
             onchange: move |e| handle_change_year_month(year(), e.value().parse().unwrap_or(1))
         }
-        PartTimeJobIncomes { year, month, part_time_job_incomes, handle_edit_part_time_job }
+        PartTimeJobIncomes {
+            year,
+            month,
+            part_time_job_incomes,
+            accounts,
+            handle_edit_part_time_job,
+            handle_add_part_time_job,
+            error: part_time_job_error,
+        }
+        MonthlyTemplates {
+            title: "毎月の収入",
+            anchor_prefix: "monthly-income",
+            templates: monthly_income_templates,
+            accounts,
+            handle_add: handle_add_monthly_income,
+            error: monthly_income_error,
+        }
+        MonthlyTemplates {
+            title: "毎月の支出",
+            anchor_prefix: "monthly-outcome",
+            templates: monthly_outcome_templates,
+            accounts,
+            handle_add: handle_add_monthly_outcome,
+            error: monthly_outcome_error,
+        }
+        HolidayList { holidays, handle_add: handle_add_holiday, handle_delete: handle_delete_holiday, error: holiday_error }
+        ScenarioList {
+            scenarios,
+            income_templates: monthly_income_templates,
+            outcome_templates: monthly_outcome_templates,
+            part_time_job_incomes,
+            handle_add: handle_add_scenario,
+            handle_delete: handle_delete_scenario,
+            handle_add_change: handle_add_scenario_change,
+            handle_delete_change: handle_delete_scenario_change,
+            error: scenario_error,
+        }
         CombinedList { year, month, incomes, outcomes }
         CombinedList { year: next_year, month: next_month, incomes: next_month_incomes, outcomes: next_month_outcomes }
     }
@@ -0,0 +1,148 @@
+use dioxus::prelude::*;
+
+use crate::finance::api::detail::get_accounts;
+use crate::finance::api::setting::{get_settings_input, store_settings, Currency, Locale, SettingsInput};
+use crate::finance::context::RepoContext;
+
+const SETTING_CSS: Asset = asset!("/assets/styling/setting.css");
+
+#[component]
+pub fn Settings() -> Element {
+    let ctx = use_context::<Signal<RepoContext>>();
+    let accounts = use_signal(|| get_accounts(&ctx.read()));
+    let mut input = use_signal(|| get_settings_input(&ctx.read()));
+    let mut error = use_signal(|| None::<String>);
+    let mut saved = use_signal(|| false);
+
+    let handle_save = move |_| {
+        let result = store_settings(&ctx.read(), input());
+        saved.set(result.is_ok());
+        error.set(result.err().map(|e| e.to_string()));
+        // 保存できなかった場合は入力を残す
+        if error().is_none() {
+            input.set(get_settings_input(&ctx.read()));
+        }
+    };
+
+    // 入力が変わったら保存済みの表示を消す
+    let mut update = move |f: &dyn Fn(&mut SettingsInput)| {
+        f(&mut input.write());
+        saved.set(false);
+    };
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: SETTING_CSS }
+        div {
+            id: "settings",
+            h1 { "設定" }
+            table {
+                tbody {
+                    tr {
+                        th { "通貨" }
+                        td {
+                            select {
+                                onchange: move |e| {
+                                    if let Some(currency) = Currency::ALL.into_iter().find(|c| c.label() == e.value()) {
+                                        update(&|input| input.currency = currency);
+                                    }
+                                },
+                                for currency in Currency::ALL {
+                                    option {
+                                        value: currency.label(),
+                                        selected: input().currency == currency,
+                                        "{currency.label()}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tr {
+                        th { "月の開始日" }
+                        td {
+                            input {
+                                type: "number",
+                                min: "1",
+                                max: "31",
+                                value: "{input().period_start_day}",
+                                onchange: move |e| update(&|input| input.period_start_day = e.value()),
+                            }
+                            "日"
+                        }
+                    }
+                    tr {
+                        th { "予測する期間" }
+                        td {
+                            input {
+                                type: "number",
+                                min: "1",
+                                max: "120",
+                                value: "{input().forecast_horizon_months}",
+                                onchange: move |e| update(&|input| input.forecast_horizon_months = e.value()),
+                            }
+                            "か月分"
+                        }
+                    }
+                    tr {
+                        th { "よく使う口座" }
+                        td {
+                            select {
+                                onchange: move |e| {
+                                    if let Ok(id) = e.value().parse() {
+                                        update(&|input| input.default_account_id = id);
+                                    }
+                                },
+                                for account in accounts() {
+                                    option {
+                                        value: "{account.id}",
+                                        selected: input().default_account_id == account.id,
+                                        "{account.name}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tr {
+                        th { "日付の表示" }
+                        td {
+                            select {
+                                onchange: move |e| {
+                                    if let Some(locale) = Locale::ALL.into_iter().find(|l| l.label() == e.value()) {
+                                        update(&|input| input.locale = locale);
+                                    }
+                                },
+                                for locale in Locale::ALL {
+                                    option {
+                                        value: locale.label(),
+                                        selected: input().locale == locale,
+                                        "{locale.label()}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tr {
+                        th { "残高の下限" }
+                        td {
+                            input {
+                                type: "text",
+                                class: "amount",
+                                value: "{input().low_balance_threshold}",
+                                onchange: move |e| update(&|input| input.low_balance_threshold = e.value()),
+                            }
+                        }
+                    }
+                }
+            }
+            button {
+                onclick: handle_save,
+                "保存"
+            }
+            if saved() {
+                span { "保存しました" }
+            }
+            if let Some(error) = error() {
+                p { class: "error", "{error}" }
+            }
+        }
+    }
+}